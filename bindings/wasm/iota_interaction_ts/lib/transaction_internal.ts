@@ -1,7 +1,7 @@
 // Copyright 2021-2025 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-import { IotaObjectRef, IotaTransactionBlockResponse, TransactionEffects } from "@iota/iota-sdk/client";
+import { GasCostSummary, IotaObjectRef, IotaTransactionBlockResponse, TransactionEffects } from "@iota/iota-sdk/client";
 import { IotaEvent } from "@iota/iota-sdk/src/client/types/generated";
 import { TransactionDataBuilder } from "@iota/iota-sdk/transactions";
 import { TransactionSigner } from "~iota_interaction_ts";
@@ -42,18 +42,72 @@ export interface Transaction<Output> {
     applyWithEvents(effects: TransactionEffects, events: IotaEvent[], client: CoreClientReadOnly): Promise<Output>;
 }
 
+export interface DryRunOutput<T extends Transaction<unknown>> {
+    effects: TransactionEffects;
+    events: IotaEvent[];
+    gasCostSummary: GasCostSummary;
+    /// `undefined` if the simulated effects couldn't be applied, see `error`.
+    output?: Awaited<ReturnType<T["apply"]>>;
+    error?: string;
+}
+
+// A portable, serializable transaction waiting to be signed and/or executed.
+// Envelopes are created through `TransactionBuilder.toEnvelope` or decoded through the
+// static methods `TransactionEnvelope.fromBcs`, `TransactionEnvelope.fromBase64` and `TransactionEnvelope.fromJson`.
+export interface TransactionEnvelope {
+    get version(): number;
+    /// The BCS encoded transaction data to sign.
+    get txData(): Uint8Array;
+    /// The base64 encoded signatures collected so far.
+    get signatures(): string[];
+    get requiredSigners(): string[];
+    get summary(): string;
+    missingSigners(): string[];
+    isComplete(): boolean;
+    addSignature(signature: string): TransactionEnvelope;
+    sign(signer: TransactionSigner): Promise<TransactionEnvelope>;
+    execute<T extends Transaction<unknown>>(tx: T, client: CoreClientReadOnly): Promise<TransactionOutput<T>>;
+    toBcs(): Uint8Array;
+    toBase64(): string;
+    toJson(): string;
+}
+
+// Callbacks run at the different stages of a transaction's life cycle, e.g. for audit logging or policy checks.
+// All callbacks are optional. Throwing an error from `beforeBuild` or `beforeSign` vetoes the transaction,
+// while errors thrown by `afterExecute` and `onApplyError` are ignored.
+export interface TransactionHook {
+    /// Called with the BCS encoded programmable transaction, before the rest of the transaction is built.
+    beforeBuild?(txType: string, programmableTx: Uint8Array): Promise<void>;
+    /// Called with the BCS encoded transaction data, before it gets signed.
+    beforeSign?(txData: Uint8Array): Promise<void>;
+    afterExecute?(response: IotaTransactionBlockResponse): Promise<void>;
+    onApplyError?(error: string): Promise<void>;
+}
+
 export type SponsorFn = (tx_data: TransactionDataBuilder) => Promise<string>;
 
+// Builders can be persisted through `toState` and resumed through the static method
+// `TransactionBuilder.fromState(tx, state)`.
 export interface TransactionBuilder<T extends Transaction<unknown>> {
     get transaction(): Readonly<T>;
+    /// Builds the programmable transaction and resolves the expiration, so that they are part of the state.
+    prepare(client: CoreClientReadOnly): Promise<TransactionBuilder<T>>;
+    /// The JSON encoded state of this builder.
+    toState(): string;
     withGasPrice(price: bigint): TransactionBuilder<T>;
     withGasBudget(budget: bigint): TransactionBuilder<T>;
+    withExpiration(epoch: bigint): TransactionBuilder<T>;
+    withExpirationInEpochs(epochs: bigint): TransactionBuilder<T>;
+    withRetryPolicy(maxAttempts: number): TransactionBuilder<T>;
+    withHook(hook: TransactionHook): TransactionBuilder<T>;
     withGasOwner(owner: string): TransactionBuilder<T>;
     withGasPayment(payment: IotaObjectRef[]): TransactionBuilder<T>;
     withSender(sender: String): TransactionBuilder<T>;
     withSignature<S extends TransactionSigner>(client: CoreClient<S>): TransactionBuilder<T>;
     withSponsor(client: CoreClientReadOnly, sponsorFn: SponsorFn): Promise<TransactionBuilder<T>>;
     build<S extends TransactionSigner>(client: CoreClient<S>): Promise<[Uint8Array, string[], T]>;
+    dryRun(client: CoreClientReadOnly): Promise<DryRunOutput<T>>;
+    toEnvelope<S extends TransactionSigner>(client: CoreClient<S>): Promise<TransactionEnvelope>;
     buildAndExecute<S extends TransactionSigner>(client: CoreClient<S>): Promise<TransactionOutput<T>>;
     executeWithGasStation<S extends TransactionSigner>(
         client: CoreClient<S>,
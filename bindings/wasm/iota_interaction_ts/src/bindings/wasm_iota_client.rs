@@ -3,25 +3,28 @@
 
 use iota_interaction::error::{Error as IotaRpcError, IotaRpcResult};
 use iota_interaction::generated_types::{
-  DevInspectTransactionBlockParams, ExecuteTransactionBlockParams, GetCoinsParams, GetDynamicFieldObjectParams,
-  GetDynamicFieldObjectV2Params, GetObjectParams, GetOwnedObjectsParams, GetTransactionBlockParams, QueryEventsParams,
-  SortOrder, WaitForTransactionParams,
+  DevInspectTransactionBlockParams, DryRunTransactionBlockParams, ExecuteTransactionBlockParams, GetCoinsParams,
+  GetDynamicFieldObjectParams, GetDynamicFieldObjectV2Params, GetDynamicFieldsParams, GetObjectParams,
+  GetOwnedObjectsParams, GetTransactionBlockParams, MultiGetObjectsParams, QueryEventsParams, SortOrder,
+  TryGetPastObjectParams, TryMultiGetPastObjectsParams, WaitForTransactionParams,
 };
 use iota_interaction::rpc_types::{
-  CoinPage, DevInspectArgs, DevInspectResults, EventFilter, EventPage, IotaObjectDataOptions, IotaObjectResponse,
-  IotaObjectResponseQuery, IotaPastObjectResponse, IotaTransactionBlockResponseOptions, ObjectsPage,
+  CoinPage, DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, DynamicFieldPage, EventFilter,
+  EventPage, IotaGetPastObjectRequest, IotaObjectDataOptions, IotaObjectResponse, IotaObjectResponseQuery,
+  IotaPastObjectResponse, IotaTransactionBlockResponseOptions, ObjectsPage,
 };
 use iota_interaction::types::base_types::{IotaAddress, SequenceNumber};
-use iota_interaction::types::crypto::Signature;
 use iota_interaction::types::digests::TransactionDigest;
 use iota_interaction::types::dynamic_field::DynamicFieldName;
 use iota_interaction::types::event::EventID;
 use iota_interaction::types::iota_serde::BigInt;
 use iota_interaction::types::quorum_driver_types::ExecuteTransactionRequestType;
+use iota_interaction::types::signature::GenericSignature;
 use iota_interaction::types::transaction::{TransactionData, TransactionKind};
 use iota_sdk_types::ObjectId;
-use js_sys::Promise;
-use serde::Serialize;
+use js_sys::{Promise, Reflect};
+use jsonrpsee::core::ClientError;
+use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 
@@ -33,10 +36,11 @@ use super::{
   WasmDryRunTransactionBlockParams, WasmWaitForTransactionParams,
 };
 use crate::bindings::{
-  PromiseIotaObjectResponse, PromiseObjectRead, PromisePaginatedCoins, PromisePaginatedEvents,
-  PromisePaginatedObjectsResponse, WasmGetCoinsParams, WasmGetDynamicFieldObjectParams,
-  WasmGetDynamicFieldObjectV2Params, WasmGetObjectParams, WasmGetOwnedObjectsParams, WasmGetTransactionBlockParams,
-  WasmQueryEventsParams, WasmTryGetPastObjectParams,
+  PromiseDynamicFieldPage, PromiseIotaObjectResponse, PromiseIotaObjectResponseArray, PromiseObjectRead,
+  PromiseObjectReadArray, PromisePaginatedCoins, PromisePaginatedEvents, PromisePaginatedObjectsResponse,
+  WasmGetCoinsParams, WasmGetDynamicFieldObjectParams, WasmGetDynamicFieldObjectV2Params, WasmGetDynamicFieldsParams,
+  WasmGetObjectParams, WasmGetOwnedObjectsParams, WasmGetTransactionBlockParams, WasmMultiGetObjectsParams,
+  WasmQueryEventsParams, WasmTryGetPastObjectParams, WasmTryMultiGetPastObjectsParams,
 };
 use crate::common::macros::console_log;
 use crate::common::types::PromiseString;
@@ -85,9 +89,15 @@ extern "C" {
     input: &WasmGetDynamicFieldObjectV2Params,
   ) -> PromiseIotaObjectResponse;
 
+  #[wasm_bindgen(method, js_name = getDynamicFields)]
+  pub fn get_dynamic_fields(this: &WasmIotaClient, input: &WasmGetDynamicFieldsParams) -> PromiseDynamicFieldPage;
+
   #[wasm_bindgen(method, js_name = getObject)]
   pub fn get_object(this: &WasmIotaClient, input: &WasmGetObjectParams) -> PromiseIotaObjectResponse;
 
+  #[wasm_bindgen(method, js_name = multiGetObjects)]
+  pub fn multi_get_objects(this: &WasmIotaClient, input: &WasmMultiGetObjectsParams) -> PromiseIotaObjectResponseArray;
+
   #[wasm_bindgen(method, js_name = getOwnedObjects)]
   pub fn get_owned_objects(this: &WasmIotaClient, input: &WasmGetOwnedObjectsParams)
     -> PromisePaginatedObjectsResponse;
@@ -101,9 +111,21 @@ extern "C" {
   #[wasm_bindgen(method, js_name = getReferenceGasPrice)]
   pub fn get_reference_gas_price(this: &WasmIotaClient) -> PromiseBigint;
 
+  #[wasm_bindgen(method, js_name = getLatestIotaSystemState)]
+  pub fn get_latest_iota_system_state(this: &WasmIotaClient) -> Promise;
+
+  #[wasm_bindgen(method, js_name = getProtocolConfig)]
+  pub fn get_protocol_config(this: &WasmIotaClient) -> Promise;
+
   #[wasm_bindgen(method, js_name = tryGetPastObject)]
   pub fn try_get_past_object(this: &WasmIotaClient, input: &WasmTryGetPastObjectParams) -> PromiseObjectRead;
 
+  #[wasm_bindgen(method, js_name = tryMultiGetPastObjects)]
+  pub fn try_multi_get_past_objects(
+    this: &WasmIotaClient,
+    input: &WasmTryMultiGetPastObjectsParams,
+  ) -> PromiseObjectReadArray;
+
   #[wasm_bindgen(method, js_name = queryEvents)]
   pub fn query_events(this: &WasmIotaClient, input: &WasmQueryEventsParams) -> PromisePaginatedEvents;
 
@@ -141,7 +163,7 @@ impl ManagedWasmIotaClient {
   pub async fn execute_transaction_block(
     &self,
     tx_data: TransactionData,
-    signatures: Vec<Signature>,
+    signatures: Vec<GenericSignature>,
     options: Option<IotaTransactionBlockResponseOptions>,
     request_type: Option<ExecuteTransactionRequestType>,
   ) -> IotaRpcResult<WasmIotaTransactionBlockResponseWrapper> {
@@ -158,7 +180,7 @@ impl ManagedWasmIotaClient {
     let promise: Promise = Promise::resolve(&WasmIotaClient::execute_transaction_block(&self.0, &wasm_params));
     let result: JsValue = JsFuture::from(promise).await.map_err(|e| {
       console_log!("Error executing JsFuture::from(promise): {:?}", e);
-      IotaRpcError::FfiError(format!("{e:?}"))
+      rpc_error_from_js(e)
     })?;
     Ok(WasmIotaTransactionBlockResponseWrapper::new(result.into()))
   }
@@ -196,6 +218,33 @@ impl ManagedWasmIotaClient {
     Ok(result.into_serde()?)
   }
 
+  pub async fn dry_run_transaction_block(
+    &self,
+    tx_data: TransactionData,
+  ) -> IotaRpcResult<DryRunTransactionBlockResponse> {
+    let params = DryRunTransactionBlockParams::new(&tx_data);
+    let wasm_params: WasmDryRunTransactionBlockParams = params
+      .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+      .map_err(|e| {
+        IotaRpcError::FfiError(format!(
+          "failed to convert DryRunTransactionBlockParams to JS value: {e}"
+        ))
+      })?
+      .into();
+
+    let promise: Promise = Promise::resolve(&WasmIotaClient::dry_run_transaction_block(&self.0, &wasm_params));
+    let result: JsValue = JsFuture::from(promise).await.map_err(|e| {
+      console_log!(
+        "Error executing JsFuture::from(promise) for dryRunTransactionBlock: {:?}",
+        e
+      );
+      IotaRpcError::FfiError(format!("dryRunTransactionBlock JS Promise rejected: {e:?}"))
+    })?;
+
+    #[allow(deprecated)] // will be refactored
+    Ok(result.into_serde()?)
+  }
+
   /**
    * Return the dynamic field object information for a specified object
    */
@@ -258,6 +307,39 @@ impl ManagedWasmIotaClient {
     Ok(result.into_serde()?)
   }
 
+  /**
+   * Return the list of dynamic field objects owned by an object
+   */
+  pub async fn get_dynamic_fields(
+    &self,
+    parent_object_id: ObjectId,
+    cursor: Option<ObjectId>,
+    limit: Option<usize>,
+  ) -> IotaRpcResult<DynamicFieldPage> {
+    let params: WasmGetDynamicFieldsParams = serde_wasm_bindgen::to_value(&GetDynamicFieldsParams::new(
+      parent_object_id.to_string(),
+      cursor.map(|v| v.to_string()),
+      limit,
+    ))
+    .map_err(|e| {
+      console_log!(
+        "Error executing serde_wasm_bindgen::to_value(WasmGetDynamicFieldsParams): {:?}",
+        e
+      );
+      IotaRpcError::FfiError(format!("{e:?}"))
+    })?
+    .into();
+
+    let promise: Promise = Promise::resolve(&WasmIotaClient::get_dynamic_fields(&self.0, &params));
+    let result: JsValue = JsFuture::from(promise).await.map_err(|e| {
+      console_log!("Error executing JsFuture::from(promise): {:?}", e);
+      IotaRpcError::FfiError(format!("{e:?}"))
+    })?;
+
+    #[allow(deprecated)] // will be refactored
+    Ok(result.into_serde()?)
+  }
+
   pub async fn get_object_with_options(
     &self,
     object_id: ObjectId,
@@ -284,6 +366,34 @@ impl ManagedWasmIotaClient {
     Ok(result.into_serde()?)
   }
 
+  pub async fn multi_get_object_with_options(
+    &self,
+    object_ids: Vec<ObjectId>,
+    options: IotaObjectDataOptions,
+  ) -> IotaRpcResult<Vec<IotaObjectResponse>> {
+    let params: WasmMultiGetObjectsParams = serde_wasm_bindgen::to_value(&MultiGetObjectsParams::new(
+      object_ids.iter().map(ToString::to_string).collect(),
+      Some(options),
+    ))
+    .map_err(|e| {
+      console_log!(
+        "Error executing serde_wasm_bindgen::to_value(MultiGetObjectsParams): {:?}",
+        e
+      );
+      IotaRpcError::FfiError(format!("{e:?}"))
+    })?
+    .into();
+
+    let promise: Promise = Promise::resolve(&WasmIotaClient::multi_get_objects(&self.0, &params));
+    let result: JsValue = JsFuture::from(promise).await.map_err(|e| {
+      console_log!("Error executing JsFuture::from(promise): {:?}", e);
+      IotaRpcError::FfiError(format!("{e:?}"))
+    })?;
+
+    #[allow(deprecated)] // will be refactored
+    Ok(result.into_serde()?)
+  }
+
   pub async fn get_owned_objects(
     &self,
     address: IotaAddress,
@@ -355,35 +465,101 @@ impl ManagedWasmIotaClient {
       .map_err(|e| IotaRpcError::FfiError(format!("failed to deserialize gas price from JS value: {e}")))
   }
 
+  pub async fn get_current_epoch(&self) -> IotaRpcResult<u64> {
+    /// The only part of the JS `IotaSystemStateSummary` we are interested in.
+    #[derive(Deserialize)]
+    struct SystemStateEpoch {
+      epoch: String,
+    }
+
+    let promise: Promise = Promise::resolve(&WasmIotaClient::get_latest_iota_system_state(&self.0));
+    let result: JsValue = JsFuture::from(promise).await.map_err(|e| {
+      console_log!("Error executing JsFuture::from(promise): {:?}", e);
+      IotaRpcError::FfiError(format!("{e:?}"))
+    })?;
+
+    let SystemStateEpoch { epoch } = serde_wasm_bindgen::from_value(result)
+      .map_err(|e| IotaRpcError::FfiError(format!("failed to deserialize system state from JS value: {e}")))?;
+    epoch
+      .parse()
+      .map_err(|e| IotaRpcError::FfiError(format!("invalid epoch {epoch}: {e}")))
+  }
+
+  pub async fn get_protocol_version(&self) -> IotaRpcResult<u64> {
+    /// The only part of the JS `ProtocolConfig` we are interested in.
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ProtocolConfigVersion {
+      protocol_version: String,
+    }
+
+    let promise: Promise = Promise::resolve(&WasmIotaClient::get_protocol_config(&self.0));
+    let result: JsValue = JsFuture::from(promise).await.map_err(|e| {
+      console_log!("Error executing JsFuture::from(promise): {:?}", e);
+      IotaRpcError::FfiError(format!("{e:?}"))
+    })?;
+
+    let ProtocolConfigVersion { protocol_version } = serde_wasm_bindgen::from_value(result)
+      .map_err(|e| IotaRpcError::FfiError(format!("failed to deserialize protocol config from JS value: {e}")))?;
+    protocol_version
+      .parse()
+      .map_err(|e| IotaRpcError::FfiError(format!("invalid protocol version {protocol_version}: {e}")))
+  }
+
   pub async fn try_get_parsed_past_object(
     &self,
-    _object_id: ObjectId,
-    _version: SequenceNumber,
-    _options: IotaObjectDataOptions,
+    object_id: ObjectId,
+    version: SequenceNumber,
+    options: IotaObjectDataOptions,
   ) -> IotaRpcResult<IotaPastObjectResponse> {
-    // TODO: does not work anymore, find out, why we need to pass a different `SequenceNumber` now
-    unimplemented!("try_get_parsed_past_object");
-    // let params: WasmTryGetPastObjectParams = serde_wasm_bindgen::to_value(&TryGetPastObjectParams::new(
-    //   object_id.to_string(),
-    //   version,
-    //   Some(options),
-    // ))
-    // .map_err(|e| {
-    //   console_log!(
-    //     "Error executing serde_wasm_bindgen::to_value(WasmIotaObjectDataOptions): {:?}",
-    //     e
-    //   );
-    //   IotaRpcError::FfiError(format!("{e:?}"))
-    // })?
-    // .into();
-
-    // let promise: Promise = Promise::resolve(&WasmIotaClient::try_get_past_object(&self.0, &params));
-    // let result: JsValue = JsFuture::from(promise).await.map_err(|e| {
-    //   console_log!("Error executing JsFuture::from(promise): {:?}", e);
-    //   IotaRpcError::FfiError(format!("{e:?}"))
-    // })?;
-
-    // Ok(result.into_serde()?)
+    let params: WasmTryGetPastObjectParams = serde_wasm_bindgen::to_value(&TryGetPastObjectParams::new(
+      object_id.to_string(),
+      version,
+      Some(options),
+    ))
+    .map_err(|e| {
+      console_log!(
+        "Error executing serde_wasm_bindgen::to_value(WasmTryGetPastObjectParams): {:?}",
+        e
+      );
+      IotaRpcError::FfiError(format!("{e:?}"))
+    })?
+    .into();
+
+    let promise: Promise = Promise::resolve(&WasmIotaClient::try_get_past_object(&self.0, &params));
+    let result: JsValue = JsFuture::from(promise).await.map_err(|e| {
+      console_log!("Error executing JsFuture::from(promise): {:?}", e);
+      IotaRpcError::FfiError(format!("{e:?}"))
+    })?;
+
+    #[allow(deprecated)] // will be refactored
+    Ok(result.into_serde()?)
+  }
+
+  pub async fn try_multi_get_past_objects(
+    &self,
+    past_objects: Vec<IotaGetPastObjectRequest>,
+    options: IotaObjectDataOptions,
+  ) -> IotaRpcResult<Vec<IotaPastObjectResponse>> {
+    let params: WasmTryMultiGetPastObjectsParams =
+      serde_wasm_bindgen::to_value(&TryMultiGetPastObjectsParams::new(past_objects, Some(options)))
+        .map_err(|e| {
+          console_log!(
+            "Error executing serde_wasm_bindgen::to_value(WasmTryMultiGetPastObjectsParams): {:?}",
+            e
+          );
+          IotaRpcError::FfiError(format!("{e:?}"))
+        })?
+        .into();
+
+    let promise: Promise = Promise::resolve(&WasmIotaClient::try_multi_get_past_objects(&self.0, &params));
+    let result: JsValue = JsFuture::from(promise).await.map_err(|e| {
+      console_log!("Error executing JsFuture::from(promise): {:?}", e);
+      IotaRpcError::FfiError(format!("{e:?}"))
+    })?;
+
+    #[allow(deprecated)] // will be refactored
+    Ok(result.into_serde()?)
   }
 
   pub async fn query_events(
@@ -492,3 +668,38 @@ impl ManagedWasmIotaClient {
     Ok(WasmIotaTransactionBlockResponseWrapper::new(result.into()))
   }
 }
+
+/// Converts an error thrown by the TS SDK while submitting a request into an [IotaRpcError] of the same kind the
+/// Rust SDK would return, telling apart the errors reported by a node from the errors of the transport.
+fn rpc_error_from_js(error: JsValue) -> IotaRpcError {
+  let property = |key: &str| Reflect::get(&error, &JsValue::from_str(key)).ok();
+  let name = property("name").and_then(|name| name.as_string()).unwrap_or_default();
+  let message = property("message")
+    .and_then(|message| message.as_string())
+    .unwrap_or_else(|| format!("{error:?}"));
+
+  // `JsonRpcError`s carry the JSON-RPC error code returned by the node.
+  if let Some(code) = property("code").and_then(|code| code.as_f64()) {
+    return match serde_json::from_value(serde_json::json!({ "code": code as i32, "message": message })) {
+      Ok(error_object) => IotaRpcError::Rpc(ClientError::Call(error_object)),
+      Err(_) => IotaRpcError::FfiError(format!("{error:?}")),
+    };
+  }
+  // `IotaHTTPStatusError`s carry the HTTP status of a request the node didn't process.
+  if let Some(status) = property("status").and_then(|status| status.as_f64()) {
+    let status = status as u16;
+    let message = format!("HTTP status {status}: {message}");
+    return if status == 408 || status == 429 || status >= 500 {
+      IotaRpcError::Rpc(ClientError::Transport(anyhow::anyhow!(message).into()))
+    } else {
+      IotaRpcError::Rpc(ClientError::Custom(message))
+    };
+  }
+
+  match name.as_str() {
+    // `fetch` rejects with a `TypeError` when the request couldn't be sent or its response couldn't be received.
+    "TypeError" => IotaRpcError::Rpc(ClientError::Transport(anyhow::anyhow!(message).into())),
+    "TimeoutError" | "AbortError" => IotaRpcError::Rpc(ClientError::RequestTimeout),
+    _ => IotaRpcError::FfiError(format!("{error:?}")),
+  }
+}
@@ -8,6 +8,7 @@ use iota_interaction::rpc_types::{IotaTransactionBlockEffects, IotaTransactionBl
 use iota_interaction::types::base_types::{IotaAddress, ObjectRef, SequenceNumber};
 use iota_interaction::types::crypto::{IotaKeyPair, PublicKey, Signature};
 use iota_interaction::types::digests::TransactionDigest;
+use iota_interaction::types::gas::GasCostSummary;
 use iota_interaction::types::transaction::TransactionData;
 use iota_interaction::ProgrammableTransactionBcs;
 use iota_sdk_types::{CommandArgumentError, ObjectId, Owner};
@@ -30,9 +31,11 @@ const TS_SDK_TYPES: &str = r#"
   import {
     Balance,
     ExecuteTransactionBlockParams,
+    GasCostSummary,
     GetCoinsParams,
     GetDynamicFieldObjectParams,
     GetDynamicFieldObjectV2Params,
+    GetDynamicFieldsParams,
     GetObjectParams,
     GetOwnedObjectsParams,
     GetTransactionBlockParams,
@@ -41,14 +44,19 @@ const TS_SDK_TYPES: &str = r#"
     IotaObjectResponse,
     IotaTransactionBlockResponse,
     IotaTransactionBlockResponseOptions,
+    MultiGetObjectsParams,
     ObjectRead,
     PaginatedCoins,
     PaginatedEvents,
     PaginatedObjectsResponse,
     QueryEventsParams,
     TryGetPastObjectParams,
+    TryMultiGetPastObjectsParams,
     DevInspectTransactionBlockParams,
-    DevInspectResults
+    DevInspectResults,
+    DynamicFieldPage,
+    DryRunTransactionBlockParams,
+    DryRunTransactionBlockResponse
   } from "@iota/iota-sdk/client";
   import { bcs } from "@iota/iota-sdk/bcs";
   import {
@@ -105,6 +113,13 @@ extern "C" {
   )]
   pub type WasmIotaTransactionBlockEvents;
 
+  #[derive(Clone)]
+  #[wasm_bindgen(
+    typescript_type = "GasCostSummary",
+    extends = js_sys::Object,
+  )]
+  pub type WasmGasCostSummary;
+
   #[wasm_bindgen(typescript_type = "GetDynamicFieldObjectParams")]
   #[derive(Clone)]
   pub type WasmGetDynamicFieldObjectParams;
@@ -113,6 +128,14 @@ extern "C" {
   #[derive(Clone)]
   pub type WasmGetDynamicFieldObjectV2Params;
 
+  #[wasm_bindgen(typescript_type = "GetDynamicFieldsParams")]
+  #[derive(Clone)]
+  pub type WasmGetDynamicFieldsParams;
+
+  #[wasm_bindgen(typescript_type = "Promise<DynamicFieldPage>")]
+  #[derive(Clone)]
+  pub type PromiseDynamicFieldPage;
+
   #[wasm_bindgen(typescript_type = "GetObjectParams")]
   #[derive(Clone)]
   pub type WasmGetObjectParams;
@@ -121,6 +144,14 @@ extern "C" {
   #[derive(Clone)]
   pub type PromiseIotaTransactionBlockResponse;
 
+  #[wasm_bindgen(typescript_type = "MultiGetObjectsParams")]
+  #[derive(Clone)]
+  pub type WasmMultiGetObjectsParams;
+
+  #[wasm_bindgen(typescript_type = "Promise<IotaObjectResponse[]>")]
+  #[derive(Clone)]
+  pub type PromiseIotaObjectResponseArray;
+
   #[wasm_bindgen(typescript_type = "Promise<IotaObjectResponse>")]
   #[derive(Clone)]
   pub type PromiseIotaObjectResponse;
@@ -141,10 +172,18 @@ extern "C" {
   #[derive(Clone)]
   pub type WasmTryGetPastObjectParams;
 
+  #[wasm_bindgen(typescript_type = "TryMultiGetPastObjectsParams")]
+  #[derive(Clone)]
+  pub type WasmTryMultiGetPastObjectsParams;
+
   #[wasm_bindgen(typescript_type = "Promise<ObjectRead>")]
   #[derive(Clone)]
   pub type PromiseObjectRead;
 
+  #[wasm_bindgen(typescript_type = "Promise<ObjectRead[]>")]
+  #[derive(Clone)]
+  pub type PromiseObjectReadArray;
+
   #[wasm_bindgen(typescript_type = "ExecutionStatus")]
   #[derive(Clone)]
   pub type WasmExecutionStatus;
@@ -221,6 +260,15 @@ impl From<&'_ IotaTransactionBlockEffects> for WasmIotaTransactionBlockEffects {
   }
 }
 
+impl From<&'_ GasCostSummary> for WasmGasCostSummary {
+  fn from(value: &'_ GasCostSummary) -> Self {
+    value
+      .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+      .expect("same representation")
+      .unchecked_into()
+  }
+}
+
 impl From<WasmIotaTransactionBlockEvents> for IotaTransactionBlockEvents {
   fn from(value: WasmIotaTransactionBlockEvents) -> Self {
     serde_wasm_bindgen::from_value(value.into()).expect("have the same repr")
@@ -304,6 +352,9 @@ extern "C" {
   #[wasm_bindgen(js_name = "from", js_class = "Transaction", static_method_of = WasmTransactionBuilder, catch)]
   pub fn from_bcs_bytes(bytes: Uint8Array) -> Result<WasmTransactionBuilder, JsValue>;
 
+  #[wasm_bindgen(js_name = "fromKind", js_class = "Transaction", static_method_of = WasmTransactionBuilder, catch)]
+  pub fn from_kind_bcs_bytes(bytes: Uint8Array) -> Result<WasmTransactionBuilder, JsValue>;
+
   #[wasm_bindgen(method, structural, catch)]
   pub async fn build(this: &WasmTransactionBuilder) -> Result<Uint8Array, JsValue>;
 
@@ -7,23 +7,24 @@ use std::result::Result;
 
 use iota_interaction::error::{Error as IotaRpcError, IotaRpcResult};
 use iota_interaction::rpc_types::{
-  CoinPage, DevInspectArgs, DevInspectResults, EventFilter, EventPage, IotaObjectData, IotaObjectDataOptions,
-  IotaObjectResponse, IotaObjectResponseQuery, IotaPastObjectResponse, IotaTransactionBlockEffects,
+  CoinPage, DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, DynamicFieldPage, EventFilter,
+  EventPage, IotaGetPastObjectRequest, IotaObjectData, IotaObjectDataOptions, IotaObjectResponse,
+  IotaObjectResponseQuery, IotaPastObjectResponse, IotaTransactionBlockEffects, IotaTransactionBlockEffectsAPI as _,
   IotaTransactionBlockEvents, IotaTransactionBlockResponseOptions, ObjectsPage,
 };
 use iota_interaction::types::base_types::{IotaAddress, SequenceNumber};
-use iota_interaction::types::crypto::Signature;
 use iota_interaction::types::digests::TransactionDigest;
 use iota_interaction::types::dynamic_field::DynamicFieldName;
 use iota_interaction::types::event::EventID;
 use iota_interaction::types::iota_serde::BigInt;
 use iota_interaction::types::quorum_driver_types::ExecuteTransactionRequestType;
+use iota_interaction::types::signature::GenericSignature;
 use iota_interaction::types::transaction::{
   ProgrammableTransaction as ProgrammableTransactionSdk, TransactionData, TransactionDataAPI as _, TransactionKind,
 };
 use iota_interaction::{
-  CoinReadTrait, EventTrait, IotaClientTrait, IotaKeySignature, IotaTransactionBlockResponseT, QuorumDriverTrait,
-  ReadTrait,
+  fetch_in_chunks, CoinReadTrait, EventTrait, IotaClientTrait, IotaKeySignature, IotaTransactionBlockResponseT,
+  QuorumDriverTrait, ReadTrait,
 };
 use iota_sdk_types::ObjectId;
 use secret_storage::Signer;
@@ -180,6 +181,15 @@ impl ReadTrait for ReadAdapter {
       .await
   }
 
+  async fn get_dynamic_fields(
+    &self,
+    parent_object_id: ObjectId,
+    cursor: Option<ObjectId>,
+    limit: Option<usize>,
+  ) -> IotaRpcResult<DynamicFieldPage> {
+    self.client.get_dynamic_fields(parent_object_id, cursor, limit).await
+  }
+
   async fn get_object_with_options(
     &self,
     object_id: ObjectId,
@@ -188,6 +198,30 @@ impl ReadTrait for ReadAdapter {
     self.client.get_object_with_options(object_id, options).await
   }
 
+  async fn multi_get_object_with_options(
+    &self,
+    object_ids: Vec<ObjectId>,
+    options: IotaObjectDataOptions,
+  ) -> IotaRpcResult<Vec<IotaObjectResponse>> {
+    fetch_in_chunks(object_ids, |chunk| {
+      let options = options.clone();
+      async move { Ok(self.client.multi_get_object_with_options(chunk, options).await?) }
+    })
+    .await
+  }
+
+  async fn multi_get_past_objects(
+    &self,
+    past_objects: Vec<IotaGetPastObjectRequest>,
+    options: IotaObjectDataOptions,
+  ) -> IotaRpcResult<Vec<IotaPastObjectResponse>> {
+    fetch_in_chunks(past_objects, |chunk| {
+      let options = options.clone();
+      async move { Ok(self.client.try_multi_get_past_objects(chunk, options).await?) }
+    })
+    .await
+  }
+
   async fn get_owned_objects(
     &self,
     address: IotaAddress,
@@ -202,6 +236,14 @@ impl ReadTrait for ReadAdapter {
     self.client.get_reference_gas_price().await
   }
 
+  async fn get_current_epoch(&self) -> IotaRpcResult<u64> {
+    self.client.get_current_epoch().await
+  }
+
+  async fn get_protocol_version(&self) -> IotaRpcResult<u64> {
+    self.client.get_protocol_version().await
+  }
+
   async fn get_transaction_with_options(
     &self,
     digest: TransactionDigest,
@@ -214,16 +256,14 @@ impl ReadTrait for ReadAdapter {
 
   async fn try_get_parsed_past_object(
     &self,
-    _object_id: ObjectId,
-    _version: SequenceNumber,
-    _options: IotaObjectDataOptions,
+    object_id: ObjectId,
+    version: SequenceNumber,
+    options: IotaObjectDataOptions,
   ) -> IotaRpcResult<IotaPastObjectResponse> {
-    // TODO: does not work anymore, find out, why we need to pass a different `SequenceNumber` now
-    unimplemented!("try_get_parsed_past_object");
-    // self
-    //   .client
-    //   .try_get_parsed_past_object(object_id, version, options)
-    //   .await
+    self
+      .client
+      .try_get_parsed_past_object(object_id, version, options)
+      .await
   }
 
   async fn dev_inspect_transaction_block(
@@ -239,6 +279,10 @@ impl ReadTrait for ReadAdapter {
       .dev_inspect_transaction_block(sender_address, tx, gas_price, epoch, additional_args)
       .await
   }
+
+  async fn dry_run_transaction_block(&self, tx_data: TransactionData) -> IotaRpcResult<DryRunTransactionBlockResponse> {
+    self.client.dry_run_transaction_block(tx_data).await
+  }
 }
 
 pub struct QuorumDriverAdapter {
@@ -253,7 +297,7 @@ impl QuorumDriverTrait for QuorumDriverAdapter {
   async fn execute_transaction_block(
     &self,
     tx_data: TransactionData,
-    signatures: Vec<Signature>,
+    signatures: Vec<GenericSignature>,
     options: Option<IotaTransactionBlockResponseOptions>,
     request_type: Option<ExecuteTransactionRequestType>,
   ) -> IotaRpcResult<IotaTransactionBlockResponseAdaptedTraitObj> {
@@ -380,8 +424,39 @@ impl IotaClientTrait for IotaClientTsSdk {
     Ok(50_000_000)
   }
 
-  async fn get_previous_version(&self, _iod: IotaObjectData) -> Result<Option<IotaObjectData>, Self::Error> {
-    unimplemented!();
+  async fn get_previous_version(&self, iod: IotaObjectData) -> Result<Option<IotaObjectData>, Self::Error> {
+    // the transaction that produced this version holds the version it was modified at, if any
+    let Some(prev_tx_digest) = iod.previous_transaction else {
+      return Ok(None);
+    };
+
+    let prev_tx_response = self
+      .read_api()
+      .get_transaction_with_options(
+        prev_tx_digest,
+        IotaTransactionBlockResponseOptions::new().with_effects(),
+      )
+      .await
+      .map_err(|err| Self::Error::JsSysError(format!("could not get previous transaction {prev_tx_digest}; {err}")))?;
+    let effects = prev_tx_response
+      .effects()
+      .ok_or_else(|| Self::Error::JsSysError(format!("could not find effects of transaction {prev_tx_digest}")))?;
+
+    // objects created - or unwrapped - by the previous tx have no previous version
+    let Some((_, previous_version)) = effects
+      .modified_at_versions()
+      .into_iter()
+      .find(|(object_id, _)| *object_id == iod.object_id)
+    else {
+      return Ok(None);
+    };
+
+    match self.get_past_object(iod.object_id, previous_version).await? {
+      IotaPastObjectResponse::VersionFound(value) => Ok(Some(value)),
+      past_obj_response => Err(Self::Error::JsSysError(format!(
+        "could not find previous version, past object response: {past_obj_response:?}"
+      ))),
+    }
   }
 
   async fn get_past_object(
@@ -438,7 +513,7 @@ impl IotaClientTsSdk {
       .quorum_driver_api()
       .execute_transaction_block(
         tx,
-        vec![signature],
+        vec![signature.into()],
         Some(IotaTransactionBlockResponseOptions::full_content()),
         Some(ExecuteTransactionRequestType::WaitForLocalExecution),
       )
@@ -3,7 +3,10 @@
 
 use std::ops::{Deref, DerefMut};
 
+use iota_interaction::move_call::TypedPtb;
+use iota_interaction::types::transaction::TransactionKind;
 use iota_interaction::{ProgrammableTransactionBcs, TransactionBuilderT};
+use js_sys::Uint8Array;
 
 use crate::bindings::WasmTransactionBuilder;
 use crate::error::TsSdkError;
@@ -21,6 +24,22 @@ impl TransactionBuilderTsSdk {
   }
 }
 
+impl TryFrom<TypedPtb> for TransactionBuilderTsSdk {
+  type Error = TsSdkError;
+
+  fn try_from(ptb: TypedPtb) -> Result<Self, Self::Error> {
+    let pt = ptb.finish().map_err(|e| TsSdkError::InvalidArgument(e.to_string()))?;
+    let tx_kind_bcs = bcs::to_bytes(&TransactionKind::Programmable(pt)).map_err(|e| {
+      TsSdkError::TransactionSerializationError(format!("failed to serialize BCS TransactionKind: {e}"))
+    })?;
+
+    NativeTsTransactionBuilderBindingWrapper::from_kind_bcs_bytes(Uint8Array::from(tx_kind_bcs.as_slice()))
+      .map(Self::new)
+      .map_err(WasmError::from)
+      .map_err(TsSdkError::from)
+  }
+}
+
 impl TransactionBuilderT for TransactionBuilderTsSdk {
   type Error = TsSdkError;
   type NativeTxBuilder = NativeTsTransactionBuilderBindingWrapper;
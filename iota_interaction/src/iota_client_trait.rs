@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use std::boxed::Box;
+use std::future::Future;
 use std::option::Option;
 use std::result::Result;
 
@@ -11,9 +12,10 @@ use secret_storage::{SignatureScheme as SignatureSchemeSecretStorage, Signer};
 
 use crate::error::IotaRpcResult;
 use crate::rpc_types::{
-  CoinPage, DevInspectArgs, DevInspectResults, EventFilter, EventPage, IotaObjectData, IotaObjectDataOptions,
-  IotaObjectResponse, IotaObjectResponseQuery, IotaPastObjectResponse, IotaTransactionBlockEffects,
-  IotaTransactionBlockEvents, IotaTransactionBlockResponseOptions, ObjectsPage,
+  CoinPage, DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, DynamicFieldPage, EventFilter,
+  EventPage, IotaGetPastObjectRequest, IotaObjectData, IotaObjectDataOptions, IotaObjectResponse,
+  IotaObjectResponseQuery, IotaPastObjectResponse, IotaTransactionBlockEffects, IotaTransactionBlockEvents,
+  IotaTransactionBlockResponseOptions, ObjectsPage,
 };
 use crate::types::base_types::{IotaAddress, SequenceNumber};
 use crate::types::crypto::{PublicKey, Signature};
@@ -22,11 +24,31 @@ use crate::types::dynamic_field::DynamicFieldName;
 use crate::types::event::EventID;
 use crate::types::iota_serde::BigInt;
 use crate::types::quorum_driver_types::ExecuteTransactionRequestType;
+use crate::types::signature::GenericSignature;
 use crate::types::transaction::{ProgrammableTransaction, TransactionData, TransactionKind};
 use crate::OptionalSend;
 #[cfg(feature = "send-sync-transaction")]
 use crate::OptionalSync;
 
+/// The maximum number of objects a node returns for a single multi-get request.
+pub const MULTI_GET_OBJECTS_LIMIT: usize = 50;
+
+/// Requests `items` through `fetch`, in consecutive chunks of at most [MULTI_GET_OBJECTS_LIMIT] items, and returns
+/// the responses to all chunks in the order of `items`.
+pub async fn fetch_in_chunks<T, R, F, Fut>(items: Vec<T>, mut fetch: F) -> IotaRpcResult<Vec<R>>
+where
+  T: Clone,
+  F: FnMut(Vec<T>) -> Fut,
+  Fut: Future<Output = IotaRpcResult<Vec<R>>>,
+{
+  let mut responses = Vec::with_capacity(items.len());
+  for chunk in items.chunks(MULTI_GET_OBJECTS_LIMIT) {
+    responses.extend(fetch(chunk.to_vec()).await?);
+  }
+
+  Ok(responses)
+}
+
 pub struct IotaKeySignature {
   pub public_key: PublicKey,
   pub signature: Signature,
@@ -101,7 +123,7 @@ pub trait QuorumDriverTrait {
   async fn execute_transaction_block(
     &self,
     tx_data: TransactionData,
-    signatures: Vec<Signature>,
+    signatures: Vec<GenericSignature>,
     options: Option<IotaTransactionBlockResponseOptions>,
     request_type: Option<ExecuteTransactionRequestType>,
   ) -> IotaRpcResult<Box<dyn IotaTransactionBlockResponseT<Error = Self::Error, NativeResponse = Self::NativeResponse>>>;
@@ -130,12 +152,57 @@ pub trait ReadTrait {
     options: Option<IotaObjectDataOptions>,
   ) -> IotaRpcResult<IotaObjectResponse>;
 
+  /// Returns a page of the dynamic fields of the given object.
+  async fn get_dynamic_fields(
+    &self,
+    parent_object_id: ObjectId,
+    cursor: Option<ObjectId>,
+    limit: Option<usize>,
+  ) -> IotaRpcResult<DynamicFieldPage>;
+
   async fn get_object_with_options(
     &self,
     object_id: ObjectId,
     options: IotaObjectDataOptions,
   ) -> IotaRpcResult<IotaObjectResponse>;
 
+  /// Returns the objects with the given IDs, in the same order.
+  ///
+  /// Implementations are expected to request objects in chunks of at most [MULTI_GET_OBJECTS_LIMIT] - see
+  /// [fetch_in_chunks]; the default implementation requests them one by one.
+  async fn multi_get_object_with_options(
+    &self,
+    object_ids: Vec<ObjectId>,
+    options: IotaObjectDataOptions,
+  ) -> IotaRpcResult<Vec<IotaObjectResponse>> {
+    let mut responses = Vec::with_capacity(object_ids.len());
+    for object_id in object_ids {
+      responses.push(self.get_object_with_options(object_id, options.clone()).await?);
+    }
+
+    Ok(responses)
+  }
+
+  /// Returns the given versions of objects, in the same order.
+  ///
+  /// Implementations are expected to request objects in chunks of at most [MULTI_GET_OBJECTS_LIMIT] - see
+  /// [fetch_in_chunks]; the default implementation requests them one by one.
+  async fn multi_get_past_objects(
+    &self,
+    past_objects: Vec<IotaGetPastObjectRequest>,
+    options: IotaObjectDataOptions,
+  ) -> IotaRpcResult<Vec<IotaPastObjectResponse>> {
+    let mut responses = Vec::with_capacity(past_objects.len());
+    for past_object in past_objects {
+      let response = self
+        .try_get_parsed_past_object(past_object.object_id, past_object.version, options.clone())
+        .await?;
+      responses.push(response);
+    }
+
+    Ok(responses)
+  }
+
   async fn get_owned_objects(
     &self,
     address: IotaAddress,
@@ -146,6 +213,12 @@ pub trait ReadTrait {
 
   async fn get_reference_gas_price(&self) -> IotaRpcResult<u64>;
 
+  /// Returns the epoch the network is currently in.
+  async fn get_current_epoch(&self) -> IotaRpcResult<u64>;
+
+  /// Returns the version of the protocol the network currently runs.
+  async fn get_protocol_version(&self) -> IotaRpcResult<u64>;
+
   async fn get_transaction_with_options(
     &self,
     digest: TransactionDigest,
@@ -167,6 +240,9 @@ pub trait ReadTrait {
     epoch: Option<BigInt<u64>>,
     additional_args: Option<DevInspectArgs>,
   ) -> IotaRpcResult<DevInspectResults>;
+
+  /// Simulates the execution of `tx_data` without signing nor submitting it.
+  async fn dry_run_transaction_block(&self, tx_data: TransactionData) -> IotaRpcResult<DryRunTransactionBlockResponse>;
 }
 
 #[cfg_attr(not(feature = "send-sync-transaction"), async_trait(?Send))]
@@ -263,3 +339,41 @@ pub trait IotaClientTrait {
     version: SequenceNumber,
   ) -> Result<IotaPastObjectResponse, Self::Error>;
 }
+
+#[cfg(test)]
+mod tests {
+  use std::future;
+
+  use futures::executor::block_on;
+
+  use super::*;
+  use crate::error::Error;
+
+  #[test]
+  fn items_are_fetched_in_chunks() {
+    let items = (0..120).collect::<Vec<u32>>();
+    let mut chunk_sizes = vec![];
+
+    let responses = block_on(fetch_in_chunks(items.clone(), |chunk| {
+      chunk_sizes.push(chunk.len());
+      future::ready(Ok(chunk.into_iter().map(|item| item * 2).collect()))
+    }))
+    .unwrap();
+
+    assert_eq!(chunk_sizes, vec![50, 50, 20]);
+    assert_eq!(responses, items.iter().map(|item| item * 2).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn fetching_stops_at_the_first_failed_chunk() {
+    let mut requests = 0;
+
+    let result = block_on(fetch_in_chunks((0..120).collect::<Vec<u32>>(), |_| {
+      requests += 1;
+      future::ready(Err::<Vec<u32>, _>(Error::Data("node unavailable".to_owned())))
+    }));
+
+    assert!(result.is_err());
+    assert_eq!(requests, 1);
+  }
+}
@@ -5,6 +5,10 @@
 
 #![allow(missing_docs)]
 
+// Lets the code generated through `#[derive(MoveType)]` refer to this crate in its own tests.
+#[cfg(test)]
+extern crate self as iota_interaction;
+
 pub mod interaction_error;
 
 mod effects_mut_api;
@@ -14,11 +18,13 @@ mod iota_verifiable_credential;
 mod keypair_signer;
 #[cfg(feature = "keytool")]
 pub mod keytool;
+pub mod move_call;
 mod move_type;
 mod transaction_builder_trait;
 
 pub use effects_mut_api::*;
 pub use iota_client_trait::*;
+pub use iota_interaction_derive::MoveType;
 pub use iota_verifiable_credential::*;
 #[cfg(feature = "keypair-signer")]
 pub use keypair_signer::*;
@@ -37,6 +43,13 @@ pub use iota_sdk::*;
 #[cfg(target_arch = "wasm32")]
 pub use sdk_types::*;
 
+/// Items used by the code generated through `#[derive(MoveType)]`.
+#[doc(hidden)]
+pub mod __private {
+  pub use iota_sdk_types::{ObjectId, TypeTag};
+  pub use serde::Serialize;
+}
+
 /// BCS serialized Transaction, where a Transaction includes the TransactionData and a Vec<Signature>
 pub type TransactionBcs = Vec<u8>;
 /// BCS serialized TransactionData
@@ -0,0 +1,370 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A typed layer over [ProgrammableTransactionBuilder] to compose Move calls.
+//!
+//! [TypedPtb] lets Move calls be built out of Rust values, converted into transaction inputs through [IntoMoveArg].
+//! Arguments are passed in order; the name given to each of them is only used in error messages. [TypedPtb] also
+//! keeps track of the hot potatoes - values that must be consumed within the same transaction -
+//! that are yet to be returned, failing to build a transaction that would be aborted because of them.
+//!
+//! ```ignore
+//! let mut ptb = TypedPtb::new();
+//! let (token, borrow) = ptb
+//!   .move_call(package, "controller", "borrow")
+//!   .arg("controller_cap", controller_cap_ref)
+//!   .call_returning::<(Argument, Argument)>()?;
+//! let borrow = ptb.hot_potato(borrow, "controller::Borrow");
+//!
+//! ptb
+//!   .move_call(package, "identity", "approve")
+//!   .arg("identity", identity_ref)
+//!   .arg("token", token)
+//!   .arg("proposal_id", proposal_id)
+//!   .call()?;
+//!
+//! ptb
+//!   .move_call(package, "controller", "put_back")
+//!   .arg("controller_cap", controller_cap_ref)
+//!   .arg("token", token)
+//!   .arg("borrow", borrow)
+//!   .call()?;
+//!
+//! let programmable_tx = ptb.finish()?;
+//! ```
+//!
+//! [TypedPtb] is platform agnostic: the resulting [ProgrammableTransaction] can be handed over to either the Rust SDK
+//! or the TS SDK transaction builder.
+
+use std::collections::BTreeMap;
+
+use iota_sdk_types::{Argument, Identifier, ObjectId, Owner, TypeTag};
+
+use crate::interaction_error::Error;
+use crate::rpc_types::OwnedObjectRef;
+use crate::types::base_types::ObjectRef;
+use crate::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use crate::types::transaction::{CallArg, ProgrammableTransaction, SharedObjectRef};
+use crate::MoveType;
+
+/// A [ProgrammableTransactionBuilder] that composes typed Move calls.
+#[derive(Default)]
+pub struct TypedPtb {
+  ptb: ProgrammableTransactionBuilder,
+  pending_hot_potatoes: BTreeMap<u64, String>,
+  next_hot_potato_id: u64,
+}
+
+impl TypedPtb {
+  /// Returns an empty [TypedPtb].
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Starts a call to `module::function` of the given package.
+  pub fn move_call<'ptb>(&'ptb mut self, package: ObjectId, module: &str, function: &str) -> MoveCall<'ptb> {
+    let target = Identifier::new(module)
+      .and_then(|module| Identifier::new(function).map(|function| (module, function)))
+      .map_err(|e| {
+        Error::InvalidArgument(format!(
+          "invalid Move call target `{package}::{module}::{function}`; {e}"
+        ))
+      });
+
+    MoveCall {
+      ptb: self,
+      package,
+      target,
+      type_args: vec![],
+      args: Ok(vec![]),
+    }
+  }
+
+  /// Marks `argument` as a hot potato, i.e. a value that must be passed to a later Move call of this transaction.
+  /// `name` is used to report the hot potatoes that were never consumed.
+  pub fn hot_potato(&mut self, argument: Argument, name: impl Into<String>) -> HotPotato {
+    let id = self.next_hot_potato_id;
+    self.next_hot_potato_id += 1;
+    self.pending_hot_potatoes.insert(id, name.into());
+
+    HotPotato { id, argument }
+  }
+
+  /// Returns a mutable reference to the underlying [ProgrammableTransactionBuilder], for the operations that
+  /// are not covered by [TypedPtb].
+  pub fn as_native(&mut self) -> &mut ProgrammableTransactionBuilder {
+    &mut self.ptb
+  }
+
+  /// Returns the underlying [ProgrammableTransactionBuilder], making sure all hot potatoes have been consumed.
+  pub fn into_inner(self) -> Result<ProgrammableTransactionBuilder, Error> {
+    if !self.pending_hot_potatoes.is_empty() {
+      let names = self.pending_hot_potatoes.into_values().collect::<Vec<_>>();
+      return Err(Error::InvalidArgument(format!(
+        "the following hot potatoes were never consumed: {}",
+        names.join(", ")
+      )));
+    }
+
+    Ok(self.ptb)
+  }
+
+  /// Builds the [ProgrammableTransaction], making sure all hot potatoes have been consumed.
+  pub fn finish(self) -> Result<ProgrammableTransaction, Error> {
+    self.into_inner().map(ProgrammableTransactionBuilder::finish)
+  }
+}
+
+impl From<ProgrammableTransactionBuilder> for TypedPtb {
+  fn from(ptb: ProgrammableTransactionBuilder) -> Self {
+    Self {
+      ptb,
+      ..Default::default()
+    }
+  }
+}
+
+/// A value that must be consumed by a Move call before the transaction ends, see [TypedPtb::hot_potato].
+///
+/// A [HotPotato] is consumed by passing it as an argument to [MoveCall::arg].
+#[derive(Debug)]
+#[must_use = "hot potatoes must be consumed within the same transaction"]
+pub struct HotPotato {
+  id: u64,
+  argument: Argument,
+}
+
+impl HotPotato {
+  /// Returns the argument of this hot potato, without consuming it.
+  pub fn argument(&self) -> Argument {
+    self.argument
+  }
+}
+
+/// Types that can be used as arguments of a [MoveCall].
+///
+/// - [MoveType]s are passed by value, as pure inputs;
+/// - [ObjectRef]s are passed as owned or immutable objects;
+/// - [OwnedObjectRef]s are passed according to their owner, shared objects being passed mutably;
+/// - [CallArg]s are passed as they are;
+/// - [Argument]s - e.g. the results of previous calls - are passed as they are;
+/// - [HotPotato]s are passed as they are, and are marked as consumed.
+pub trait IntoMoveArg {
+  /// Adds this value to `ptb`'s inputs, if needed, returning the [Argument] referencing it.
+  fn into_move_arg(self, ptb: &mut TypedPtb) -> anyhow::Result<Argument>;
+}
+
+impl<T: MoveType> IntoMoveArg for T {
+  fn into_move_arg(self, ptb: &mut TypedPtb) -> anyhow::Result<Argument> {
+    ptb.ptb.pure(self)
+  }
+}
+
+impl IntoMoveArg for Argument {
+  fn into_move_arg(self, _ptb: &mut TypedPtb) -> anyhow::Result<Argument> {
+    Ok(self)
+  }
+}
+
+impl IntoMoveArg for HotPotato {
+  fn into_move_arg(self, ptb: &mut TypedPtb) -> anyhow::Result<Argument> {
+    ptb.pending_hot_potatoes.remove(&self.id);
+    Ok(self.argument)
+  }
+}
+
+impl IntoMoveArg for CallArg {
+  fn into_move_arg(self, ptb: &mut TypedPtb) -> anyhow::Result<Argument> {
+    ptb.ptb.input(self)
+  }
+}
+
+impl IntoMoveArg for ObjectRef {
+  fn into_move_arg(self, ptb: &mut TypedPtb) -> anyhow::Result<Argument> {
+    ptb.ptb.obj(CallArg::ImmutableOrOwned(self))
+  }
+}
+
+impl IntoMoveArg for OwnedObjectRef {
+  fn into_move_arg(self, ptb: &mut TypedPtb) -> anyhow::Result<Argument> {
+    let call_arg = match self.owner {
+      Owner::Shared(initial_shared_version) => CallArg::Shared(SharedObjectRef {
+        object_id: self.object_id(),
+        initial_shared_version,
+        mutable: true,
+      }),
+      _ => CallArg::ImmutableOrOwned(self.reference),
+    };
+    ptb.ptb.obj(call_arg)
+  }
+}
+
+/// The results of a Move call, see [MoveCall::call_returning].
+pub trait CallResults: Sized {
+  /// Returns the results of the command at index `command_idx`.
+  fn from_command(command_idx: u16) -> Self;
+}
+
+impl CallResults for Argument {
+  fn from_command(command_idx: u16) -> Self {
+    Argument::Result(command_idx)
+  }
+}
+
+macro_rules! impl_call_results_for_tuple {
+  ($($arg:ident : $idx:tt),+) => {
+    impl CallResults for ($($arg,)+) {
+      fn from_command(command_idx: u16) -> Self {
+        ($(Argument::NestedResult(command_idx, $idx),)+)
+      }
+    }
+  };
+}
+
+impl_call_results_for_tuple!(Argument: 0, Argument: 1);
+impl_call_results_for_tuple!(Argument: 0, Argument: 1, Argument: 2);
+impl_call_results_for_tuple!(Argument: 0, Argument: 1, Argument: 2, Argument: 3);
+impl_call_results_for_tuple!(Argument: 0, Argument: 1, Argument: 2, Argument: 3, Argument: 4);
+
+/// A Move call being built, see [TypedPtb::move_call].
+///
+/// Errors are reported once the call is added to the transaction, through [MoveCall::call] or
+/// [MoveCall::call_returning].
+#[must_use = "a Move call is only added to the transaction through `call` or `call_returning`"]
+pub struct MoveCall<'ptb> {
+  ptb: &'ptb mut TypedPtb,
+  package: ObjectId,
+  target: Result<(Identifier, Identifier), Error>,
+  type_args: Vec<TypeTag>,
+  args: Result<Vec<Argument>, Error>,
+}
+
+impl MoveCall<'_> {
+  /// Adds `T`'s Move type - relative to the called package - to this call's type arguments.
+  pub fn type_arg<T: MoveType>(mut self) -> Self {
+    self.type_args.push(T::move_type(self.package));
+    self
+  }
+
+  /// Adds `type_tag` to this call's type arguments.
+  pub fn type_arg_tag(mut self, type_tag: TypeTag) -> Self {
+    self.type_args.push(type_tag);
+    self
+  }
+
+  /// Adds the next argument to this call. `name` is only used to report an invalid argument.
+  pub fn arg(mut self, name: &str, value: impl IntoMoveArg) -> Self {
+    if let Ok(args) = &mut self.args {
+      match value.into_move_arg(self.ptb) {
+        Ok(arg) => args.push(arg),
+        Err(e) => self.args = Err(Error::InvalidArgument(format!("invalid argument `{name}`; {e}"))),
+      }
+    }
+    self
+  }
+
+  /// Adds this call to the transaction, returning its result.
+  pub fn call(self) -> Result<Argument, Error> {
+    self.call_returning()
+  }
+
+  /// Adds this call to the transaction, returning its results - e.g. `(Argument, Argument)` for a Move function
+  /// returning two values.
+  pub fn call_returning<R: CallResults>(self) -> Result<R, Error> {
+    let (module, function) = self.target?;
+    let args = self.args?;
+    let Argument::Result(command_idx) =
+      self
+        .ptb
+        .ptb
+        .programmable_move_call(self.package, module, function, self.type_args, args)
+    else {
+      unreachable!("making move calls always return a result variant");
+    };
+
+    Ok(R::from_command(command_idx))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use anyhow::anyhow;
+
+  use super::*;
+
+  fn package() -> ObjectId {
+    ObjectId::new([0xaa; 32])
+  }
+
+  /// An argument that cannot be added to a transaction.
+  struct Invalid;
+
+  impl IntoMoveArg for Invalid {
+    fn into_move_arg(self, _ptb: &mut TypedPtb) -> anyhow::Result<Argument> {
+      Err(anyhow!("not today"))
+    }
+  }
+
+  #[test]
+  fn hot_potatoes_must_be_consumed() {
+    let mut ptb = TypedPtb::new();
+    let borrow = ptb.move_call(package(), "controller", "borrow").call().unwrap();
+    let _borrow = ptb.hot_potato(borrow, "controller::Borrow");
+
+    let error = ptb.finish().unwrap_err().to_string();
+    assert!(error.contains("controller::Borrow"), "{error}");
+  }
+
+  #[test]
+  fn consumed_hot_potatoes_let_the_transaction_be_built() {
+    let mut ptb = TypedPtb::new();
+    let borrow = ptb.move_call(package(), "controller", "borrow").call().unwrap();
+    let borrow = ptb.hot_potato(borrow, "controller::Borrow");
+    ptb
+      .move_call(package(), "controller", "put_back")
+      .arg("borrow", borrow)
+      .call()
+      .unwrap();
+
+    assert_eq!(ptb.finish().unwrap().commands.len(), 2);
+  }
+
+  #[test]
+  fn results_refer_to_their_command() {
+    let mut ptb = TypedPtb::new();
+    let first = ptb.move_call(package(), "token", "new").call().unwrap();
+    let (a, b) = ptb
+      .move_call(package(), "token", "split")
+      .arg("token", first)
+      .call_returning::<(Argument, Argument)>()
+      .unwrap();
+
+    assert_eq!(first, Argument::Result(0));
+    assert_eq!((a, b), (Argument::NestedResult(1, 0), Argument::NestedResult(1, 1)));
+  }
+
+  #[test]
+  fn the_first_invalid_argument_is_reported() {
+    let mut ptb = TypedPtb::new();
+    let error = ptb
+      .move_call(package(), "token", "mint")
+      .arg("amount", 42_u64)
+      .arg("recipient", Invalid)
+      .arg("metadata", Invalid)
+      .call()
+      .unwrap_err()
+      .to_string();
+
+    assert!(error.contains("invalid argument `recipient`; not today"), "{error}");
+    // The call was not added to the transaction.
+    assert!(ptb.finish().unwrap().commands.is_empty());
+  }
+
+  #[test]
+  fn invalid_targets_are_reported() {
+    let mut ptb = TypedPtb::new();
+    let result = ptb.move_call(package(), "token", "not-a-function").call();
+
+    assert!(matches!(result, Err(Error::InvalidArgument(_))));
+  }
+}
@@ -1,10 +1,16 @@
 // Copyright 2020-2025 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use std::marker::PhantomData;
+use std::str::FromStr;
+
 use iota_sdk_types::{ObjectId, TypeTag};
-use serde::Serialize;
+use move_core_types::u256::U256;
+use serde::{Deserialize, Serialize};
 
 use crate::types::base_types::IotaAddress;
+use crate::types::collection_types::{VecMap, VecSet};
+use crate::types::id::{ID, UID};
 use crate::IotaVerifiableCredential;
 
 pub enum TypedValue<'a, T: MoveType> {
@@ -73,3 +79,177 @@ impl<T: MoveType> MoveType for Vec<T> {
     TypeTag::Vector(Box::new(T::move_type(package)))
   }
 }
+
+impl MoveType for U256 {
+  fn move_type(_package: ObjectId) -> TypeTag {
+    TypeTag::U256
+  }
+}
+
+impl MoveType for String {
+  fn move_type(_package: ObjectId) -> TypeTag {
+    framework_type("0x1::string::String", &[])
+  }
+}
+
+impl<T: MoveType> MoveType for Option<T> {
+  fn move_type(package: ObjectId) -> TypeTag {
+    framework_type("0x1::option::Option", &[T::move_type(package)])
+  }
+}
+
+impl MoveType for ObjectId {
+  fn move_type(_package: ObjectId) -> TypeTag {
+    framework_type("0x2::object::ID", &[])
+  }
+}
+
+impl MoveType for ID {
+  fn move_type(_package: ObjectId) -> TypeTag {
+    framework_type("0x2::object::ID", &[])
+  }
+}
+
+impl MoveType for UID {
+  fn move_type(_package: ObjectId) -> TypeTag {
+    framework_type("0x2::object::UID", &[])
+  }
+}
+
+impl<T: MoveType> MoveType for VecSet<T> {
+  fn move_type(package: ObjectId) -> TypeTag {
+    framework_type("0x2::vec_set::VecSet", &[T::move_type(package)])
+  }
+}
+
+impl<K: MoveType, V: MoveType> MoveType for VecMap<K, V> {
+  fn move_type(package: ObjectId) -> TypeTag {
+    framework_type("0x2::vec_map::VecMap", &[K::move_type(package), V::move_type(package)])
+  }
+}
+
+/// The Rust counterpart of Move's `0x2::table::Table<K, V>`, keeping track of its key and value types.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Table<K, V> {
+  /// The ID of the table.
+  pub id: ObjectId,
+  /// The number of entries in the table.
+  pub size: u64,
+  #[serde(skip)]
+  _entries: PhantomData<(K, V)>,
+}
+
+impl<K, V> Table<K, V> {
+  /// Returns a new [Table] with the given ID and size.
+  pub fn new(id: ObjectId, size: u64) -> Self {
+    Self {
+      id,
+      size,
+      _entries: PhantomData,
+    }
+  }
+}
+
+impl<K: MoveType, V: MoveType> MoveType for Table<K, V> {
+  fn move_type(package: ObjectId) -> TypeTag {
+    framework_type("0x2::table::Table", &[K::move_type(package), V::move_type(package)])
+  }
+}
+
+/// Returns the [TypeTag] of the given Move framework type, instantiated with `type_params`.
+fn framework_type(type_name: &str, type_params: &[TypeTag]) -> TypeTag {
+  let type_tag = if type_params.is_empty() {
+    type_name.to_owned()
+  } else {
+    let type_params = type_params.iter().map(ToString::to_string).collect::<Vec<_>>();
+    format!("{type_name}<{}>", type_params.join(", "))
+  };
+
+  TypeTag::from_str(&type_tag).expect("valid Move type")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn package() -> ObjectId {
+    ObjectId::new([0xaa; 32])
+  }
+
+  fn type_tag(type_tag: &str) -> TypeTag {
+    TypeTag::from_str(type_tag).expect("valid type tag")
+  }
+
+  #[derive(Serialize, crate::MoveType)]
+  #[move_type(module = "token")]
+  struct Token {
+    balance: u64,
+  }
+
+  #[derive(Serialize, crate::MoveType)]
+  #[move_type(module = "coin", name = "Coin", address = "0x2")]
+  struct FrameworkCoin<T> {
+    balance: u64,
+    #[serde(skip)]
+    _coin_type: PhantomData<T>,
+  }
+
+  #[derive(Serialize, crate::MoveType)]
+  #[move_type(module = "wrapper", type_params(V))]
+  struct Wrapper<K, V> {
+    key: K,
+    value: V,
+  }
+
+  #[test]
+  fn framework_types_ignore_the_package() {
+    let package = package();
+
+    assert_eq!(String::move_type(package), type_tag("0x1::string::String"));
+    assert_eq!(
+      Option::<String>::move_type(package),
+      type_tag("0x1::option::Option<0x1::string::String>")
+    );
+    assert_eq!(
+      VecMap::<String, u64>::move_type(package),
+      type_tag("0x2::vec_map::VecMap<0x1::string::String, u64>")
+    );
+    assert_eq!(
+      Table::<ID, Vec<u8>>::move_type(package),
+      type_tag("0x2::table::Table<0x2::object::ID, vector<u8>>")
+    );
+    assert_eq!(ID::move_type(package), type_tag("0x2::object::ID"));
+    assert_eq!(ObjectId::move_type(package), ID::move_type(package));
+  }
+
+  #[test]
+  fn derived_types_default_to_the_package_and_rust_name() {
+    let package = package();
+
+    assert_eq!(Token::move_type(package), type_tag(&format!("{package}::token::Token")));
+    assert_eq!(
+      Vec::<Token>::move_type(package),
+      type_tag(&format!("vector<{package}::token::Token>"))
+    );
+  }
+
+  #[test]
+  fn derived_types_honor_address_and_name() {
+    let package = package();
+
+    assert_eq!(
+      FrameworkCoin::<Token>::move_type(package),
+      type_tag(&format!("0x2::coin::Coin<{package}::token::Token>"))
+    );
+  }
+
+  #[test]
+  fn derived_types_only_use_the_listed_type_params() {
+    let package = package();
+
+    assert_eq!(
+      Wrapper::<u8, Option<bool>>::move_type(package),
+      type_tag(&format!("{package}::wrapper::Wrapper<0x1::option::Option<bool>>"))
+    );
+  }
+}
@@ -6,13 +6,15 @@ use serde::{Deserialize, Serialize};
 
 use super::iota_json_rpc_types::iota_transaction::IotaTransactionBlockResponseOptions;
 use super::iota_types::quorum_driver_types::ExecuteTransactionRequestType;
-use super::types::crypto::Signature;
+use super::types::signature::GenericSignature;
 use super::types::transaction::TransactionData;
-use crate::rpc_types::{DevInspectArgs, EventFilter, IotaObjectDataFilter, IotaObjectDataOptions};
-use crate::types::base_types::IotaAddress;
+use crate::rpc_types::{
+  DevInspectArgs, EventFilter, IotaGetPastObjectRequest, IotaObjectDataFilter, IotaObjectDataOptions,
+};
+use crate::types::base_types::{IotaAddress, SequenceNumber};
 use crate::types::dynamic_field::DynamicFieldName;
 use crate::types::event::EventID;
-use crate::types::iota_serde::{BigInt, SequenceNumber};
+use crate::types::iota_serde::BigInt;
 use crate::types::transaction::TransactionKind;
 // The types defined in this file:
 // * do not exist in the iota rust sdk
@@ -40,7 +42,7 @@ pub struct ExecuteTransactionBlockParams {
 impl ExecuteTransactionBlockParams {
   pub fn new(
     tx_data: TransactionData,
-    signatures: Vec<Signature>,
+    signatures: Vec<GenericSignature>,
     options: Option<IotaTransactionBlockResponseOptions>,
     request_type: Option<ExecuteTransactionRequestType>,
   ) -> Self {
@@ -58,6 +60,23 @@ impl ExecuteTransactionBlockParams {
   }
 }
 
+/// Simulates the execution of a transaction without submitting it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunTransactionBlockParams {
+  /// BCS serialized transaction data bytes without its type tag, as base-64 encoded string.
+  transaction_block: Base64,
+}
+
+impl DryRunTransactionBlockParams {
+  pub fn new(tx_data: &TransactionData) -> Self {
+    let tx_data_bcs = bcs::to_bytes(tx_data).expect("this serialization cannot fail");
+    DryRunTransactionBlockParams {
+      transaction_block: Base64::from_bytes(&tx_data_bcs),
+    }
+  }
+}
+
 /// Return the dynamic field object information for a specified object
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -98,6 +117,29 @@ impl GetDynamicFieldObjectV2Params {
   }
 }
 
+/// Return the list of dynamic field objects owned by an object.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetDynamicFieldsParams {
+  /// The ID of the parent object
+  parent_id: String,
+  /// An optional paging cursor. If provided, the query will start from the next item after the specified
+  /// cursor. Default to start from the first item if not specified.
+  cursor: Option<String>,
+  /// Maximum item returned per page, default to [QUERY_MAX_RESULT_LIMIT] if not specified.
+  limit: Option<usize>,
+}
+
+impl GetDynamicFieldsParams {
+  pub fn new(parent_id: String, cursor: Option<String>, limit: Option<usize>) -> Self {
+    GetDynamicFieldsParams {
+      parent_id,
+      cursor,
+      limit,
+    }
+  }
+}
+
 /// Return the object information for a specified object
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -114,6 +156,22 @@ impl GetObjectParams {
   }
 }
 
+/// Return the object data for a list of objects
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiGetObjectsParams {
+  /// the IDs of the queried objects
+  ids: Vec<String>,
+  /// options for specifying the content to be returned
+  options: Option<IotaObjectDataOptions>,
+}
+
+impl MultiGetObjectsParams {
+  pub fn new(ids: Vec<String>, options: Option<IotaObjectDataOptions>) -> Self {
+    MultiGetObjectsParams { ids, options }
+  }
+}
+
 /// Return the list of objects owned by an address. Note that if the address owns more than
 /// `QUERY_MAX_RESULT_LIMIT` objects, the pagination is not accurate, because previous page may have
 /// been updated when the next page is fetched. Please use iotax_queryObjects if this is a concern.
@@ -175,15 +233,36 @@ impl GetTransactionBlockParams {
 pub struct TryGetPastObjectParams {
   /// the ID of the queried object
   id: String,
-  /// the version of the queried object. If None, default to the latest known version
-  version: SequenceNumber,
+  /// the version of the queried object, as a plain number
+  version: u64,
   //// options for specifying the content to be returned
   options: Option<IotaObjectDataOptions>,
 }
 
 impl TryGetPastObjectParams {
   pub fn new(id: String, version: SequenceNumber, options: Option<IotaObjectDataOptions>) -> Self {
-    TryGetPastObjectParams { id, version, options }
+    TryGetPastObjectParams {
+      id,
+      version: version.value(),
+      options,
+    }
+  }
+}
+
+/// Note there is no software-level guarantee/SLA that objects with past versions can be retrieved by
+/// this API, even if the object and version exists/existed. The result may vary across nodes depending
+/// on their pruning policies. Return the object information for a specified version
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TryMultiGetPastObjectsParams {
+  /// a vector of object and versions to be queried
+  past_objects: Vec<IotaGetPastObjectRequest>,
+  /// options for specifying the content to be returned
+  options: Option<IotaObjectDataOptions>,
+}
+
+impl TryMultiGetPastObjectsParams {
+  pub fn new(past_objects: Vec<IotaGetPastObjectRequest>, options: Option<IotaObjectDataOptions>) -> Self {
+    TryMultiGetPastObjectsParams { past_objects, options }
   }
 }
 
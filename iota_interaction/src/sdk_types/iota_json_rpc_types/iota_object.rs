@@ -17,6 +17,7 @@ use crate::types::{
         IotaAddress, ObjectDigest, ObjectInfo, ObjectRef, ObjectType, SequenceNumber,
         TransactionDigest,
     },
+    dynamic_field::DynamicFieldInfo,
     error::{ExecutionError, UserInputError, UserInputResult},
     move_package::{MovePackage, TypeOrigin, UpgradeInfo},
 };
@@ -821,6 +822,8 @@ pub struct IotaMovePackage {
 // CheckpointedObjectID is not available at the moment
 pub type ObjectsPage = Page<IotaObjectResponse, ObjectId>;
 
+pub type DynamicFieldPage = Page<DynamicFieldInfo, ObjectId>;
+
 #[serde_as]
 #[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq)]
 #[serde(rename = "GetPastObjectRequest", rename_all = "camelCase")]
@@ -366,6 +366,18 @@ pub struct IotaTransactionBlockEvents {
     pub data: Vec<IotaEvent>,
 }
 
+/// The response from dry running a transaction.
+///
+/// Only a subset of the fields returned by the node is supported.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename = "DryRunTransactionBlockResponse", rename_all = "camelCase")]
+pub struct DryRunTransactionBlockResponse {
+    /// The effects the transaction would have if executed.
+    pub effects: IotaTransactionBlockEffects,
+    /// The events the transaction would emit if executed.
+    pub events: IotaTransactionBlockEvents,
+}
+
 // TODO: this file might not be the best place for this struct.
 /// Additional arguments supplied to dev inspect beyond what is allowed in
 /// today's API.
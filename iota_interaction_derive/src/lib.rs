@@ -0,0 +1,276 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Derive macros for the traits of `iota_interaction`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned as _;
+use syn::{parse_macro_input, Data, DeriveInput, Ident, LitStr};
+
+/// Derives `iota_interaction::MoveType` for a struct or an enum.
+///
+/// ## Attributes
+/// - `#[move_type(module = "...")]`: **required**, the Move module the type is defined in;
+/// - `#[move_type(name = "...")]`: the name of the Move type, defaults to the name of the Rust type;
+/// - `#[move_type(address = "...")]`: the address of the package the type is defined in - e.g. `"0x2"` - defaults to
+///   the `package` passed to `MoveType::move_type`;
+/// - `#[move_type(type_params(A, B))]`: the Rust type parameters that map to the Move type parameters, in order.
+///   Defaults to all the Rust type parameters.
+///
+/// ## Example
+/// ```ignore
+/// #[derive(Serialize, MoveType)]
+/// #[move_type(module = "token", name = "Token")]
+/// struct Token<T> {
+///   id: UID,
+///   balance: T,
+/// }
+///
+/// // `{package}::token::Token<u64>`
+/// let type_tag = Token::<u64>::move_type(package);
+/// ```
+#[proc_macro_derive(MoveType, attributes(move_type))]
+pub fn derive_move_type(input: TokenStream) -> TokenStream {
+  let input = parse_macro_input!(input as DeriveInput);
+  match expand_move_type(input) {
+    Ok(tokens) => tokens.into(),
+    Err(e) => e.into_compile_error().into(),
+  }
+}
+
+#[derive(Default)]
+struct MoveTypeAttrs {
+  module: Option<LitStr>,
+  name: Option<LitStr>,
+  address: Option<LitStr>,
+  type_params: Option<Vec<Ident>>,
+}
+
+impl MoveTypeAttrs {
+  fn parse(input: &DeriveInput) -> syn::Result<Self> {
+    let mut attrs = Self::default();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("move_type")) {
+      attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("module") {
+          attrs.module = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("name") {
+          attrs.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("address") {
+          attrs.address = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("type_params") {
+          let mut params = vec![];
+          meta.parse_nested_meta(|param| {
+            let ident = param
+              .path
+              .get_ident()
+              .ok_or_else(|| param.error("expected a type parameter"))?;
+            params.push(ident.clone());
+            Ok(())
+          })?;
+          attrs.type_params = Some(params);
+        } else {
+          return Err(meta.error("unsupported `move_type` attribute"));
+        }
+        Ok(())
+      })?;
+    }
+
+    Ok(attrs)
+  }
+}
+
+fn expand_move_type(input: DeriveInput) -> syn::Result<TokenStream2> {
+  if let Data::Union(data) = &input.data {
+    return Err(syn::Error::new(
+      data.union_token.span(),
+      "`MoveType` cannot be derived for unions",
+    ));
+  }
+
+  let attrs = MoveTypeAttrs::parse(&input)?;
+  let module = attrs.module.ok_or_else(|| {
+    syn::Error::new(
+      input.ident.span(),
+      "missing Move module, e.g. `#[move_type(module = \"my_module\")]`",
+    )
+  })?;
+  check_identifier(&module)?;
+  let name = attrs
+    .name
+    .unwrap_or_else(|| LitStr::new(&input.ident.to_string(), input.ident.span()));
+  check_identifier(&name)?;
+
+  let declared_params = input
+    .generics
+    .type_params()
+    .map(|param| &param.ident)
+    .collect::<Vec<_>>();
+  let type_params = match attrs.type_params {
+    Some(type_params) => {
+      if let Some(unknown) = type_params.iter().find(|param| !declared_params.contains(param)) {
+        return Err(syn::Error::new(unknown.span(), "unknown type parameter"));
+      }
+      type_params
+    }
+    None => declared_params.into_iter().cloned().collect(),
+  };
+
+  let address = match attrs.address {
+    Some(address) => {
+      check_address(&address)?;
+      quote! { #address }
+    }
+    None => quote! { package },
+  };
+
+  let ident = &input.ident;
+  let mut generics = input.generics.clone();
+  {
+    let where_clause = generics.make_where_clause();
+    // Type parameters that don't map to Move ones are not bound by `MoveType`, thus neither by `Serialize`.
+    where_clause
+      .predicates
+      .push(syn::parse_quote!(Self: ::iota_interaction::__private::Serialize));
+    for param in &type_params {
+      where_clause
+        .predicates
+        .push(syn::parse_quote!(#param: ::iota_interaction::MoveType));
+    }
+  }
+  let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+  let type_tag = if type_params.is_empty() {
+    quote! { format!("{}::{}::{}", #address, #module, #name) }
+  } else {
+    quote! {
+      format!(
+        "{}::{}::{}<{}>",
+        #address,
+        #module,
+        #name,
+        [#(<#type_params as ::iota_interaction::MoveType>::move_type(package).to_string()),*].join(", ")
+      )
+    }
+  };
+
+  Ok(quote! {
+    impl #impl_generics ::iota_interaction::MoveType for #ident #ty_generics #where_clause {
+      #[allow(unused_variables)]
+      fn move_type(package: ::iota_interaction::__private::ObjectId) -> ::iota_interaction::__private::TypeTag {
+        #type_tag
+          .parse()
+          .expect("valid Move type")
+      }
+    }
+  })
+}
+
+/// Makes sure `lit` is a valid Move identifier.
+fn check_identifier(lit: &LitStr) -> syn::Result<()> {
+  let value = lit.value();
+  let mut chars = value.chars();
+  let valid_start = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+  if valid_start && chars.all(|c| c.is_ascii_alphanumeric() || c == '_') && value != "_" {
+    Ok(())
+  } else {
+    Err(syn::Error::new(
+      lit.span(),
+      format!("`{value}` is not a valid Move identifier"),
+    ))
+  }
+}
+
+/// Makes sure `lit` is a valid hex-encoded address, e.g. `0x2`.
+fn check_address(lit: &LitStr) -> syn::Result<()> {
+  let value = lit.value();
+  let is_valid = value
+    .strip_prefix("0x")
+    .is_some_and(|hex| !hex.is_empty() && hex.len() <= 64 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+  if is_valid {
+    Ok(())
+  } else {
+    Err(syn::Error::new(lit.span(), format!("`{value}` is not a valid address")))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use proc_macro2::Span;
+  use syn::parse_quote;
+
+  use super::*;
+
+  fn expansion_error(input: DeriveInput) -> String {
+    expand_move_type(input).expect_err("invalid input").to_string()
+  }
+
+  #[test]
+  fn move_identifiers_are_checked() {
+    for valid in ["token", "Token", "_token", "token_2"] {
+      assert!(
+        check_identifier(&LitStr::new(valid, Span::call_site())).is_ok(),
+        "{valid}"
+      );
+    }
+    for invalid in ["", "_", "2token", "my-token", "token::Token"] {
+      assert!(
+        check_identifier(&LitStr::new(invalid, Span::call_site())).is_err(),
+        "{invalid}"
+      );
+    }
+  }
+
+  #[test]
+  fn addresses_are_checked() {
+    assert!(check_address(&LitStr::new("0x2", Span::call_site())).is_ok());
+    assert!(check_address(&LitStr::new(&format!("0x{}", "a".repeat(64)), Span::call_site())).is_ok());
+    let too_long = format!("0x{}", "a".repeat(65));
+    for invalid in ["2", "0x", "0xg", too_long.as_str()] {
+      assert!(
+        check_address(&LitStr::new(invalid, Span::call_site())).is_err(),
+        "{invalid}"
+      );
+    }
+  }
+
+  #[test]
+  fn invalid_inputs_do_not_compile() {
+    let cases: [(DeriveInput, &str); 7] = [
+      (parse_quote! { struct Token; }, "missing Move module"),
+      (
+        parse_quote! { #[move_type(module = "1token")] struct Token; },
+        "`1token` is not a valid Move identifier",
+      ),
+      (
+        parse_quote! { #[move_type(module = "token", name = "my-token")] struct Token; },
+        "`my-token` is not a valid Move identifier",
+      ),
+      (
+        parse_quote! { #[move_type(module = "token", address = "2")] struct Token; },
+        "`2` is not a valid address",
+      ),
+      (
+        parse_quote! { #[move_type(module = "token", type_params(U))] struct Token<T>(T); },
+        "unknown type parameter",
+      ),
+      (
+        parse_quote! { #[move_type(module = "token", package = "0x2")] struct Token; },
+        "unsupported `move_type` attribute",
+      ),
+      (
+        parse_quote! { #[move_type(module = "token")] union Token { a: u8 } },
+        "cannot be derived for unions",
+      ),
+    ];
+
+    for (input, expected_error) in cases {
+      let error = expansion_error(input);
+      assert!(
+        error.contains(expected_error),
+        "expected `{expected_error}`, got `{error}`"
+      );
+    }
+  }
+}
@@ -7,29 +7,29 @@ use std::option::Option;
 use std::result::Result;
 
 use async_trait::async_trait;
-use iota_interaction::apis::{CoinReadApi, EventApi, QuorumDriverApi, ReadApi};
+use iota_interaction::apis::{CoinReadApi, EventApi, GovernanceApi, QuorumDriverApi, ReadApi};
 use iota_interaction::error::IotaRpcResult;
 use iota_interaction::interaction_error::Error;
 use iota_interaction::rpc_types::{
-  Coin, CoinPage, DevInspectArgs, DevInspectResults, EventFilter, EventPage, IotaExecutionStatus, IotaObjectData,
-  IotaObjectDataOptions, IotaObjectResponse, IotaObjectResponseQuery, IotaPastObjectResponse,
-  IotaTransactionBlockEffects, IotaTransactionBlockEffectsAPI, IotaTransactionBlockEffectsV1,
-  IotaTransactionBlockEvents, IotaTransactionBlockResponse, IotaTransactionBlockResponseOptions, ObjectChange,
-  ObjectsPage,
+  Coin, CoinPage, DevInspectArgs, DevInspectResults, DryRunTransactionBlockResponse, DynamicFieldPage, EventFilter,
+  EventPage, IotaExecutionStatus, IotaGetPastObjectRequest, IotaObjectData, IotaObjectDataOptions, IotaObjectResponse,
+  IotaObjectResponseQuery, IotaPastObjectResponse, IotaTransactionBlockEffects, IotaTransactionBlockEffectsAPI,
+  IotaTransactionBlockEffectsV1, IotaTransactionBlockEvents, IotaTransactionBlockResponse,
+  IotaTransactionBlockResponseOptions, ObjectChange, ObjectsPage,
 };
 use iota_interaction::types::base_types::{IotaAddress, SequenceNumber};
-use iota_interaction::types::crypto::Signature;
 use iota_interaction::types::digests::TransactionDigest;
 use iota_interaction::types::dynamic_field::DynamicFieldName;
 use iota_interaction::types::event::EventID;
 use iota_interaction::types::iota_serde::BigInt;
 use iota_interaction::types::quorum_driver_types::ExecuteTransactionRequestType;
+use iota_interaction::types::signature::GenericSignature;
 use iota_interaction::types::transaction::{
   ProgrammableTransaction, Transaction, TransactionData, TransactionDataAPI as _, TransactionKind,
 };
 use iota_interaction::{
-  CoinReadTrait, EventTrait, IotaClient, IotaClientTrait, IotaKeySignature, IotaTransactionBlockResponseT,
-  OptionalSync, QuorumDriverTrait, ReadTrait,
+  fetch_in_chunks, CoinReadTrait, EventTrait, IotaClient, IotaClientTrait, IotaKeySignature,
+  IotaTransactionBlockResponseT, OptionalSync, QuorumDriverTrait, ReadTrait,
 };
 use iota_sdk_types::ObjectId;
 use secret_storage::Signer;
@@ -143,11 +143,11 @@ impl QuorumDriverTrait for QuorumDriverAdapter<'_> {
   async fn execute_transaction_block(
     &self,
     tx_data: TransactionData,
-    signatures: Vec<Signature>,
+    signatures: Vec<GenericSignature>,
     options: Option<IotaTransactionBlockResponseOptions>,
     request_type: Option<ExecuteTransactionRequestType>,
   ) -> IotaRpcResult<IotaTransactionBlockResponseAdaptedTraitObj> {
-    let tx = Transaction::from_data(tx_data, signatures);
+    let tx = Transaction::from_generic_sig_data(tx_data, signatures);
     let response = self
       .api
       .execute_transaction_block(tx, options.unwrap_or_default(), request_type)
@@ -158,6 +158,7 @@ impl QuorumDriverTrait for QuorumDriverAdapter<'_> {
 
 pub struct ReadAdapter<'a> {
   api: &'a ReadApi,
+  governance_api: &'a GovernanceApi,
 }
 
 #[async_trait::async_trait()]
@@ -193,6 +194,15 @@ impl ReadTrait for ReadAdapter<'_> {
       .await
   }
 
+  async fn get_dynamic_fields(
+    &self,
+    parent_object_id: ObjectId,
+    cursor: Option<ObjectId>,
+    limit: Option<usize>,
+  ) -> IotaRpcResult<DynamicFieldPage> {
+    self.api.get_dynamic_fields(parent_object_id, cursor, limit).await
+  }
+
   async fn get_object_with_options(
     &self,
     object_id: ObjectId,
@@ -201,6 +211,30 @@ impl ReadTrait for ReadAdapter<'_> {
     self.api.get_object_with_options(object_id, options).await
   }
 
+  async fn multi_get_object_with_options(
+    &self,
+    object_ids: Vec<ObjectId>,
+    options: IotaObjectDataOptions,
+  ) -> IotaRpcResult<Vec<IotaObjectResponse>> {
+    fetch_in_chunks(object_ids, |chunk| {
+      let options = options.clone();
+      async move { Ok(self.api.multi_get_object_with_options(chunk, options).await?) }
+    })
+    .await
+  }
+
+  async fn multi_get_past_objects(
+    &self,
+    past_objects: Vec<IotaGetPastObjectRequest>,
+    options: IotaObjectDataOptions,
+  ) -> IotaRpcResult<Vec<IotaPastObjectResponse>> {
+    fetch_in_chunks(past_objects, |chunk| {
+      let options = options.clone();
+      async move { Ok(self.api.try_multi_get_parsed_past_object(chunk, options).await?) }
+    })
+    .await
+  }
+
   async fn get_owned_objects(
     &self,
     address: IotaAddress,
@@ -215,6 +249,16 @@ impl ReadTrait for ReadAdapter<'_> {
     self.api.get_reference_gas_price().await
   }
 
+  async fn get_current_epoch(&self) -> IotaRpcResult<u64> {
+    let system_state = self.governance_api.get_latest_iota_system_state().await?;
+    Ok(system_state.epoch)
+  }
+
+  async fn get_protocol_version(&self) -> IotaRpcResult<u64> {
+    let protocol_config = self.api.get_protocol_config(None).await?;
+    Ok(protocol_config.protocol_version.as_u64())
+  }
+
   async fn get_transaction_with_options(
     &self,
     digest: TransactionDigest,
@@ -245,6 +289,10 @@ impl ReadTrait for ReadAdapter<'_> {
       .dev_inspect_transaction_block(sender_address, tx, gas_price, epoch, additional_args)
       .await
   }
+
+  async fn dry_run_transaction_block(&self, tx_data: TransactionData) -> IotaRpcResult<DryRunTransactionBlockResponse> {
+    self.api.dry_run_transaction_block(tx_data).await
+  }
 }
 
 pub struct CoinReadAdapter<'a> {
@@ -306,6 +354,7 @@ impl IotaClientTrait for IotaClientRustSdk {
   fn read_api(&self) -> Box<dyn ReadTrait<Error = Error, NativeResponse = IotaTransactionBlockResponse> + Send + '_> {
     Box::new(ReadAdapter {
       api: self.iota_client.read_api(),
+      governance_api: self.iota_client.governance_api(),
     })
   }
 
@@ -4,6 +4,7 @@
 use std::ops::{Deref, DerefMut};
 
 use iota_interaction::interaction_error::Error;
+use iota_interaction::move_call::TypedPtb;
 use iota_interaction::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
 use iota_interaction::{ProgrammableTransactionBcs, TransactionBuilderT};
 
@@ -18,6 +19,14 @@ impl TransactionBuilderRustSdk {
   }
 }
 
+impl TryFrom<TypedPtb> for TransactionBuilderRustSdk {
+  type Error = Error;
+
+  fn try_from(ptb: TypedPtb) -> Result<Self, Self::Error> {
+    ptb.into_inner().map(Self::new)
+  }
+}
+
 impl TransactionBuilderT for TransactionBuilderRustSdk {
   type Error = Error;
   type NativeTxBuilder = ProgrammableTransactionBuilder;
@@ -8,10 +8,11 @@ use async_trait::async_trait;
 use fastcrypto::traits::EncodeDecodeBase64;
 use iota_interaction::rpc_types::{IotaTransactionBlockEffects, IotaTransactionBlockEvents};
 use iota_interaction::types::crypto::Signature;
+use iota_interaction::types::signature::GenericSignature;
 use iota_interaction::types::transaction::{ProgrammableTransaction, TransactionData, TransactionDataAPI as _};
 use iota_interaction_ts::bindings::{
-  WasmIotaTransactionBlockEffects, WasmIotaTransactionBlockEvents, WasmIotaTransactionBlockResponse, WasmObjectRef,
-  WasmTransactionDataBuilder,
+  WasmGasCostSummary, WasmIotaTransactionBlockEffects, WasmIotaTransactionBlockEvents,
+  WasmIotaTransactionBlockResponse, WasmObjectRef, WasmTransactionDataBuilder, WasmTransactionSigner,
 };
 use iota_interaction_ts::core_client::{WasmCoreClient, WasmCoreClientReadOnly};
 use js_sys::{JsString, Reflect};
@@ -22,8 +23,11 @@ use wasm_bindgen_futures::JsFuture;
 use crate::bindings::core_client::{WasmManagedCoreClient, WasmManagedCoreClientReadOnly};
 use crate::bindings::wasm_error::{Result, WasmResult as _};
 use crate::core_client::CoreClientReadOnly;
+use crate::transaction::hooks::{ExecutionResponse, TransactionHook};
 use crate::transaction::transaction_builder::{MutGasDataRef, Transaction, TransactionBuilder};
-use crate::transaction::TransactionOutputInternal;
+use crate::transaction::{
+  DryRunOutput, RetryPolicy, TransactionBuilderState, TransactionEnvelope, TransactionOutputInternal,
+};
 
 #[wasm_bindgen]
 extern "C" {
@@ -114,6 +118,80 @@ impl Transaction for WasmTransaction {
   }
 }
 
+#[wasm_bindgen]
+extern "C" {
+  #[derive(Clone, Debug)]
+  #[wasm_bindgen(typescript_type = "TransactionHook")]
+  pub type WasmTransactionHook;
+
+  #[wasm_bindgen(method, catch, js_name = beforeBuild)]
+  pub async fn before_build(this: &WasmTransactionHook, tx_type: &str, programmable_tx: Vec<u8>) -> Result<JsValue>;
+
+  #[wasm_bindgen(method, catch, js_name = beforeSign)]
+  pub async fn before_sign(this: &WasmTransactionHook, tx_data: Vec<u8>) -> Result<JsValue>;
+
+  #[wasm_bindgen(method, catch, js_name = afterExecute)]
+  pub async fn after_execute(
+    this: &WasmTransactionHook,
+    response: &WasmIotaTransactionBlockResponse,
+  ) -> Result<JsValue>;
+
+  #[wasm_bindgen(method, catch, js_name = onApplyError)]
+  pub async fn on_apply_error(this: &WasmTransactionHook, error: String) -> Result<JsValue>;
+}
+
+impl WasmTransactionHook {
+  fn has_js_fn(&self, name: &str) -> bool {
+    Reflect::has(self, &JsValue::from_str(name)).unwrap_or(false)
+  }
+}
+
+// All hook methods are optional in TS, missing ones are skipped.
+// Errors thrown by the `afterExecute` and `onApplyError` callbacks are ignored, as the transaction
+// has already been executed when they are called.
+#[async_trait(?Send)]
+impl TransactionHook for WasmTransactionHook {
+  async fn before_build(
+    &self,
+    tx_type: &str,
+    programmable_tx: &ProgrammableTransaction,
+  ) -> StdResult<(), crate::Error> {
+    if !self.has_js_fn("beforeBuild") {
+      return Ok(());
+    }
+    let pt_bcs = bcs::to_bytes(programmable_tx).map_err(|e| crate::Error::TransactionBuildingFailed(e.to_string()))?;
+
+    Self::before_build(self, tx_type, pt_bcs)
+      .await
+      .map(|_| ())
+      .map_err(|e| crate::Error::TransactionVetoed(format!("{e:?}")))
+  }
+
+  async fn before_sign(&self, tx_data: &TransactionData) -> StdResult<(), crate::Error> {
+    if !self.has_js_fn("beforeSign") {
+      return Ok(());
+    }
+    let tx_data_bcs = bcs::to_bytes(tx_data).map_err(|e| crate::Error::TransactionBuildingFailed(e.to_string()))?;
+
+    Self::before_sign(self, tx_data_bcs)
+      .await
+      .map(|_| ())
+      .map_err(|e| crate::Error::TransactionVetoed(format!("{e:?}")))
+  }
+
+  async fn after_execute(&self, response: &ExecutionResponse) {
+    if self.has_js_fn("afterExecute") {
+      let _ = Self::after_execute(self, &response.as_native_response().response()).await;
+    }
+  }
+
+  async fn on_apply_error(&self, error: &crate::Error) {
+    if self.has_js_fn("onApplyError") {
+      let _ = Self::on_apply_error(self, error.to_string()).await;
+    }
+  }
+}
+
 #[wasm_bindgen(js_name = TransactionBuilder, skip_typescript)]
 pub struct WasmTransactionBuilder(pub(crate) TransactionBuilder<WasmTransaction>);
 
@@ -124,11 +202,30 @@ impl WasmTransactionBuilder {
     Self(TransactionBuilder::new(tx))
   }
 
+  /// Resumes a builder from the JSON encoded state returned by `toState`, wrapping `tx`.
+  #[wasm_bindgen(js_name = fromState)]
+  pub fn from_state(tx: WasmTransaction, state: &str) -> Result<Self> {
+    let state = TransactionBuilderState::from_json(state).wasm_result()?;
+    Ok(Self(TransactionBuilder::from_state(tx, state)))
+  }
+
+  /// Returns the JSON encoded state of this builder, see `fromState`.
+  #[wasm_bindgen(js_name = toState)]
+  pub fn to_state(&self) -> String {
+    self.0.state().to_json()
+  }
+
   #[wasm_bindgen(getter)]
   pub fn transaction(&self) -> WasmTransaction {
     self.0.as_ref().clone()
   }
 
+  pub async fn prepare(mut self, client: &WasmCoreClientReadOnly) -> Result<Self> {
+    let managed_client = WasmManagedCoreClientReadOnly::from_wasm(client)?;
+    self.0 = self.0.prepare(&managed_client).await.wasm_result()?;
+    Ok(self)
+  }
+
   #[wasm_bindgen(js_name = withGasPrice)]
   pub fn with_gas_price(mut self, price: u64) -> Self {
     self.0 = self.0.with_gas_price(price);
@@ -141,6 +238,34 @@ impl WasmTransactionBuilder {
     self
   }
 
+  #[wasm_bindgen(js_name = withExpiration)]
+  pub fn with_expiration(mut self, epoch: u64) -> Self {
+    self.0 = self.0.with_expiration(epoch);
+    self
+  }
+
+  #[wasm_bindgen(js_name = withExpirationInEpochs)]
+  pub fn with_expiration_in_epochs(mut self, epochs: u64) -> Self {
+    self.0 = self.0.with_expiration_in_epochs(epochs);
+    self
+  }
+
+  /// Submits the transaction up to `maxAttempts` times when its submission fails because of a transient
+  /// issue, waiting for it to be finalized between attempts.
+  #[wasm_bindgen(js_name = withRetryPolicy)]
+  pub fn with_retry_policy(mut self, max_attempts: u32) -> Self {
+    self.0 = self
+      .0
+      .with_retry_policy(RetryPolicy::new().with_max_attempts(max_attempts));
+    self
+  }
+
+  #[wasm_bindgen(js_name = withHook)]
+  pub fn with_hook(mut self, hook: WasmTransactionHook) -> Self {
+    self.0 = self.0.with_hook(hook);
+    self
+  }
+
   #[wasm_bindgen(js_name = withGasOwner)]
   pub fn with_gas_owner(mut self, owner: &str) -> Result<Self> {
     let owner = owner.parse().wasm_result()?;
@@ -235,6 +360,19 @@ impl WasmTransactionBuilder {
       .and_then(tx_parts_to_js)
   }
 
+  #[wasm_bindgen(js_name = dryRun, unchecked_return_type = "DryRunOutput<unknown>")]
+  pub async fn dry_run(self, client: &WasmCoreClientReadOnly) -> Result<WasmDryRunOutput> {
+    let managed_client = WasmManagedCoreClientReadOnly::from_wasm(client)?;
+    self.0.dry_run(&managed_client).await.wasm_result().map(Into::into)
+  }
+
+  #[wasm_bindgen(js_name = toEnvelope, unchecked_return_type = "TransactionEnvelope")]
+  pub async fn to_envelope(self, client: &WasmCoreClient) -> Result<WasmTransactionEnvelope> {
+    let managed_client = WasmManagedCoreClient::from_wasm(client)?;
+    let (envelope, _) = self.0.into_envelope(&managed_client).await.wasm_result()?;
+    Ok(WasmTransactionEnvelope(envelope))
+  }
+
   #[wasm_bindgen(js_name = buildAndExecute, unchecked_return_type = "TransactionOutput<unknown>")]
   pub async fn build_and_execute(self, client: &WasmCoreClient) -> Result<WasmTransactionOutput> {
     let managed_client = WasmManagedCoreClient::from_wasm(client)?;
@@ -247,6 +385,97 @@ impl WasmTransactionBuilder {
   }
 }
 
+#[wasm_bindgen(js_name = TransactionEnvelope, skip_typescript)]
+pub struct WasmTransactionEnvelope(pub(crate) TransactionEnvelope);
+
+#[wasm_bindgen(js_class = TransactionEnvelope)]
+impl WasmTransactionEnvelope {
+  #[wasm_bindgen(js_name = fromBcs)]
+  pub fn from_bcs(bytes: &[u8]) -> Result<Self> {
+    TransactionEnvelope::from_bcs(bytes).wasm_result().map(Self)
+  }
+
+  #[wasm_bindgen(js_name = fromBase64)]
+  pub fn from_base64(b64: &str) -> Result<Self> {
+    TransactionEnvelope::from_base64(b64).wasm_result().map(Self)
+  }
+
+  #[wasm_bindgen(js_name = fromJson)]
+  pub fn from_json(json: &str) -> Result<Self> {
+    TransactionEnvelope::from_json(json).wasm_result().map(Self)
+  }
+
+  #[wasm_bindgen(js_name = toBcs)]
+  pub fn to_bcs(&self) -> Vec<u8> {
+    self.0.to_bcs()
+  }
+
+  #[wasm_bindgen(js_name = toBase64)]
+  pub fn to_base64(&self) -> String {
+    self.0.to_base64()
+  }
+
+  #[wasm_bindgen(js_name = toJson)]
+  pub fn to_json(&self) -> String {
+    self.0.to_json()
+  }
+
+  #[wasm_bindgen(getter)]
+  pub fn version(&self) -> u8 {
+    self.0.version()
+  }
+
+  #[wasm_bindgen(getter, js_name = txData)]
+  pub fn tx_data(&self) -> Result<Vec<u8>> {
+    bcs::to_bytes(self.0.tx_data()).wasm_result()
+  }
+
+  #[wasm_bindgen(getter)]
+  pub fn signatures(&self) -> Vec<String> {
+    self.0.signatures().iter().map(|sig| sig.encode_base64()).collect()
+  }
+
+  #[wasm_bindgen(getter, js_name = requiredSigners)]
+  pub fn required_signers(&self) -> Vec<String> {
+    self.0.required_signers().iter().map(ToString::to_string).collect()
+  }
+
+  #[wasm_bindgen(getter)]
+  pub fn summary(&self) -> String {
+    self.0.summary().to_owned()
+  }
+
+  #[wasm_bindgen(js_name = missingSigners)]
+  pub fn missing_signers(&self) -> Vec<String> {
+    self.0.missing_signers().iter().map(ToString::to_string).collect()
+  }
+
+  #[wasm_bindgen(js_name = isComplete)]
+  pub fn is_complete(&self) -> bool {
+    self.0.is_complete()
+  }
+
+  #[wasm_bindgen(js_name = addSignature)]
+  pub fn add_signature(mut self, signature: &str) -> Result<Self> {
+    let signature = GenericSignature::decode_base64(signature)
+      .context("failed to decode b64 signature")
+      .wasm_result()?;
+    self.0.add_signature(signature).wasm_result()?;
+    Ok(self)
+  }
+
+  pub async fn sign(mut self, signer: &WasmTransactionSigner) -> Result<Self> {
+    self.0.sign(signer).await.wasm_result()?;
+    Ok(self)
+  }
+
+  #[wasm_bindgen(unchecked_return_type = "TransactionOutput<unknown>")]
+  pub async fn execute(self, tx: WasmTransaction, client: &WasmCoreClientReadOnly) -> Result<WasmTransactionOutput> {
+    let managed_client = WasmManagedCoreClientReadOnly::from_wasm(client)?;
+    self.0.execute(tx, &managed_client).await.wasm_result().map(Into::into)
+  }
+}
+
 #[wasm_bindgen(
   js_name = TransactionOutput,
   skip_typescript,
@@ -267,7 +496,38 @@ impl From<TransactionOutputInternal<JsValue>> for WasmTransactionOutput {
   }
 }
 
-fn tx_parts_to_js((tx_data, signatures, tx): (TransactionData, Vec<Signature>, WasmTransaction)) -> Result<JsValue> {
+#[wasm_bindgen(js_name = DryRunOutput, skip_typescript, inspectable, getter_with_clone)]
+pub struct WasmDryRunOutput {
+  pub effects: WasmIotaTransactionBlockEffects,
+  pub events: WasmIotaTransactionBlockEvents,
+  #[wasm_bindgen(js_name = gasCostSummary)]
+  pub gas_cost_summary: WasmGasCostSummary,
+  /// `undefined` when the simulated effects couldn't be applied.
+  pub output: JsValue,
+  /// The reason why the simulated effects couldn't be applied, if any.
+  pub error: Option<String>,
+}
+
+impl From<DryRunOutput<JsValue>> for WasmDryRunOutput {
+  fn from(value: DryRunOutput<JsValue>) -> Self {
+    let (output, error) = match value.output {
+      Ok(output) => (output, None),
+      Err(e) => (JsValue::UNDEFINED, Some(e.to_string())),
+    };
+
+    Self {
+      effects: WasmIotaTransactionBlockEffects::from(&value.effects),
+      events: WasmIotaTransactionBlockEvents::from(&value.events),
+      gas_cost_summary: WasmGasCostSummary::from(&value.gas_cost_summary),
+      output,
+      error,
+    }
+  }
+}
+
+fn tx_parts_to_js(
+  (tx_data, signatures, tx): (TransactionData, Vec<GenericSignature>, WasmTransaction),
+) -> Result<JsValue> {
   let tx_data_bcs = bcs::to_bytes(&tx_data)
     .wasm_result()
     .map(|bcs_bytes| js_sys::Uint8Array::from(bcs_bytes.as_slice()))?;
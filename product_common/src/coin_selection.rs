@@ -0,0 +1,184 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Strategies to select which coins should be used to cover a given amount, e.g. a transaction's gas budget.
+//!
+//! Selection strategies implement [CoinSelector] and only deal with the coins' balances, which makes them easy to
+//! compose and to test. Fetching the coins - through all result pages - is done by
+//! [CoreClientReadOnly::select_coins](crate::core_client::CoreClientReadOnly::select_coins).
+
+use std::fmt::Debug;
+
+use iota_interaction::{OptionalSend, OptionalSync};
+
+/// The type of IOTA coins.
+pub const IOTA_COIN_TYPE: &str = "0x2::iota::IOTA";
+
+/// The maximum number of coins that can be used as gas payment for a single transaction,
+/// as defined by the protocol's `max_gas_payment_objects` configuration.
+///
+/// `TransactionBuilder` uses the limit of the protocol version the network runs instead, whenever it is known - see
+/// `ProtocolLimits::max_gas_payment_objects`.
+pub const MAX_GAS_PAYMENT_OBJECTS: usize = 256;
+
+/// A strategy to select the coins that should be used to cover a given amount.
+pub trait CoinSelector: Debug + OptionalSend + OptionalSync {
+  /// Selects among the coins with the given `balances` the ones that cover `amount`, using
+  /// at most `max_coins` coins.
+  ///
+  /// Returns the indices of the selected coins, or `None` if `amount` cannot be covered.
+  fn select(&self, balances: &[u64], amount: u64, max_coins: usize) -> Option<Vec<usize>>;
+}
+
+/// Selects the coins with the largest balance first, until `amount` is covered.
+///
+/// This strategy minimizes the number of selected coins.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LargestFirst;
+
+impl CoinSelector for LargestFirst {
+  fn select(&self, balances: &[u64], amount: u64, max_coins: usize) -> Option<Vec<usize>> {
+    let mut indices = (0..balances.len()).collect::<Vec<_>>();
+    indices.sort_unstable_by(|a, b| balances[*b].cmp(&balances[*a]));
+
+    let mut selected = vec![];
+    let mut selected_balance = 0u64;
+    for idx in indices.into_iter().take(max_coins) {
+      if selected_balance >= amount && !selected.is_empty() {
+        break;
+      }
+      selected_balance = selected_balance.saturating_add(balances[idx]);
+      selected.push(idx);
+    }
+
+    (selected_balance >= amount && !selected.is_empty()).then_some(selected)
+  }
+}
+
+/// Selects the single coin with the smallest balance that covers `amount` on its own.
+///
+/// When no single coin is enough, this strategy falls back to [LargestFirst].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SmallestSufficient;
+
+impl CoinSelector for SmallestSufficient {
+  fn select(&self, balances: &[u64], amount: u64, max_coins: usize) -> Option<Vec<usize>> {
+    if max_coins == 0 {
+      return None;
+    }
+
+    balances
+      .iter()
+      .enumerate()
+      .filter(|(_, balance)| **balance >= amount)
+      .min_by_key(|(_, balance)| **balance)
+      .map(|(idx, _)| vec![idx])
+      .or_else(|| LargestFirst.select(balances, amount, max_coins))
+  }
+}
+
+/// Selects a single coin whose balance is exactly `amount`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ExactMatch;
+
+impl CoinSelector for ExactMatch {
+  fn select(&self, balances: &[u64], amount: u64, max_coins: usize) -> Option<Vec<usize>> {
+    if max_coins == 0 {
+      return None;
+    }
+
+    balances
+      .iter()
+      .position(|balance| *balance == amount)
+      .map(|idx| vec![idx])
+  }
+}
+
+/// Selects at most the given number of coins, with the largest balance first.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxCoins(pub usize);
+
+impl CoinSelector for MaxCoins {
+  fn select(&self, balances: &[u64], amount: u64, max_coins: usize) -> Option<Vec<usize>> {
+    LargestFirst.select(balances, amount, max_coins.min(self.0))
+  }
+}
+
+/// Extends the selection made by another [CoinSelector] with all coins whose balance is below a given threshold.
+///
+/// When used to select gas coins, all the selected coins are merged into the first one upon execution,
+/// thus consolidating dust coins at no additional cost.
+#[derive(Debug, Clone)]
+pub struct WithDustConsolidation<S> {
+  inner: S,
+  dust_threshold: u64,
+}
+
+impl<S> WithDustConsolidation<S> {
+  /// Consolidates all coins with a balance lower than `dust_threshold`
+  /// alongside the ones selected by `inner`.
+  pub fn new(inner: S, dust_threshold: u64) -> Self {
+    Self { inner, dust_threshold }
+  }
+}
+
+impl<S: CoinSelector> CoinSelector for WithDustConsolidation<S> {
+  fn select(&self, balances: &[u64], amount: u64, max_coins: usize) -> Option<Vec<usize>> {
+    let mut selected = self.inner.select(balances, amount, max_coins)?;
+    let dust = balances
+      .iter()
+      .enumerate()
+      .filter(|(idx, balance)| **balance < self.dust_threshold && !selected.contains(idx))
+      .map(|(idx, _)| idx)
+      .take(max_coins.saturating_sub(selected.len()))
+      .collect::<Vec<_>>();
+    selected.extend(dust);
+
+    Some(selected)
+  }
+}
+
+impl<S: CoinSelector + ?Sized> CoinSelector for Box<S> {
+  fn select(&self, balances: &[u64], amount: u64, max_coins: usize) -> Option<Vec<usize>> {
+    self.as_ref().select(balances, amount, max_coins)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const BALANCES: [u64; 6] = [5, 100, 1, 40, 60, 2];
+
+  #[test]
+  fn largest_first_uses_fewest_coins() {
+    assert_eq!(LargestFirst.select(&BALANCES, 150, 10), Some(vec![1, 4]));
+    assert_eq!(LargestFirst.select(&BALANCES, 150, 1), None);
+    assert_eq!(LargestFirst.select(&BALANCES, 1_000, 10), None);
+  }
+
+  #[test]
+  fn smallest_sufficient_prefers_single_coin() {
+    assert_eq!(SmallestSufficient.select(&BALANCES, 50, 10), Some(vec![4]));
+    assert_eq!(SmallestSufficient.select(&BALANCES, 120, 10), Some(vec![1, 4]));
+  }
+
+  #[test]
+  fn exact_match_requires_exact_balance() {
+    assert_eq!(ExactMatch.select(&BALANCES, 40, 10), Some(vec![3]));
+    assert_eq!(ExactMatch.select(&BALANCES, 41, 10), None);
+  }
+
+  #[test]
+  fn max_coins_limits_selection() {
+    assert_eq!(MaxCoins(2).select(&BALANCES, 160, 10), Some(vec![1, 4]));
+    assert_eq!(MaxCoins(2).select(&BALANCES, 161, 10), None);
+  }
+
+  #[test]
+  fn dust_is_consolidated_within_limits() {
+    let selector = WithDustConsolidation::new(LargestFirst, 10);
+    assert_eq!(selector.select(&BALANCES, 100, 10), Some(vec![1, 0, 2, 5]));
+    assert_eq!(selector.select(&BALANCES, 100, 2), Some(vec![1, 0]));
+  }
+}
@@ -1,22 +1,34 @@
 // Copyright 2020-2025 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use std::sync::Arc;
+
 use anyhow::{anyhow, Context};
 use async_trait::async_trait;
+use futures::{future, StreamExt as _, TryStreamExt as _};
 use iota_interaction::rpc_types::{
-  IotaData, IotaObjectData, IotaObjectDataFilter, IotaObjectDataOptions, IotaObjectResponseQuery, IotaParsedData,
-  OwnedObjectRef,
+  Coin, EventFilter, IotaData, IotaEvent, IotaObjectData, IotaObjectDataFilter, IotaObjectDataOptions,
+  IotaObjectResponse, IotaObjectResponseQuery, IotaRawData, OwnedObjectRef, Page,
 };
-use iota_interaction::types::base_types::{IotaAddress, ObjectRef};
+use iota_interaction::types::base_types::{IotaAddress, ObjectRef, SequenceNumber};
 use iota_interaction::types::crypto::PublicKey;
-use iota_interaction::{IotaClientTrait, IotaKeySignature, MoveType};
+use iota_interaction::types::dynamic_field::{DynamicFieldInfo, DynamicFieldName};
+use iota_interaction::{IotaClientTrait, IotaKeySignature, MoveType, OptionalSend, OptionalSync};
 use iota_sdk_types::{ObjectId, StructTag};
 use secret_storage::Signer;
 use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 
+use crate::coin_selection::{CoinSelector, LargestFirst, IOTA_COIN_TYPE, MAX_GAS_PAYMENT_OBJECTS};
 use crate::iota_interaction_adapter::IotaClientAdapter;
 use crate::network_name::NetworkName;
+use crate::object_history::{ObjectHistoryCache, ObjectVersion};
+use crate::pagination::{boxed, paginate, PageStream, PaginationOptions};
 use crate::tf_components_registry;
+use crate::transaction::events::{decode_event, is_of_type, struct_tags};
+use crate::transaction::hooks::TransactionHook;
+use crate::transaction::object_arg_resolver::ObjectArgResolver;
+use crate::transaction::object_lock::ObjectLockManager;
 
 #[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
 #[cfg_attr(feature = "send-sync", async_trait)]
@@ -50,6 +62,30 @@ pub trait CoreClientReadOnly {
     vec![self.package_id()]
   }
 
+  /// Returns the [`TransactionHook`]s to run on every transaction built or executed through this client.
+  ///
+  /// Clients without hooks can rely on the default implementation.
+  fn transaction_hooks(&self) -> &[Arc<dyn TransactionHook>] {
+    &[]
+  }
+
+  /// Returns the [`ObjectLockManager`] used to lock the owned objects of the transactions built through this client,
+  /// if any.
+  ///
+  /// Clients that don't submit transactions concurrently can rely on the default implementation.
+  fn object_lock_manager(&self) -> Option<&ObjectLockManager> {
+    None
+  }
+
+  /// Returns an [`ObjectArgResolver`] that builds the transaction arguments of objects from their IDs, fetching
+  /// them through this client.
+  fn object_arg_resolver(&self) -> ObjectArgResolver<'_, Self>
+  where
+    Self: Sized,
+  {
+    ObjectArgResolver::new(self)
+  }
+
   /// Retrieves a _Move_ Object by its ID.
   ///
   /// This function parses the object ID and returns the corresponding object
@@ -62,23 +98,58 @@ pub trait CoreClientReadOnly {
   ///
   /// Returns `Ok(T)` if the object is found and successfully deserialized,
   /// or an error if the operation fails.
+  ///
+  /// The object is decoded from its BCS encoding - `T` mirroring the layout of the Move struct - falling back to its
+  /// JSON representation, see [`decode_move_object`].
   async fn get_object_by_id<T: DeserializeOwned>(&self, object_id: ObjectId) -> anyhow::Result<T> {
     self
       .client_adapter()
       .read_api()
-      .get_object_with_options(object_id, IotaObjectDataOptions::new().with_content())
+      .get_object_with_options(object_id, move_object_options())
       .await
       .context("lookup request failed")
       .and_then(|res| res.data.context("missing data in response"))
-      .and_then(|data| data.content.context("missing object content in data"))
-      .and_then(|content| content.try_into_move().context("not a move object"))
-      .and_then(|obj| {
-        serde_json::from_value(obj.fields.to_json_value())
-          .map_err(|err| anyhow!("failed to deserialize move object; {err}"))
-      })
+      .and_then(decode_move_object)
       .context("failed to get object by id")
   }
 
+  /// Retrieves the objects with the given IDs and decodes them as `T`, in the same order.
+  ///
+  /// Objects are fetched in as few requests as the node allows, and decoded like
+  /// [`CoreClientReadOnly::get_object_by_id`] does.
+  /// ## Errors
+  /// Fails if any of the objects doesn't exist or cannot be decoded as `T`.
+  async fn get_objects_by_ids<T: DeserializeOwned>(&self, object_ids: &[ObjectId]) -> anyhow::Result<Vec<T>> {
+    let responses = self
+      .client_adapter()
+      .read_api()
+      .multi_get_object_with_options(object_ids.to_vec(), move_object_options())
+      .await
+      .context("lookup request failed")?;
+
+    decode_move_objects(object_ids, responses)
+  }
+
+  /// Retrieves the object with the given ID as it was at `version`, and decodes it as `T`.
+  ///
+  /// Like [`CoreClientReadOnly::get_object_by_id`], the object is decoded from its BCS encoding, falling back to its
+  /// JSON representation.
+  async fn get_past_object_by_id<T: DeserializeOwned>(
+    &self,
+    object_id: ObjectId,
+    version: SequenceNumber,
+  ) -> anyhow::Result<T> {
+    self
+      .client_adapter()
+      .read_api()
+      .try_get_parsed_past_object(object_id, version, move_object_options())
+      .await
+      .context("lookup request failed")
+      .and_then(|res| res.into_object().map_err(|e| anyhow!("{e}")))
+      .and_then(decode_move_object)
+      .with_context(|| format!("failed to get object {object_id} at version {version}"))
+  }
+
   /// Retrieves an object's [`OwnedObjectRef`], if any.
   ///
   /// # Arguments
@@ -120,49 +191,17 @@ pub trait CoreClientReadOnly {
     T: MoveType + DeserializeOwned,
     P: Fn(&T) -> bool + Send,
   {
-    let filter = {
-      // We don't know the exact Move type of T, therefore we have to check for all
-      // possible ones by going through the package's history.
-      let struct_filters = self
-        .package_history()
-        .into_iter()
-        .map(|pkg| {
-          T::move_type(pkg)
-            .to_string()
-            .parse()
-            .expect("type tag is a valid struct tag")
-        })
-        .map(IotaObjectDataFilter::StructType)
-        .collect();
-
-      IotaObjectDataFilter::MatchAny(struct_filters)
-    };
-    let query = IotaObjectResponseQuery::new(Some(filter), Some(IotaObjectDataOptions::default().with_content()));
-    let mut cursor = None;
-    loop {
-      let mut page = self
-        .client_adapter()
-        .read_api()
-        .get_owned_objects(address, Some(query.clone()), cursor, Some(25))
-        .await?;
-      let maybe_obj = std::mem::take(&mut page.data)
-        .into_iter()
-        .filter_map(|res| res.data)
-        .filter_map(|data| data.content)
-        .filter_map(|obj_data| {
-          let IotaParsedData::MoveObject(move_object) = obj_data else {
-            unreachable!()
-          };
-          serde_json::from_value(move_object.fields.to_json_value()).ok()
-        })
-        .find(&predicate);
-      cursor = page.next_cursor;
-
-      if maybe_obj.is_some() {
-        return Ok(maybe_obj);
-      }
-      if !page.has_next_page {
-        break;
+    let query = IotaObjectResponseQuery::new(
+      Some(move_type_filter::<T>(&self.package_history())),
+      Some(move_object_options()),
+    );
+    let mut objects = self.raw_owned_objects_stream(address, query, PaginationOptions::new().with_page_size(25));
+    while let Some(data) = objects.try_next().await? {
+      // Objects that cannot be decoded as `T` are not the ones we are looking for.
+      if let Ok(object) = decode_move_object::<T>(data) {
+        if predicate(&object) {
+          return Ok(Some(object));
+        }
       }
     }
 
@@ -184,26 +223,49 @@ pub trait CoreClientReadOnly {
     owner: IotaAddress,
     balance: u64,
   ) -> anyhow::Result<Vec<ObjectRef>> {
-    let mut coins = self
-      .client_adapter()
-      .coin_read_api()
-      .get_coins(owner, Some(String::from("0x2::iota::IOTA")), None, None)
-      .await?
-      .data;
-    coins.sort_unstable_by_key(|coin| coin.balance);
-
-    let mut needed_coins = vec![];
-    let mut needed_coins_balance = 0;
-    while let Some(coin) = coins.pop() {
-      needed_coins_balance += coin.balance;
-      needed_coins.push(coin.object_ref());
-
-      if needed_coins_balance >= balance {
-        return Ok(needed_coins);
-      }
-    }
+    self
+      .select_coins(owner, IOTA_COIN_TYPE, balance, MAX_GAS_PAYMENT_OBJECTS, &LargestFirst)
+      .await
+  }
+
+  /// Retrieves all the coins of type `coin_type` owned by `owner`, going through all result pages.
+  async fn get_all_coins(&self, owner: IotaAddress, coin_type: &str) -> anyhow::Result<Vec<Coin>> {
+    self
+      .coins_stream(owner, Some(coin_type), PaginationOptions::new())
+      .try_collect()
+      .await
+  }
 
-    anyhow::bail!("address {owner} does not have enough coins to form a balance of {balance}");
+  /// Selects among the coins of type `coin_type` owned by `owner` the ones that cover `amount`, according to
+  /// `selector`.
+  ///
+  /// # Arguments
+  ///
+  /// * `owner` - The address of the owner of the coins.
+  /// * `coin_type` - The type of the coins, e.g. [`IOTA_COIN_TYPE`].
+  /// * `amount` - The balance the selected coins must cover.
+  /// * `max_coins` - The maximum number of coins that can be selected.
+  /// * `selector` - The [`CoinSelector`] used to pick the coins.
+  async fn select_coins<S>(
+    &self,
+    owner: IotaAddress,
+    coin_type: &str,
+    amount: u64,
+    max_coins: usize,
+    selector: &S,
+  ) -> anyhow::Result<Vec<ObjectRef>>
+  where
+    S: CoinSelector + ?Sized,
+  {
+    let coins = self.get_all_coins(owner, coin_type).await?;
+    let balances = coins.iter().map(|coin| coin.balance).collect::<Vec<_>>();
+    let selected = selector.select(&balances, amount, max_coins).with_context(|| {
+      format!(
+        "address {owner} does not have enough coins to form a balance of {amount} using at most {max_coins} coins"
+      )
+    })?;
+
+    Ok(selected.into_iter().map(|idx| coins[idx].object_ref()).collect())
   }
 
   /// Queries the object owned by this sender address and returns the first one
@@ -217,32 +279,471 @@ pub trait CoreClientReadOnly {
   where
     P: Fn(&IotaObjectData) -> bool + Send,
   {
-    let filter = IotaObjectResponseQuery::new_with_filter(IotaObjectDataFilter::StructType(tag));
+    let query = IotaObjectResponseQuery::new_with_filter(IotaObjectDataFilter::StructType(tag));
+    let mut objects = self.raw_owned_objects_stream(address, query, PaginationOptions::new());
+    while let Some(data) = objects.try_next().await? {
+      if predicate(&data) {
+        return Ok(Some(data.object_ref()));
+      }
+    }
 
-    let mut cursor = None;
-    loop {
-      let mut page = self
+    Ok(None)
+  }
+
+  /// Returns a stream over the objects of type `T` owned by `address`, decoded from their content.
+  ///
+  /// Objects of type `T` as defined by any version of this client's package are included, see
+  /// [`CoreClientReadOnly::package_history`].
+  fn owned_objects_stream<'a, T>(
+    &'a self,
+    address: IotaAddress,
+    options: PaginationOptions,
+  ) -> PageStream<'a, anyhow::Result<T>>
+  where
+    T: MoveType + DeserializeOwned + OptionalSend + 'a,
+    Self: OptionalSync,
+  {
+    let query = IotaObjectResponseQuery::new(
+      Some(move_type_filter::<T>(&self.package_history())),
+      Some(move_object_options()),
+    );
+    let objects = self
+      .raw_owned_objects_stream(address, query, options)
+      .map(|data| data.and_then(decode_move_object));
+
+    boxed(objects)
+  }
+
+  /// Returns a stream over the objects owned by `address` that match `query`.
+  fn raw_owned_objects_stream(
+    &self,
+    address: IotaAddress,
+    query: IotaObjectResponseQuery,
+    options: PaginationOptions,
+  ) -> PageStream<'_, anyhow::Result<IotaObjectData>>
+  where
+    Self: OptionalSync,
+  {
+    let objects = paginate(options, move |cursor, limit| {
+      let query = query.clone();
+      async move {
+        self
+          .client_adapter()
+          .read_api()
+          .get_owned_objects(address, Some(query), cursor, limit)
+          .await
+          .context("failed to fetch owned objects")
+      }
+    })
+    .try_filter_map(|response| future::ready(Ok(response.data)));
+
+    boxed(objects)
+  }
+
+  /// Returns a stream over the coins owned by `owner`, either of type `coin_type` or of any type.
+  fn coins_stream(
+    &self,
+    owner: IotaAddress,
+    coin_type: Option<&str>,
+    options: PaginationOptions,
+  ) -> PageStream<'_, anyhow::Result<Coin>>
+  where
+    Self: OptionalSync,
+  {
+    let coin_type = coin_type.map(str::to_owned);
+    let coins = paginate(options, move |cursor, limit| {
+      let coin_type = coin_type.clone();
+      async move {
+        self
+          .client_adapter()
+          .coin_read_api()
+          .get_coins(owner, coin_type, cursor, limit)
+          .await
+          .context("failed to fetch coins")
+      }
+    });
+
+    boxed(coins)
+  }
+
+  /// Returns a stream over the events of type `T` that match `filter`, decoded into `T`.
+  ///
+  /// Events of type `T` as defined by any version of this client's package are included, see
+  /// [`CoreClientReadOnly::package_history`]; all other events are skipped.
+  fn events_stream<'a, T>(
+    &'a self,
+    filter: EventFilter,
+    options: PaginationOptions,
+  ) -> PageStream<'a, anyhow::Result<T>>
+  where
+    T: MoveType + DeserializeOwned + OptionalSend + 'a,
+    Self: OptionalSync,
+  {
+    let type_tags = struct_tags::<T>(&self.package_history());
+    let events = self.raw_events_stream(filter, options).try_filter_map(move |event| {
+      let decoded = is_of_type(&event, &type_tags)
+        .then(|| decode_event::<T>(&event))
+        .transpose()
+        .map_err(anyhow::Error::from);
+      future::ready(decoded)
+    });
+
+    boxed(events)
+  }
+
+  /// Returns a stream over the events that match `filter`.
+  fn raw_events_stream(
+    &self,
+    filter: EventFilter,
+    options: PaginationOptions,
+  ) -> PageStream<'_, anyhow::Result<IotaEvent>>
+  where
+    Self: OptionalSync,
+  {
+    let descending_order = options.descending_order();
+    let events = paginate(options, move |cursor, limit| {
+      let filter = filter.clone();
+      async move {
+        self
+          .client_adapter()
+          .event_api()
+          .query_events(filter, cursor, limit, descending_order)
+          .await
+          .context("failed to fetch events")
+      }
+    });
+
+    boxed(events)
+  }
+
+  /// Returns the value of the dynamic field of `parent` whose name is `key`, if any.
+  ///
+  /// `key`'s Move type is looked up as defined by any version of this client's package, see
+  /// [`CoreClientReadOnly::package_history`]. Both dynamic fields and dynamic object fields are supported.
+  async fn get_dynamic_field<K, V>(&self, parent: ObjectId, key: K) -> anyhow::Result<Option<V>>
+  where
+    K: MoveType + Serialize + DeserializeOwned + OptionalSend,
+    V: DeserializeOwned,
+    Self: OptionalSync,
+  {
+    // Names are tried from the initial package version on, as Move types keep the ID of the package version that
+    // introduced them.
+    for name in dynamic_field_names(&self.package_history(), &key)? {
+      let response = self
         .client_adapter()
         .read_api()
-        .get_owned_objects(address, Some(filter.clone()), cursor, None)
-        .await?;
-      let obj_ref = std::mem::take(&mut page.data)
-        .into_iter()
-        .filter_map(|res| res.data)
-        .find(|obj| predicate(obj))
-        .map(|obj_data| obj_data.object_ref());
-      cursor = page.next_cursor;
-
-      if obj_ref.is_some() {
-        return Ok(obj_ref);
-      }
-      if !page.has_next_page {
-        break;
+        .get_dynamic_field_object_v2(parent, name, Some(IotaObjectDataOptions::default().with_bcs()))
+        .await
+        .context("failed to fetch dynamic field")?;
+      if let Some(data) = response.data {
+        return decode_dynamic_field::<K, V>(data).map(Some);
       }
     }
 
     Ok(None)
   }
+
+  /// Returns a stream over the dynamic fields of `parent`.
+  fn dynamic_fields_stream(
+    &self,
+    parent: ObjectId,
+    options: PaginationOptions,
+  ) -> PageStream<'_, anyhow::Result<DynamicFieldInfo>>
+  where
+    Self: OptionalSync,
+  {
+    let fields = paginate(options, move |cursor, limit| async move {
+      self
+        .client_adapter()
+        .read_api()
+        .get_dynamic_fields(parent, cursor, limit)
+        .await
+        .context("failed to fetch dynamic fields")
+    });
+
+    boxed(fields)
+  }
+
+  /// Returns a stream over the entries of the `0x2::table::Table<K, V>` with ID `table_id`.
+  ///
+  /// The entries of a page are fetched together, so a stream with a page size of `n` makes one request for `n`
+  /// dynamic fields plus one for every [`MULTI_GET_OBJECTS_LIMIT`](iota_interaction::MULTI_GET_OBJECTS_LIMIT) of
+  /// them.
+  fn table_entries<'a, K, V>(
+    &'a self,
+    table_id: ObjectId,
+    options: PaginationOptions,
+  ) -> PageStream<'a, anyhow::Result<(K, V)>>
+  where
+    K: DeserializeOwned + OptionalSend + 'a,
+    V: DeserializeOwned + OptionalSend + 'a,
+    Self: OptionalSync,
+  {
+    let entries = paginate(options, move |cursor, limit| async move {
+      let page = self
+        .client_adapter()
+        .read_api()
+        .get_dynamic_fields(table_id, cursor, limit)
+        .await
+        .context("failed to fetch table entries")?;
+
+      let field_ids = page.data.iter().map(|field| field.object_id).collect::<Vec<_>>();
+      let responses = self
+        .client_adapter()
+        .read_api()
+        .multi_get_object_with_options(field_ids.clone(), IotaObjectDataOptions::default().with_bcs())
+        .await
+        .context("failed to fetch table entries")?;
+
+      let mut data = Vec::with_capacity(field_ids.len());
+      for (field_id, response) in field_ids.into_iter().zip(responses) {
+        let field_data = response
+          .data
+          .with_context(|| format!("table entry {field_id} not found"))?;
+        data.push(decode_table_entry::<K, V>(field_data)?);
+      }
+
+      Ok(Page {
+        data,
+        next_cursor: page.next_cursor,
+        has_next_page: page.has_next_page,
+      })
+    });
+
+    boxed(entries)
+  }
+
+  /// Returns a stream over the versions of the object with ID `object_id`, decoded as `T`, from the current one back
+  /// to the one it was created - or last unwrapped - with.
+  ///
+  /// Past versions are fetched from the node, which may have pruned them; the stream ends with an error in that
+  /// case.
+  fn object_history<'a, T>(&'a self, object_id: ObjectId) -> PageStream<'a, anyhow::Result<ObjectVersion<T>>>
+  where
+    T: DeserializeOwned + OptionalSend + 'a,
+    Self: OptionalSync,
+  {
+    self.object_history_with_cache(object_id, ObjectHistoryCache::new())
+  }
+
+  /// Like [`CoreClientReadOnly::object_history`], reusing the transactions already fetched into `cache`.
+  fn object_history_with_cache<'a, T>(
+    &'a self,
+    object_id: ObjectId,
+    cache: ObjectHistoryCache,
+  ) -> PageStream<'a, anyhow::Result<ObjectVersion<T>>>
+  where
+    T: DeserializeOwned + OptionalSend + 'a,
+    Self: OptionalSync,
+  {
+    let versions = self.raw_object_history(object_id, cache).and_then(|data| {
+      let object_ref = data.object_ref();
+      let previous_transaction = data.previous_transaction;
+      future::ready(decode_move_object(data).map(|object| ObjectVersion {
+        object_ref,
+        previous_transaction,
+        object,
+      }))
+    });
+
+    boxed(versions)
+  }
+
+  /// Returns a stream over the versions of the object with ID `object_id`, from the current one back to the one it
+  /// was created - or last unwrapped - with.
+  fn raw_object_history(
+    &self,
+    object_id: ObjectId,
+    cache: ObjectHistoryCache,
+  ) -> PageStream<'_, anyhow::Result<IotaObjectData>>
+  where
+    Self: OptionalSync,
+  {
+    // The state is the version to fetch next: `Some(None)` for the current one, `None` once the object's first
+    // version has been yielded.
+    let versions = futures::stream::try_unfold(Some(None), move |next_version| {
+      let cache = cache.clone();
+      async move {
+        let Some(version) = next_version else {
+          return Ok(None);
+        };
+        let data = match version {
+          None => self
+            .client_adapter()
+            .read_api()
+            .get_object_with_options(object_id, move_object_options())
+            .await
+            .context("lookup request failed")?
+            .data
+            .with_context(|| format!("object {object_id} not found"))?,
+          Some(version) => self
+            .client_adapter()
+            .read_api()
+            .try_get_parsed_past_object(object_id, version, move_object_options())
+            .await
+            .context("lookup request failed")?
+            .into_object()
+            .map_err(|e| anyhow!("failed to get object {object_id} at version {version}; {e}"))?,
+        };
+
+        let previous_version = match data.previous_transaction {
+          Some(tx_digest) => cache.previous_version(self, object_id, tx_digest).await?,
+          None => None,
+        };
+
+        Ok(Some((data, previous_version.map(Some))))
+      }
+    });
+
+    boxed(versions)
+  }
+}
+
+/// The Move object `0x2::dynamic_field::Field<K, V>` that holds the value of a dynamic field.
+#[derive(Deserialize)]
+struct Field<K, V> {
+  #[allow(dead_code)]
+  id: ObjectId,
+  name: K,
+  value: V,
+}
+
+/// Returns the names of the dynamic field whose name is `key`, one for each distinct Move type `K` has across
+/// `package_history`, in the same order.
+fn dynamic_field_names<K: MoveType + Serialize>(
+  package_history: &[ObjectId],
+  key: &K,
+) -> anyhow::Result<Vec<DynamicFieldName>> {
+  let value = serde_json::to_value(key).context("failed to serialize dynamic field name")?;
+  let mut names: Vec<DynamicFieldName> = vec![];
+  for package in package_history {
+    let type_ = K::move_type(*package);
+    if names.iter().all(|name| name.type_ != type_) {
+      names.push(DynamicFieldName {
+        type_,
+        value: value.clone(),
+      });
+    }
+  }
+
+  Ok(names)
+}
+
+/// Decodes the value of a dynamic field - or of a dynamic object field - out of its object `data`.
+fn decode_dynamic_field<K, V>(data: IotaObjectData) -> anyhow::Result<V>
+where
+  K: DeserializeOwned,
+  V: DeserializeOwned,
+{
+  let Some(IotaRawData::MoveObject(object)) = data.bcs else {
+    anyhow::bail!("dynamic field {} is not a move object", data.object_id);
+  };
+  if is_dynamic_field(&object.type_.to_string()) {
+    object.deserialize::<Field<K, V>>().map(|field| field.value)
+  } else {
+    object.deserialize::<V>()
+  }
+  .map_err(|e| anyhow!("failed to deserialize dynamic field {}; {e}", data.object_id))
+}
+
+/// Decodes the name and value of a table entry out of the object `data` of its `0x2::dynamic_field::Field<K, V>`.
+fn decode_table_entry<K, V>(data: IotaObjectData) -> anyhow::Result<(K, V)>
+where
+  K: DeserializeOwned,
+  V: DeserializeOwned,
+{
+  let Some(IotaRawData::MoveObject(object)) = data.bcs else {
+    anyhow::bail!("table entry {} is not a move object", data.object_id);
+  };
+  let field = object
+    .deserialize::<Field<K, V>>()
+    .map_err(|e| anyhow!("failed to deserialize table entry {}; {e}", data.object_id))?;
+
+  Ok((field.name, field.value))
+}
+
+/// Returns whether `move_type` is an instance of `0x2::dynamic_field::Field`.
+fn is_dynamic_field(move_type: &str) -> bool {
+  let field = "0x2::dynamic_field::Field"
+    .parse::<StructTag>()
+    .expect("valid struct tag");
+  let base_type = move_type.split_once('<').map_or(move_type, |(base_type, _)| base_type);
+  base_type.parse::<StructTag>().is_ok_and(|base_type| base_type == field)
+}
+
+/// Returns a filter matching the Move type of `T` as defined by any package in `package_history`.
+fn move_type_filter<T: MoveType>(package_history: &[ObjectId]) -> IotaObjectDataFilter {
+  // We don't know the exact Move type of T, therefore we have to check for all
+  // possible ones by going through the package's history.
+  let struct_filters = package_history
+    .iter()
+    .map(|pkg| {
+      T::move_type(*pkg)
+        .to_string()
+        .parse()
+        .expect("type tag is a valid struct tag")
+    })
+    .map(IotaObjectDataFilter::StructType)
+    .collect();
+
+  IotaObjectDataFilter::MatchAny(struct_filters)
+}
+
+/// Returns the options to fetch an object with, in order to decode it through [`decode_move_object`].
+fn move_object_options() -> IotaObjectDataOptions {
+  IotaObjectDataOptions::bcs_lossless().with_content()
+}
+
+/// Decodes the objects with the given IDs out of the `responses` to a request for them, in the same order.
+fn decode_move_objects<T: DeserializeOwned>(
+  object_ids: &[ObjectId],
+  responses: Vec<IotaObjectResponse>,
+) -> anyhow::Result<Vec<T>> {
+  anyhow::ensure!(
+    responses.len() == object_ids.len(),
+    "requested {} objects but received {}",
+    object_ids.len(),
+    responses.len()
+  );
+
+  object_ids
+    .iter()
+    .zip(responses)
+    .map(|(object_id, response)| {
+      response
+        .data
+        .with_context(|| format!("object {object_id} not found"))
+        .and_then(decode_move_object)
+        .with_context(|| format!("failed to get object {object_id}"))
+    })
+    .collect()
+}
+
+/// Decodes a Move object from its BCS encoding, falling back to its JSON content.
+///
+/// Decoding from BCS requires `T` to mirror the layout of the Move struct - e.g. `u64`s as [u64], `Option`s as
+/// [Option] and `VecMap`s as a vector of entries. Types whose [Deserialize] implementation targets the JSON
+/// representation of the object are still decoded from its content, when `data` carries it.
+pub fn decode_move_object<T: DeserializeOwned>(data: IotaObjectData) -> anyhow::Result<T> {
+  let bcs_error = match data.bcs {
+    Some(IotaRawData::MoveObject(object)) => match object.deserialize::<T>() {
+      Ok(decoded) => return Ok(decoded),
+      Err(e) => Some(e),
+    },
+    Some(IotaRawData::Package(_)) => anyhow::bail!("object {} is not a move object", data.object_id),
+    None => None,
+  };
+
+  let object = data
+    .content
+    .context("missing object content in data")?
+    .try_into_move()
+    .context("not a move object")?;
+
+  serde_json::from_value(object.fields.to_json_value()).map_err(|json_error| match bcs_error {
+    Some(bcs_error) => anyhow!("failed to deserialize move object; BCS: {bcs_error}, JSON: {json_error}"),
+    None => anyhow!("failed to deserialize move object; {json_error}"),
+  })
 }
 
 #[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
@@ -258,3 +759,287 @@ pub trait CoreClient<S: Signer<IotaKeySignature>>: CoreClientReadOnly {
   /// Returns the bytes of the sender's public key.
   fn sender_public_key(&self) -> &PublicKey;
 }
+
+#[cfg(test)]
+mod tests {
+  use std::collections::BTreeMap;
+
+  use iota_interaction::rpc_types::{
+    IotaMoveStruct, IotaMoveValue, IotaParsedData, IotaParsedMoveObject, IotaRawMoveObject,
+  };
+  use iota_sdk_types::TypeTag;
+  use serde::Deserialize;
+
+  use super::*;
+  use crate::transaction::testing::{object_id, object_ref};
+
+  /// Mirrors the BCS layout of `0xaa::counter::Counter { id: UID, value: u64 }`.
+  #[derive(Debug, PartialEq, Deserialize)]
+  struct Counter {
+    id: ObjectId,
+    value: u64,
+  }
+
+  /// Targets the JSON representation of `0xaa::counter::Counter`, where `u64`s are strings.
+  #[derive(Debug, PartialEq, Deserialize)]
+  struct CounterJson {
+    value: String,
+  }
+
+  /// Matches neither the BCS layout nor the JSON representation of `0xaa::counter::Counter`.
+  #[derive(Debug, Deserialize)]
+  struct Flag {
+    #[allow(dead_code)]
+    flag: bool,
+  }
+
+  /// Returns counter [object_id]`(1)` with a value of 42, as returned by a node when asked for its BCS encoding, its
+  /// content, or both.
+  fn counter(with_bcs: bool, with_content: bool) -> IotaObjectData {
+    counter_data(1, 42, with_bcs, with_content)
+  }
+
+  /// Returns counter [object_id]`(byte)` with the given value, as returned by a node when asked for its BCS encoding,
+  /// its content, or both.
+  fn counter_data(byte: u8, value: u64, with_bcs: bool, with_content: bool) -> IotaObjectData {
+    let object_ref = object_ref(byte, 3);
+    let type_ = "0xaa::counter::Counter".parse::<StructTag>().unwrap();
+    let bcs = with_bcs.then(|| {
+      IotaRawData::MoveObject(IotaRawMoveObject {
+        type_: type_.clone(),
+        version: object_ref.version,
+        bcs_bytes: bcs::to_bytes(&(object_id(byte), value)).unwrap(),
+      })
+    });
+    let content = with_content.then(|| {
+      let fields = BTreeMap::from([
+        ("id".to_owned(), IotaMoveValue::UID { id: object_id(byte) }),
+        ("value".to_owned(), IotaMoveValue::String(value.to_string())),
+      ]);
+      IotaParsedData::MoveObject(IotaParsedMoveObject {
+        type_: type_.clone(),
+        fields: IotaMoveStruct::WithFields(fields),
+      })
+    });
+
+    IotaObjectData {
+      object_id: object_ref.object_id,
+      version: object_ref.version,
+      digest: object_ref.digest,
+      type_: None,
+      owner: None,
+      previous_transaction: None,
+      storage_rebate: None,
+      display: None,
+      content,
+      bcs,
+    }
+  }
+
+  #[test]
+  fn move_objects_are_decoded_from_bcs() {
+    let expected = Counter {
+      id: object_id(1),
+      value: 42,
+    };
+    assert_eq!(decode_move_object::<Counter>(counter(true, true)).unwrap(), expected);
+    assert_eq!(decode_move_object::<Counter>(counter(true, false)).unwrap(), expected);
+    // The JSON representation of a `u64` is a string.
+    assert!(decode_move_object::<Counter>(counter(false, true)).is_err());
+  }
+
+  #[test]
+  fn move_objects_are_decoded_from_json_as_a_fallback() {
+    let expected = CounterJson { value: "42".to_owned() };
+    assert_eq!(
+      decode_move_object::<CounterJson>(counter(true, true)).unwrap(),
+      expected
+    );
+    assert_eq!(
+      decode_move_object::<CounterJson>(counter(false, true)).unwrap(),
+      expected
+    );
+    assert!(decode_move_object::<CounterJson>(counter(true, false)).is_err());
+  }
+
+  #[test]
+  fn objects_are_decoded_in_the_requested_order() {
+    let responses = vec![
+      IotaObjectResponse::new_with_data(counter_data(2, 7, true, false)),
+      IotaObjectResponse::new_with_data(counter_data(1, 42, true, false)),
+    ];
+
+    let counters = decode_move_objects::<Counter>(&[object_id(2), object_id(1)], responses).unwrap();
+
+    assert_eq!(
+      counters,
+      vec![
+        Counter {
+          id: object_id(2),
+          value: 7
+        },
+        Counter {
+          id: object_id(1),
+          value: 42
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn missing_objects_are_reported() {
+    let responses = vec![
+      IotaObjectResponse::new_with_data(counter(true, false)),
+      IotaObjectResponse {
+        data: None,
+        error: None,
+      },
+    ];
+
+    let error = decode_move_objects::<Counter>(&[object_id(1), object_id(3)], responses).unwrap_err();
+
+    assert_eq!(error.to_string(), format!("failed to get object {}", object_id(3)));
+    assert!(format!("{error:#}").contains("not found"), "{error:#}");
+  }
+
+  #[test]
+  fn responses_must_match_the_requested_objects() {
+    let responses = vec![IotaObjectResponse::new_with_data(counter(true, false))];
+
+    let error = decode_move_objects::<Counter>(&[object_id(1), object_id(2)], responses).unwrap_err();
+
+    assert_eq!(error.to_string(), "requested 2 objects but received 1");
+  }
+
+  #[test]
+  fn decoding_errors_report_both_encodings() {
+    let error = decode_move_object::<Flag>(counter(true, true)).unwrap_err().to_string();
+    assert!(error.starts_with("failed to deserialize move object; BCS: "), "{error}");
+    assert!(error.contains(", JSON: "), "{error}");
+    assert!(error.contains("flag"), "{error}");
+
+    let error = decode_move_object::<Flag>(counter(false, true))
+      .unwrap_err()
+      .to_string();
+    assert!(!error.contains("BCS"), "{error}");
+  }
+
+  #[test]
+  fn dynamic_field_wrappers_are_recognized() {
+    assert!(is_dynamic_field("0x2::dynamic_field::Field<u64, bool>"));
+    assert!(is_dynamic_field(
+      "0x0000000000000000000000000000000000000000000000000000000000000002::dynamic_field::Field<0x2::object::ID, u8>"
+    ));
+    assert!(!is_dynamic_field("0x2::dynamic_object_field::Wrapper<u64>"));
+    assert!(!is_dynamic_field("0x2::coin::Coin<0x2::iota::IOTA>"));
+  }
+
+  /// Returns object [object_id]`(byte)` of the given Move type, as returned by a node when asked for its BCS encoding
+  /// only.
+  fn raw_object_data(byte: u8, move_type: &str, bcs_bytes: Vec<u8>) -> IotaObjectData {
+    IotaObjectData {
+      content: None,
+      bcs: Some(IotaRawData::MoveObject(IotaRawMoveObject {
+        type_: move_type.parse().unwrap(),
+        version: SequenceNumber::from_u64(3),
+        bcs_bytes,
+      })),
+      ..counter_data(byte, 0, false, false)
+    }
+  }
+
+  /// Returns the `0x2::dynamic_field::Field<String, u64>` named `name`, holding `value`.
+  fn string_to_u64_field(byte: u8, name: &str, value: u64) -> IotaObjectData {
+    raw_object_data(
+      byte,
+      "0x2::dynamic_field::Field<0x1::string::String, u64>",
+      bcs::to_bytes(&(object_id(byte), name, value)).unwrap(),
+    )
+  }
+
+  /// A dynamic field name whose Move type is defined by each version of the package.
+  #[derive(Serialize)]
+  struct Key {
+    name: String,
+  }
+
+  impl MoveType for Key {
+    fn move_type(package: ObjectId) -> TypeTag {
+      TypeTag::Struct(Box::new(format!("{package}::registry::Key").parse().unwrap()))
+    }
+  }
+
+  #[test]
+  fn dynamic_field_values_are_decoded() {
+    let value = decode_dynamic_field::<String, u64>(string_to_u64_field(5, "alice", 42)).unwrap();
+    assert_eq!(value, 42);
+
+    let error = decode_dynamic_field::<String, bool>(string_to_u64_field(5, "alice", 42))
+      .unwrap_err()
+      .to_string();
+    assert!(
+      error.starts_with(&format!("failed to deserialize dynamic field {}", object_id(5))),
+      "{error}"
+    );
+  }
+
+  #[test]
+  fn dynamic_object_field_values_are_decoded() {
+    let value = decode_dynamic_field::<String, Counter>(counter(true, false)).unwrap();
+    assert_eq!(
+      value,
+      Counter {
+        id: object_id(1),
+        value: 42
+      }
+    );
+  }
+
+  #[test]
+  fn dynamic_fields_without_bcs_are_rejected() {
+    let error = decode_dynamic_field::<String, CounterJson>(counter(false, true))
+      .unwrap_err()
+      .to_string();
+    assert_eq!(error, format!("dynamic field {} is not a move object", object_id(1)));
+  }
+
+  #[test]
+  fn table_entries_are_decoded() {
+    let entry = decode_table_entry::<String, u64>(string_to_u64_field(6, "bob", 7)).unwrap();
+    assert_eq!(entry, ("bob".to_owned(), 7));
+
+    let error = decode_table_entry::<u64, u64>(string_to_u64_field(6, "bob", 7)).unwrap_err();
+    assert!(
+      error.to_string().starts_with("failed to deserialize table entry"),
+      "{error}"
+    );
+
+    let error = decode_table_entry::<String, u64>(counter(false, true)).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      format!("table entry {} is not a move object", object_id(1))
+    );
+  }
+
+  #[test]
+  fn dynamic_field_names_are_looked_up_once_per_move_type() {
+    let packages = [object_id(0xaa), object_id(0xbb)];
+
+    let names = dynamic_field_names(&packages, &42u64).unwrap();
+    assert_eq!(names.len(), 1);
+    assert_eq!(names[0].type_, TypeTag::U64);
+    assert_eq!(names[0].value, serde_json::json!(42));
+
+    let key = Key {
+      name: "alice".to_owned(),
+    };
+    let names = dynamic_field_names(&packages, &key).unwrap();
+    assert_eq!(
+      names.iter().map(|name| name.type_.clone()).collect::<Vec<_>>(),
+      vec![Key::move_type(object_id(0xaa)), Key::move_type(object_id(0xbb))]
+    );
+    assert!(names
+      .iter()
+      .all(|name| name.value == serde_json::json!({ "name": "alice" })));
+  }
+}
@@ -44,6 +44,32 @@ pub enum Error {
   /// the expected result.
   #[error("transaction returned an unexpected response; {0}")]
   TransactionUnexpectedResponse(String),
+  /// The transaction exceeds a limit of the protocol the network runs, and would therefore be rejected.
+  #[cfg(feature = "transaction")]
+  #[error("transaction exceeds protocol limits; {0}")]
+  ProtocolLimitExceeded(crate::transaction::protocol_limits::ProtocolLimitViolation),
+  /// A transaction hook prevented the transaction from being built or signed.
+  #[error("transaction vetoed: {0}")]
+  TransactionVetoed(String),
+  /// The network rejected the transaction, which therefore was not executed.
+  #[error("transaction {digest} was rejected; {reason}")]
+  TransactionRejected {
+    /// The digest of the rejected transaction.
+    digest: iota_interaction::types::digests::TransactionDigest,
+    /// The reason the transaction was rejected for.
+    reason: String,
+  },
+  /// The transaction couldn't be confirmed as executed, nor as rejected. It might still be executed later on.
+  #[error("the outcome of transaction {digest} is unknown; {reason}")]
+  TransactionOutcomeUnknown {
+    /// The digest of the transaction, which can be used to query its status later on.
+    digest: iota_interaction::types::digests::TransactionDigest,
+    /// The last error encountered while submitting the transaction.
+    reason: String,
+  },
+  /// A workflow's progress could not be persisted or restored, or doesn't match the workflow being run.
+  #[error("workflow error: {0}")]
+  Workflow(String),
 
   /// Transaction specific error.
   #[error("Transaction specific error: {0}")]
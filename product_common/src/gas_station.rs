@@ -11,7 +11,7 @@ use fastcrypto::encoding::{Base64, Encoding as _};
 use fastcrypto::traits::EncodeDecodeBase64 as _;
 use iota_interaction::rpc_types::IotaTransactionBlockEffects;
 use iota_interaction::types::base_types::{IotaAddress, ObjectRef};
-use iota_interaction::types::crypto::Signature;
+use iota_interaction::types::signature::GenericSignature;
 use iota_interaction::types::transaction::TransactionData;
 use serde::{Deserialize, Deserializer, Serialize};
 
@@ -546,7 +546,7 @@ pub(crate) struct ExecuteTxResponse {
 pub(crate) async fn execute_sponsored_tx<H>(
   gas_station_url: &Url,
   tx_data: TransactionData,
-  sender_sig: Signature,
+  sender_sig: GenericSignature,
   reservation_id: u64,
   headers: HeaderMap,
   http_client: &H,
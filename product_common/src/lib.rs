@@ -4,6 +4,8 @@
 #[cfg(all(feature = "bindings", target_arch = "wasm32"))]
 pub mod bindings;
 #[cfg(feature = "core-client")]
+pub mod coin_selection;
+#[cfg(feature = "core-client")]
 pub mod core_client;
 pub mod error;
 #[cfg(feature = "gas-station")]
@@ -14,7 +16,11 @@ pub mod http_client;
 pub mod move_history_manager;
 pub mod network_name;
 pub mod object;
+#[cfg(feature = "core-client")]
+pub mod object_history;
 pub mod package_registry;
+#[cfg(feature = "core-client")]
+pub mod pagination;
 pub mod tf_components_registry;
 pub mod well_known_networks;
 
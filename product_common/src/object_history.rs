@@ -0,0 +1,189 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Walking through the past versions of an object.
+//!
+//! Every version of an object records the transaction that produced it, whose effects in turn record the version
+//! the object was modified at.
+//! [CoreClientReadOnly::object_history](crate::core_client::CoreClientReadOnly::object_history) follows this chain back
+//! to the object's creation, newest version first:
+//! ```ignore
+//! let mut history = client.object_history::<Token>(token_id);
+//! while let Some(ObjectVersion { object_ref, object, .. }) = history.try_next().await? {
+//!   println!("version {}: {object:?}", object_ref.version);
+//! }
+//! ```
+//! The effects of the transactions met along the way are kept in an [ObjectHistoryCache], which can be shared
+//! among the histories of objects that changed together - e.g. an object and the capabilities controlling it.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context as _;
+use iota_interaction::rpc_types::{IotaTransactionBlockEffectsAPI as _, IotaTransactionBlockResponseOptions};
+use iota_interaction::types::base_types::{ObjectRef, SequenceNumber};
+use iota_interaction::types::digests::TransactionDigest;
+use iota_interaction::{IotaClientTrait, IotaTransactionBlockResponseT as _, OptionalSync};
+use iota_sdk_types::ObjectId;
+
+use crate::core_client::CoreClientReadOnly;
+
+/// A past version of an object, as yielded by
+/// [CoreClientReadOnly::object_history](crate::core_client::CoreClientReadOnly::object_history).
+#[derive(Debug, Clone)]
+pub struct ObjectVersion<T> {
+  /// The reference to the object at this version.
+  pub object_ref: ObjectRef,
+  /// The transaction that produced this version.
+  pub previous_transaction: Option<TransactionDigest>,
+  /// The object's content at this version.
+  pub object: T,
+}
+
+/// The versions objects were modified at by the transactions fetched while walking through object histories.
+///
+/// Cloning an [ObjectHistoryCache] returns a handle to the same cache.
+#[derive(Debug, Clone, Default)]
+pub struct ObjectHistoryCache {
+  modified_at_versions: Arc<Mutex<HashMap<TransactionDigest, Vec<(ObjectId, SequenceNumber)>>>>,
+}
+
+impl ObjectHistoryCache {
+  /// Returns an empty cache.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns the number of transactions in the cache.
+  pub fn len(&self) -> usize {
+    self.lock().len()
+  }
+
+  /// Returns whether the cache is empty.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// Returns the version `object_id` was at before `tx_digest` modified it, or `None` if `tx_digest` created - or
+  /// unwrapped - it.
+  pub(crate) async fn previous_version<C>(
+    &self,
+    client: &C,
+    object_id: ObjectId,
+    tx_digest: TransactionDigest,
+  ) -> anyhow::Result<Option<SequenceNumber>>
+  where
+    C: CoreClientReadOnly + OptionalSync + ?Sized,
+  {
+    let cached = self.lock().get(&tx_digest).cloned();
+    let modified_at_versions = match cached {
+      Some(modified_at_versions) => modified_at_versions,
+      None => {
+        let response = client
+          .client_adapter()
+          .read_api()
+          .get_transaction_with_options(tx_digest, IotaTransactionBlockResponseOptions::new().with_effects())
+          .await
+          .with_context(|| format!("failed to fetch transaction {tx_digest}"))?;
+        let modified_at_versions = response
+          .effects()
+          .with_context(|| format!("missing effects of transaction {tx_digest}"))?
+          .modified_at_versions();
+        self.lock().insert(tx_digest, modified_at_versions.clone());
+
+        modified_at_versions
+      }
+    };
+
+    Ok(
+      modified_at_versions
+        .into_iter()
+        .find_map(|(id, version)| (id == object_id).then_some(version)),
+    )
+  }
+
+  fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<TransactionDigest, Vec<(ObjectId, SequenceNumber)>>> {
+    // The map is never left in an inconsistent state, a poisoned lock can be used as is.
+    self.modified_at_versions.lock().unwrap_or_else(|e| e.into_inner())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use futures::executor::block_on;
+
+  use super::*;
+  use crate::transaction::testing::{object_id, OfflineClient};
+
+  fn tx_digest(byte: u8) -> TransactionDigest {
+    TransactionDigest::new([byte; 32])
+  }
+
+  fn version(version: u64) -> SequenceNumber {
+    SequenceNumber::from_u64(version)
+  }
+
+  /// Returns a cache holding the transactions that created object [object_id]`(1)` - along with object
+  /// [object_id]`(2)` - and then modified it twice.
+  fn cache() -> ObjectHistoryCache {
+    let cache = ObjectHistoryCache::new();
+    cache.lock().extend([
+      (tx_digest(1), vec![]),
+      (
+        tx_digest(2),
+        vec![(object_id(1), version(1)), (object_id(2), version(1))],
+      ),
+      (tx_digest(3), vec![(object_id(1), version(2))]),
+    ]);
+
+    cache
+  }
+
+  #[test]
+  fn previous_versions_are_read_from_the_cache() {
+    let cache = cache();
+    let client = OfflineClient::new();
+
+    let previous_version = |object, tx| block_on(cache.previous_version(&client, object, tx)).unwrap();
+    assert_eq!(previous_version(object_id(1), tx_digest(3)), Some(version(2)));
+    assert_eq!(previous_version(object_id(1), tx_digest(2)), Some(version(1)));
+    assert_eq!(previous_version(object_id(2), tx_digest(2)), Some(version(1)));
+    // Objects a transaction didn't modify were created - or unwrapped - by it.
+    assert_eq!(previous_version(object_id(1), tx_digest(1)), None);
+    assert_eq!(previous_version(object_id(2), tx_digest(3)), None);
+  }
+
+  #[test]
+  fn histories_are_walked_back_to_the_creation() {
+    let cache = cache();
+    let client = OfflineClient::new();
+    // The transaction that produced each version of object 1.
+    let previous_transactions = HashMap::from([
+      (version(3), tx_digest(3)),
+      (version(2), tx_digest(2)),
+      (version(1), tx_digest(1)),
+    ]);
+
+    let mut versions = vec![];
+    let mut next_version = Some(version(3));
+    while let Some(current) = next_version {
+      versions.push(current);
+      let tx_digest = previous_transactions[&current];
+      next_version = block_on(cache.previous_version(&client, object_id(1), tx_digest)).unwrap();
+    }
+
+    assert_eq!(versions, vec![version(3), version(2), version(1)]);
+  }
+
+  #[test]
+  fn clones_share_the_cache() {
+    let cache = ObjectHistoryCache::new();
+    assert!(cache.is_empty());
+
+    let handle = cache.clone();
+    handle.lock().insert(tx_digest(1), vec![]);
+
+    assert_eq!(cache.len(), 1);
+    assert!(!cache.is_empty());
+  }
+}
@@ -0,0 +1,255 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! [Stream]s over paginated RPC results.
+//!
+//! A [Paginator] goes through the pages of a cursor-based RPC method - e.g. owned objects, coins or events - yielding
+//! their items one by one, and fetching the next page ahead of time when asked to. It's what the streams of
+//! [CoreClientReadOnly](crate::core_client::CoreClientReadOnly) - e.g.
+//! [owned_objects_stream](crate::core_client::CoreClientReadOnly::owned_objects_stream) - are built upon:
+//! ```ignore
+//! let tokens = client
+//!   .owned_objects_stream::<Token>(owner, PaginationOptions::new().with_prefetch(1))
+//!   .try_filter(|token| future::ready(token.is_active()))
+//!   .try_collect::<Vec<_>>()
+//!   .await?;
+//! ```
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::Stream;
+use iota_interaction::rpc_types::Page;
+
+/// The number of items per page a node returns when no page size is requested.
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// A boxed [Stream] as returned by paginated queries, [Send] when the `send-sync` feature is enabled.
+#[cfg(feature = "send-sync")]
+pub type PageStream<'a, T> = futures::stream::BoxStream<'a, T>;
+/// A boxed [Stream] as returned by paginated queries, [Send] when the `send-sync` feature is enabled.
+#[cfg(not(feature = "send-sync"))]
+pub type PageStream<'a, T> = futures::stream::LocalBoxStream<'a, T>;
+
+/// How a [Paginator] goes through the pages of a query.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PaginationOptions {
+  page_size: Option<usize>,
+  prefetch: usize,
+  descending_order: bool,
+}
+
+impl PaginationOptions {
+  /// Returns the default options: pages of the node's default size, fetched when needed, in ascending order.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the number of items requested per page.
+  pub fn with_page_size(mut self, page_size: usize) -> Self {
+    self.page_size = Some(page_size);
+    self
+  }
+
+  /// Sets the number of pages to keep buffered ahead of the consumer. With a prefetch of `1`, the next page is
+  /// requested as soon as the current one is received, instead of when its last item is consumed.
+  pub fn with_prefetch(mut self, pages: usize) -> Self {
+    self.prefetch = pages;
+    self
+  }
+
+  /// Goes through the results in descending order - i.e. the most recent first.
+  /// ## Notes
+  /// Only queries that support ordering - e.g. events - take this option into account.
+  pub fn with_descending_order(mut self) -> Self {
+    self.descending_order = true;
+    self
+  }
+
+  /// Returns the number of items requested per page, if set.
+  pub fn page_size(&self) -> Option<usize> {
+    self.page_size
+  }
+
+  /// Returns whether results are requested in descending order.
+  pub fn descending_order(&self) -> bool {
+    self.descending_order
+  }
+}
+
+/// A [Stream] over the items of all the pages of a cursor-based query, see [paginate].
+#[must_use = "streams do nothing unless polled"]
+pub struct Paginator<T, Cur, F, Fut> {
+  fetch_page: F,
+  page_size: Option<usize>,
+  prefetch_threshold: usize,
+  cursor: Option<Cur>,
+  buffer: VecDeque<T>,
+  in_flight: Option<Pin<Box<Fut>>>,
+  exhausted: bool,
+  error: Option<anyhow::Error>,
+}
+
+/// Returns a [Paginator] that calls `fetch_page` - with the cursor and size of the page to fetch - to go through
+/// the pages of a query.
+/// ## Notes
+/// The stream ends after yielding the first error returned by `fetch_page`, once the items of the pages fetched before
+/// it have been yielded.
+pub fn paginate<T, Cur, F, Fut>(options: PaginationOptions, fetch_page: F) -> Paginator<T, Cur, F, Fut>
+where
+  F: FnMut(Option<Cur>, Option<usize>) -> Fut,
+  Fut: Future<Output = anyhow::Result<Page<T, Cur>>>,
+{
+  Paginator {
+    fetch_page,
+    page_size: options.page_size,
+    prefetch_threshold: options.prefetch * options.page_size.unwrap_or(DEFAULT_PAGE_SIZE),
+    cursor: None,
+    buffer: VecDeque::new(),
+    in_flight: None,
+    exhausted: false,
+    error: None,
+  }
+}
+
+// No field is structurally pinned: the page being fetched is boxed.
+impl<T, Cur, F, Fut> Unpin for Paginator<T, Cur, F, Fut> {}
+
+impl<T, Cur, F, Fut> Stream for Paginator<T, Cur, F, Fut>
+where
+  F: FnMut(Option<Cur>, Option<usize>) -> Fut,
+  Fut: Future<Output = anyhow::Result<Page<T, Cur>>>,
+{
+  type Item = anyhow::Result<T>;
+
+  fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    let this = self.get_mut();
+    loop {
+      if let Some(page) = this.in_flight.as_mut() {
+        if let Poll::Ready(result) = page.as_mut().poll(cx) {
+          this.in_flight = None;
+          match result {
+            Ok(page) => {
+              // A page without a cursor cannot be followed, whatever it claims.
+              this.exhausted = !page.has_next_page || page.next_cursor.is_none();
+              this.cursor = page.next_cursor;
+              this.buffer.extend(page.data);
+            }
+            Err(e) => {
+              // The items already fetched are yielded before the error.
+              this.exhausted = true;
+              this.error = Some(e);
+            }
+          }
+        }
+      }
+
+      if this.in_flight.is_none() && !this.exhausted && this.buffer.len() <= this.prefetch_threshold {
+        let page = (this.fetch_page)(this.cursor.take(), this.page_size);
+        this.in_flight = Some(Box::pin(page));
+        continue;
+      }
+
+      if let Some(item) = this.buffer.pop_front() {
+        return Poll::Ready(Some(Ok(item)));
+      }
+      return match this.error.take() {
+        Some(e) => Poll::Ready(Some(Err(e))),
+        None if this.in_flight.is_none() => Poll::Ready(None),
+        None => Poll::Pending,
+      };
+    }
+  }
+}
+
+/// Boxes `stream` into a [PageStream].
+#[cfg(feature = "send-sync")]
+pub(crate) fn boxed<'a, S>(stream: S) -> PageStream<'a, S::Item>
+where
+  S: Stream + Send + 'a,
+{
+  futures::StreamExt::boxed(stream)
+}
+
+/// Boxes `stream` into a [PageStream].
+#[cfg(not(feature = "send-sync"))]
+pub(crate) fn boxed<'a, S>(stream: S) -> PageStream<'a, S::Item>
+where
+  S: Stream + 'a,
+{
+  futures::StreamExt::boxed_local(stream)
+}
+
+#[cfg(test)]
+mod tests {
+  use futures::executor::block_on;
+  use futures::{future, TryStreamExt as _};
+
+  use super::*;
+
+  fn page(data: Vec<u32>, next_cursor: Option<u32>) -> Page<u32, u32> {
+    Page {
+      has_next_page: next_cursor.is_some(),
+      data,
+      next_cursor,
+    }
+  }
+
+  #[test]
+  fn all_pages_are_yielded_in_order() {
+    let mut requested_cursors = vec![];
+    let items = block_on(
+      paginate(PaginationOptions::new().with_prefetch(1), |cursor, _| {
+        requested_cursors.push(cursor);
+        future::ready(Ok(match cursor {
+          None => page(vec![1, 2], Some(2)),
+          Some(2) => page(vec![3], Some(3)),
+          _ => page(vec![], None),
+        }))
+      })
+      .try_collect::<Vec<_>>(),
+    )
+    .unwrap();
+
+    assert_eq!(items, vec![1, 2, 3]);
+    assert_eq!(requested_cursors, vec![None, Some(2), Some(3)]);
+  }
+
+  #[test]
+  fn stream_ends_after_an_error() {
+    let mut stream = paginate(PaginationOptions::new(), |cursor: Option<u32>, _| {
+      future::ready(match cursor {
+        None => Ok(page(vec![1], Some(1))),
+        _ => Err(anyhow::anyhow!("node unavailable")),
+      })
+    });
+
+    block_on(async {
+      assert_eq!(stream.try_next().await.unwrap(), Some(1));
+      assert!(stream.try_next().await.is_err());
+      assert!(futures::StreamExt::next(&mut stream).await.is_none());
+    });
+  }
+
+  #[test]
+  fn prefetched_items_are_yielded_before_an_error() {
+    let mut stream = paginate(
+      PaginationOptions::new().with_page_size(2).with_prefetch(1),
+      |cursor: Option<u32>, _| {
+        future::ready(match cursor {
+          None => Ok(page(vec![1, 2], Some(2))),
+          _ => Err(anyhow::anyhow!("node unavailable")),
+        })
+      },
+    );
+
+    block_on(async {
+      assert_eq!(stream.try_next().await.unwrap(), Some(1));
+      assert_eq!(stream.try_next().await.unwrap(), Some(2));
+      assert!(stream.try_next().await.is_err());
+      assert!(futures::StreamExt::next(&mut stream).await.is_none());
+    });
+  }
+}
@@ -0,0 +1,540 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Batched execution of several [Transaction]s within a single programmable transaction.
+//!
+//! Tuples of up to eight - possibly heterogeneous - [Transaction]s, as well as `Vec`s of
+//! transactions of the same type, are themselves [Transaction]s. Their programmable transactions
+//! are merged into one, which is executed once, and their off-chain effects are applied in order
+//! on the shared transaction effects.
+//!
+//! ## Example
+//! ```ignore
+//! let TransactionOutput { output: (a_out, b_out), .. } = TransactionBuilder::new((tx_a, tx_b))
+//!   .build_and_execute(&client)
+//!   .await?;
+//! ```
+
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use iota_interaction::rpc_types::{IotaTransactionBlockEffects, IotaTransactionBlockEvents};
+use iota_interaction::types::transaction::{CallArg, Command, ProgrammableTransaction};
+use iota_interaction::{OptionalSend, OptionalSync};
+use iota_sdk_types::{Argument, ObjectId};
+
+use super::Transaction;
+use crate::core_client::CoreClientReadOnly;
+use crate::Error;
+
+/// Merges `pts` into a single [ProgrammableTransaction] that executes all their commands in order.
+///
+/// Inputs and results referenced by each transaction's commands are re-indexed to point to
+/// their position in the merged transaction. Object inputs that are shared across transactions
+/// are only added once: a shared object is accessed mutably if any transaction does so.
+/// ## Errors
+/// Fails if transactions use the same object in incompatible ways - e.g. one owns it while another receives it,
+/// or they reference different versions of it - or if a transaction uses an object a previous transaction
+/// consumed, by merging it into another coin or by transferring it.
+pub fn merge_programmable_transactions(
+  pts: impl IntoIterator<Item = ProgrammableTransaction>,
+) -> Result<ProgrammableTransaction, Error> {
+  let mut inputs: Vec<CallArg> = vec![];
+  let mut commands: Vec<Command> = vec![];
+  // The merged inputs consumed by the transactions merged so far.
+  let mut consumed_inputs: HashSet<u16> = HashSet::new();
+
+  for ProgrammableTransaction {
+    inputs: pt_inputs,
+    commands: pt_commands,
+  } in pts
+  {
+    // Maps the index of each of this transaction's inputs to its index in the merged transaction.
+    let mut input_map = Vec::with_capacity(pt_inputs.len());
+    for input in pt_inputs {
+      let existing_idx = match input_object_id(&input) {
+        Some(object_id) => inputs
+          .iter()
+          .position(|merged_input| input_object_id(merged_input) == Some(object_id)),
+        None => None,
+      };
+      let idx = match existing_idx {
+        Some(idx) => {
+          merge_object_input(&mut inputs[idx], input)?;
+          idx
+        }
+        None => {
+          inputs.push(input);
+          inputs.len() - 1
+        }
+      };
+      input_map.push(to_u16_index(idx, "inputs")?);
+    }
+
+    let command_offset = to_u16_index(commands.len(), "commands")?;
+    let offset_result = |idx: u16| idx.checked_add(command_offset).ok_or_else(|| too_many("commands"));
+    let merged_input = |idx: u16| {
+      input_map.get(idx as usize).copied().ok_or_else(|| {
+        Error::TransactionBuildingFailed(format!(
+          "batched transaction refers to input {idx} but only has {} inputs",
+          input_map.len()
+        ))
+      })
+    };
+    let first_command = commands.len();
+    for mut command in pt_commands {
+      for arg in command_arguments_mut(&mut command) {
+        *arg = match *arg {
+          Argument::Input(idx) => {
+            let idx = merged_input(idx)?;
+            if consumed_inputs.contains(&idx) {
+              return Err(Error::TransactionBuildingFailed(format!(
+                "batched transaction uses object {} after a previous transaction consumed it",
+                input_object_id(&inputs[idx as usize]).expect("object input")
+              )));
+            }
+            Argument::Input(idx)
+          }
+          Argument::Result(idx) => Argument::Result(offset_result(idx)?),
+          Argument::NestedResult(idx, nested_idx) => Argument::NestedResult(offset_result(idx)?, nested_idx),
+          gas_coin => gas_coin,
+        };
+      }
+      commands.push(command);
+    }
+    consumed_inputs.extend(commands[first_command..].iter().flat_map(consumed_inputs_of));
+  }
+  to_u16_index(commands.len(), "commands")?;
+
+  Ok(ProgrammableTransaction { inputs, commands })
+}
+
+/// Returns the ID of the object `input` refers to, if any.
+fn input_object_id(input: &CallArg) -> Option<ObjectId> {
+  match input {
+    CallArg::Pure(_) => None,
+    CallArg::ImmutableOrOwned(object_ref) | CallArg::Receiving(object_ref) => Some(object_ref.object_id),
+    CallArg::Shared(shared) => Some(shared.object_id),
+  }
+}
+
+/// Merges `input` into `merged`, an input referring to the same object, keeping the strongest access of the two.
+fn merge_object_input(merged: &mut CallArg, input: CallArg) -> Result<(), Error> {
+  match (&mut *merged, &input) {
+    (CallArg::Shared(merged), CallArg::Shared(shared))
+      if merged.initial_shared_version == shared.initial_shared_version =>
+    {
+      merged.mutable |= shared.mutable;
+      Ok(())
+    }
+    (CallArg::ImmutableOrOwned(merged), CallArg::ImmutableOrOwned(object_ref))
+    | (CallArg::Receiving(merged), CallArg::Receiving(object_ref))
+      if merged == object_ref =>
+    {
+      Ok(())
+    }
+    _ => Err(Error::TransactionBuildingFailed(format!(
+      "batched transactions use object {} in incompatible ways: {merged:?} and {input:?}",
+      input_object_id(&input).expect("object input")
+    ))),
+  }
+}
+
+fn too_many(what: &str) -> Error {
+  Error::TransactionBuildingFailed(format!(
+    "batched transaction has too many {what}; at most {} are allowed",
+    u16::MAX
+  ))
+}
+
+fn to_u16_index(idx: usize, what: &str) -> Result<u16, Error> {
+  u16::try_from(idx).map_err(|_| too_many(what))
+}
+
+/// Returns mutable references to all the [Argument]s used by `command`.
+fn command_arguments_mut(command: &mut Command) -> Vec<&mut Argument> {
+  match command {
+    Command::MoveCall(call) => call.arguments.iter_mut().collect(),
+    Command::TransferObjects(objects, address) => objects.iter_mut().chain(std::iter::once(address)).collect(),
+    Command::SplitCoins(coin, amounts) => std::iter::once(coin).chain(amounts.iter_mut()).collect(),
+    Command::MergeCoins(coin, coins) => std::iter::once(coin).chain(coins.iter_mut()).collect(),
+    Command::MakeMoveVec(_, elements) => elements.iter_mut().collect(),
+    Command::Upgrade(_, _, _, ticket) => vec![ticket],
+    Command::Publish(_, _) => vec![],
+  }
+}
+
+/// Returns the indexes of the inputs `command` consumes, i.e. that no later command can use.
+fn consumed_inputs_of(command: &Command) -> Vec<u16> {
+  let consumed = match command {
+    Command::MergeCoins(_, coins) => coins.as_slice(),
+    Command::TransferObjects(objects, _) => objects.as_slice(),
+    _ => &[],
+  };
+
+  consumed
+    .iter()
+    .filter_map(|arg| match arg {
+      Argument::Input(idx) => Some(*idx),
+      _ => None,
+    })
+    .collect()
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl<Tx> Transaction for Vec<Tx>
+where
+  Tx: Transaction + OptionalSend + OptionalSync,
+  Tx::Output: OptionalSend,
+{
+  type Error = Error;
+  type Output = Vec<Tx::Output>;
+
+  async fn build_programmable_transaction<C>(&self, client: &C) -> Result<ProgrammableTransaction, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let mut pts = Vec::with_capacity(self.len());
+    for tx in self {
+      let pt = tx
+        .build_programmable_transaction(client)
+        .await
+        .map_err(|e| Error::Transaction(Box::new(e)))?;
+      pts.push(pt);
+    }
+
+    merge_programmable_transactions(pts)
+  }
+
+  async fn apply<C>(self, effects: &mut IotaTransactionBlockEffects, client: &C) -> Result<Self::Output, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let mut outputs = Vec::with_capacity(self.len());
+    for tx in self {
+      let output = tx
+        .apply(effects, client)
+        .await
+        .map_err(|e| Error::Transaction(Box::new(e)))?;
+      outputs.push(output);
+    }
+
+    Ok(outputs)
+  }
+
+  async fn apply_with_events<C>(
+    self,
+    effects: &mut IotaTransactionBlockEffects,
+    events: &mut IotaTransactionBlockEvents,
+    client: &C,
+  ) -> Result<Self::Output, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let mut outputs = Vec::with_capacity(self.len());
+    for tx in self {
+      let output = tx
+        .apply_with_events(effects, events, client)
+        .await
+        .map_err(|e| Error::Transaction(Box::new(e)))?;
+      outputs.push(output);
+    }
+
+    Ok(outputs)
+  }
+}
+
+macro_rules! impl_transaction_for_tuple {
+  ($($tx:ident : $idx:tt),+) => {
+    #[cfg_attr(feature = "send-sync", async_trait)]
+    #[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+    impl<$($tx),+> Transaction for ($($tx,)+)
+    where
+      $(
+        $tx: Transaction + OptionalSend + OptionalSync,
+        $tx::Output: OptionalSend,
+      )+
+    {
+      type Error = Error;
+      type Output = ($($tx::Output,)+);
+
+      async fn build_programmable_transaction<C>(&self, client: &C) -> Result<ProgrammableTransaction, Self::Error>
+      where
+        C: CoreClientReadOnly + OptionalSync,
+      {
+        let pts = vec![$(
+          self
+            .$idx
+            .build_programmable_transaction(client)
+            .await
+            .map_err(|e| Error::Transaction(Box::new(e)))?,
+        )+];
+
+        merge_programmable_transactions(pts)
+      }
+
+      async fn apply<C>(
+        self,
+        effects: &mut IotaTransactionBlockEffects,
+        client: &C,
+      ) -> Result<Self::Output, Self::Error>
+      where
+        C: CoreClientReadOnly + OptionalSync,
+      {
+        Ok(($(
+          self
+            .$idx
+            .apply(effects, client)
+            .await
+            .map_err(|e| Error::Transaction(Box::new(e)))?,
+        )+))
+      }
+
+      async fn apply_with_events<C>(
+        self,
+        effects: &mut IotaTransactionBlockEffects,
+        events: &mut IotaTransactionBlockEvents,
+        client: &C,
+      ) -> Result<Self::Output, Self::Error>
+      where
+        C: CoreClientReadOnly + OptionalSync,
+      {
+        Ok(($(
+          self
+            .$idx
+            .apply_with_events(effects, events, client)
+            .await
+            .map_err(|e| Error::Transaction(Box::new(e)))?,
+        )+))
+      }
+    }
+  };
+}
+
+impl_transaction_for_tuple!(T1: 0, T2: 1);
+impl_transaction_for_tuple!(T1: 0, T2: 1, T3: 2);
+impl_transaction_for_tuple!(T1: 0, T2: 1, T3: 2, T4: 3);
+impl_transaction_for_tuple!(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4);
+impl_transaction_for_tuple!(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5);
+impl_transaction_for_tuple!(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6);
+impl_transaction_for_tuple!(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7);
+
+#[cfg(test)]
+mod tests {
+  use futures::executor::block_on;
+  use iota_interaction::types::base_types::{ObjectRef, SequenceNumber};
+  use iota_interaction::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+  use iota_interaction::types::transaction::SharedObjectRef;
+  use iota_interaction::types::IOTA_FRAMEWORK_PACKAGE_ID;
+  use iota_interaction::IotaTransactionBlockEffectsMutAPI as _;
+  use iota_sdk_types::Identifier;
+
+  use super::*;
+  use crate::transaction::testing::{address, effects, object_id, object_ref, owned_by, OfflineClient};
+
+  fn make_pt(value: u64) -> ProgrammableTransaction {
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let arg = ptb.pure(value).unwrap();
+    let res = ptb.programmable_move_call(
+      IOTA_FRAMEWORK_PACKAGE_ID,
+      Identifier::from_static("module"),
+      Identifier::from_static("first"),
+      vec![],
+      vec![arg],
+    );
+    ptb.programmable_move_call(
+      IOTA_FRAMEWORK_PACKAGE_ID,
+      Identifier::from_static("module"),
+      Identifier::from_static("second"),
+      vec![],
+      vec![res, Argument::NestedResult(0, 1)],
+    );
+    ptb.finish()
+  }
+
+  fn move_call_args(command: &Command) -> &[Argument] {
+    let Command::MoveCall(call) = command else {
+      panic!("expected a move call");
+    };
+    &call.arguments
+  }
+
+  #[test]
+  fn merged_transaction_arguments_are_reindexed() {
+    let merged = merge_programmable_transactions([make_pt(1), make_pt(2)]).unwrap();
+
+    assert_eq!(merged.inputs.len(), 2);
+    assert_eq!(merged.commands.len(), 4);
+    assert_eq!(move_call_args(&merged.commands[0]), &[Argument::Input(0)]);
+    assert_eq!(
+      move_call_args(&merged.commands[1]),
+      &[Argument::Result(0), Argument::NestedResult(0, 1)]
+    );
+    assert_eq!(move_call_args(&merged.commands[2]), &[Argument::Input(1)]);
+    assert_eq!(
+      move_call_args(&merged.commands[3]),
+      &[Argument::Result(2), Argument::NestedResult(2, 1)]
+    );
+  }
+
+  #[test]
+  fn arguments_referring_to_missing_inputs_are_rejected() {
+    let mut pt = make_pt(1);
+    pt.inputs.clear();
+
+    let result = merge_programmable_transactions([make_pt(2), pt]);
+
+    assert!(matches!(result, Err(Error::TransactionBuildingFailed(_))));
+  }
+
+  fn shared(byte: u8, mutable: bool) -> CallArg {
+    CallArg::Shared(SharedObjectRef {
+      object_id: object_id(byte),
+      initial_shared_version: SequenceNumber::from_u64(1),
+      mutable,
+    })
+  }
+
+  fn pt_with_inputs(inputs: Vec<CallArg>) -> ProgrammableTransaction {
+    ProgrammableTransaction {
+      inputs,
+      commands: vec![],
+    }
+  }
+
+  #[test]
+  fn object_inputs_are_merged_by_object_id() {
+    let merged = merge_programmable_transactions([
+      pt_with_inputs(vec![shared(1, false), CallArg::ImmutableOrOwned(object_ref(2, 3))]),
+      pt_with_inputs(vec![CallArg::ImmutableOrOwned(object_ref(2, 3)), shared(1, true)]),
+    ])
+    .unwrap();
+
+    assert_eq!(
+      merged.inputs,
+      vec![shared(1, true), CallArg::ImmutableOrOwned(object_ref(2, 3))]
+    );
+  }
+
+  #[test]
+  fn conflicting_object_inputs_are_rejected() {
+    let owned_and_received = merge_programmable_transactions([
+      pt_with_inputs(vec![CallArg::ImmutableOrOwned(object_ref(2, 3))]),
+      pt_with_inputs(vec![CallArg::Receiving(object_ref(2, 3))]),
+    ]);
+    assert!(owned_and_received.is_err());
+
+    let different_versions = merge_programmable_transactions([
+      pt_with_inputs(vec![CallArg::ImmutableOrOwned(object_ref(2, 3))]),
+      pt_with_inputs(vec![CallArg::ImmutableOrOwned(object_ref(2, 4))]),
+    ]);
+    assert!(different_versions.is_err());
+  }
+
+  fn merge_coins_pt(coin: ObjectRef, coins: &[ObjectRef]) -> ProgrammableTransaction {
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let coin = ptb.obj(CallArg::ImmutableOrOwned(coin)).unwrap();
+    let coins = coins
+      .iter()
+      .map(|&coin| ptb.obj(CallArg::ImmutableOrOwned(coin)).unwrap())
+      .collect();
+    ptb.command(Command::MergeCoins(coin, coins));
+    ptb.finish()
+  }
+
+  #[test]
+  fn objects_consumed_by_previous_transactions_are_rejected() {
+    let merged_twice = merge_programmable_transactions([
+      merge_coins_pt(object_ref(1, 1), &[object_ref(2, 1)]),
+      merge_coins_pt(object_ref(1, 1), &[object_ref(2, 1)]),
+    ]);
+    assert!(matches!(merged_twice, Err(Error::TransactionBuildingFailed(_))));
+
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    ptb.transfer_object(address(7), object_ref(1, 1)).unwrap();
+    let transferred_then_merged =
+      merge_programmable_transactions([ptb.finish(), merge_coins_pt(object_ref(1, 1), &[object_ref(2, 1)])]);
+    assert!(matches!(
+      transferred_then_merged,
+      Err(Error::TransactionBuildingFailed(_))
+    ));
+  }
+
+  #[test]
+  fn objects_kept_by_previous_transactions_can_be_used() {
+    let merged = merge_programmable_transactions([
+      merge_coins_pt(object_ref(1, 1), &[object_ref(2, 1)]),
+      merge_coins_pt(object_ref(1, 1), &[object_ref(3, 1)]),
+    ])
+    .unwrap();
+
+    assert_eq!(merged.inputs.len(), 3);
+    assert_eq!(
+      merged.commands[1],
+      Command::MergeCoins(Argument::Input(0), vec![Argument::Input(2)])
+    );
+  }
+
+  #[test]
+  fn result_indexes_overflowing_u16_are_rejected() {
+    let mut long_pt = make_pt(1);
+    long_pt.commands = vec![long_pt.commands[0].clone(); u16::MAX as usize];
+    let mut late_result_pt = make_pt(2);
+    let Command::MoveCall(call) = &mut late_result_pt.commands[1] else {
+      unreachable!()
+    };
+    call.arguments = vec![Argument::Result(5)];
+
+    assert!(merge_programmable_transactions([long_pt, late_result_pt]).is_err());
+  }
+
+  /// A transaction whose output is the first object left among the created ones.
+  struct TakeCreated;
+
+  #[cfg_attr(feature = "send-sync", async_trait)]
+  #[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+  impl Transaction for TakeCreated {
+    type Error = Error;
+    type Output = ObjectId;
+
+    async fn build_programmable_transaction<C>(&self, _client: &C) -> Result<ProgrammableTransaction, Self::Error>
+    where
+      C: CoreClientReadOnly + OptionalSync,
+    {
+      Ok(ProgrammableTransactionBuilder::new().finish())
+    }
+
+    async fn apply<C>(self, effects: &mut IotaTransactionBlockEffects, _client: &C) -> Result<Self::Output, Self::Error>
+    where
+      C: CoreClientReadOnly + OptionalSync,
+    {
+      Ok(effects.created_mut().remove(0).object_id())
+    }
+  }
+
+  fn created_objects_effects() -> IotaTransactionBlockEffects {
+    let owner = address(9);
+    effects(
+      vec![owned_by(owner, object_ref(1, 1)), owned_by(owner, object_ref(2, 1))],
+      vec![],
+      owned_by(owner, object_ref(3, 2)),
+    )
+  }
+
+  #[test]
+  fn vec_outputs_are_applied_in_order() {
+    let mut effects = created_objects_effects();
+    let outputs = block_on(vec![TakeCreated, TakeCreated].apply(&mut effects, &OfflineClient::new())).unwrap();
+
+    assert_eq!(outputs, vec![object_id(1), object_id(2)]);
+  }
+
+  #[test]
+  fn tuple_outputs_are_applied_in_order() {
+    let mut effects = created_objects_effects();
+    let outputs = block_on((TakeCreated, TakeCreated).apply(&mut effects, &OfflineClient::new())).unwrap();
+
+    assert_eq!(outputs, (object_id(1), object_id(2)));
+  }
+}
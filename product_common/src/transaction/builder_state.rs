@@ -0,0 +1,292 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Persistence of a [TransactionBuilder]'s progress.
+//!
+//! A [TransactionBuilderState] captures everything a [TransactionBuilder] collected so far - its programmable
+//! transaction, sender, gas information, expiration and signatures - allowing a partly prepared transaction to be
+//! saved and finished later on, possibly by another process:
+//! ```ignore
+//! // Build the programmable transaction once, so that all signatures are produced over the same transaction.
+//! let builder = TransactionBuilder::new(tx).prepare(&client).await?;
+//! store.save(builder.state().to_json())?;
+//!
+//! // Hours later, in another process.
+//! let state = TransactionBuilderState::from_json(&store.load()?)?;
+//! let output = TransactionBuilder::from_state(tx, state)
+//!   .with_signature(&client)
+//!   .await?
+//!   .build_and_execute(&client)
+//!   .await?;
+//! ```
+//! When `Tx` is serializable, a [TransactionBuilder] can also be serialized as a whole, alongside its state.
+//!
+//! Hooks, coin selectors, gas budget estimators and object lock managers are not part of the state, and must be
+//! set again on the restored builder.
+
+use iota_interaction::types::base_types::IotaAddress;
+use iota_interaction::types::signature::GenericSignature;
+use iota_interaction::types::transaction::ProgrammableTransaction;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::protocol_limits::ProtocolLimits;
+use super::retry::RetryPolicy;
+use super::transaction_builder::{PartialExpiration, PartialGasData, TransactionBuilder};
+use crate::Error;
+
+/// The version of the [TransactionBuilderState] format produced by this library.
+pub const TRANSACTION_BUILDER_STATE_VERSION: u8 = 1;
+
+/// A versioned, serializable snapshot of a [TransactionBuilder]'s progress, see [TransactionBuilder::state].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionBuilderState {
+  // Dev-comment: must remain the first field, as it's used to detect the format of BCS encoded states.
+  version: u8,
+  pub(super) programmable_tx: Option<ProgrammableTransaction>,
+  pub(super) sender: Option<IotaAddress>,
+  pub(super) gas: PartialGasData,
+  pub(super) expiration: PartialExpiration,
+  pub(super) retry_policy: RetryPolicy,
+  pub(super) protocol_limits: Option<ProtocolLimits>,
+  pub(super) validate_protocol_limits: bool,
+  pub(super) signatures: Vec<GenericSignature>,
+}
+
+impl TransactionBuilderState {
+  #[allow(clippy::too_many_arguments)]
+  pub(super) fn new(
+    programmable_tx: Option<ProgrammableTransaction>,
+    sender: Option<IotaAddress>,
+    gas: PartialGasData,
+    expiration: PartialExpiration,
+    retry_policy: RetryPolicy,
+    protocol_limits: Option<ProtocolLimits>,
+    validate_protocol_limits: bool,
+    signatures: Vec<GenericSignature>,
+  ) -> Self {
+    Self {
+      version: TRANSACTION_BUILDER_STATE_VERSION,
+      programmable_tx,
+      sender,
+      gas,
+      expiration,
+      retry_policy,
+      protocol_limits,
+      validate_protocol_limits,
+      signatures,
+    }
+  }
+
+  /// Returns the version of this state's format.
+  pub fn version(&self) -> u8 {
+    self.version
+  }
+
+  /// Returns the programmable transaction, if it has already been built.
+  pub fn programmable_tx(&self) -> Option<&ProgrammableTransaction> {
+    self.programmable_tx.as_ref()
+  }
+
+  /// Returns the sender of the transaction, if set.
+  pub fn sender(&self) -> Option<IotaAddress> {
+    self.sender
+  }
+
+  /// Returns the signatures collected so far.
+  pub fn signatures(&self) -> &[GenericSignature] {
+    &self.signatures
+  }
+
+  /// Encodes this state using BCS.
+  pub fn to_bcs(&self) -> Vec<u8> {
+    bcs::to_bytes(self).expect("builder state is BCS serializable")
+  }
+
+  /// Decodes a state out of its BCS encoding.
+  pub fn from_bcs(bytes: &[u8]) -> Result<Self, Error> {
+    let version = bytes
+      .first()
+      .copied()
+      .ok_or_else(|| Error::TransactionBuildingFailed("empty transaction builder state".to_owned()))?;
+    check_version(version)?;
+
+    bcs::from_bytes(bytes)
+      .map_err(|e| Error::TransactionBuildingFailed(format!("failed to decode transaction builder state: {e}")))
+  }
+
+  /// Encodes this state as a JSON string.
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).expect("builder state is JSON serializable")
+  }
+
+  /// Decodes a state out of its JSON encoding.
+  pub fn from_json(json: &str) -> Result<Self, Error> {
+    let state: Self = serde_json::from_str(json)
+      .map_err(|e| Error::TransactionBuildingFailed(format!("failed to decode transaction builder state: {e}")))?;
+    check_version(state.version)?;
+
+    Ok(state)
+  }
+}
+
+fn check_version(version: u8) -> Result<(), Error> {
+  if version != TRANSACTION_BUILDER_STATE_VERSION {
+    return Err(Error::TransactionBuildingFailed(format!(
+      "unsupported transaction builder state version {version}; expected version {TRANSACTION_BUILDER_STATE_VERSION}"
+    )));
+  }
+
+  Ok(())
+}
+
+#[derive(Serialize)]
+struct SerializedBuilderRef<'a, Tx> {
+  state: TransactionBuilderState,
+  tx: &'a Tx,
+}
+
+#[derive(Deserialize)]
+struct SerializedBuilder<Tx> {
+  state: TransactionBuilderState,
+  tx: Tx,
+}
+
+impl<Tx: Serialize> Serialize for TransactionBuilder<Tx> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    SerializedBuilderRef {
+      state: self.state(),
+      tx: self.as_ref(),
+    }
+    .serialize(serializer)
+  }
+}
+
+impl<'de, Tx: DeserializeOwned> Deserialize<'de> for TransactionBuilder<Tx> {
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let SerializedBuilder { state, tx } = SerializedBuilder::<Tx>::deserialize(deserializer)?;
+    check_version(state.version).map_err(serde::de::Error::custom)?;
+
+    Ok(TransactionBuilder::from_state(tx, state))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use futures::executor::block_on;
+  use iota_interaction::types::transaction::GasData;
+
+  use super::*;
+  use crate::core_client::CoreClient;
+  use crate::transaction::testing::{address, object_ref, Noop, OfflineClient};
+
+  fn state() -> TransactionBuilderState {
+    TransactionBuilderState::new(
+      None,
+      Some(IotaAddress::ZERO),
+      PartialGasData::default(),
+      PartialExpiration::InEpochs(2),
+      RetryPolicy::default(),
+      None,
+      true,
+      vec![],
+    )
+  }
+
+  #[test]
+  fn state_roundtrips_through_json_and_bcs() {
+    let from_json = TransactionBuilderState::from_json(&state().to_json()).unwrap();
+    assert_eq!(from_json.sender(), Some(IotaAddress::ZERO));
+    assert_eq!(from_json.retry_policy, RetryPolicy::default());
+
+    let from_bcs = TransactionBuilderState::from_bcs(&state().to_bcs()).unwrap();
+    assert!(matches!(from_bcs.expiration, PartialExpiration::InEpochs(2)));
+  }
+
+  #[test]
+  fn unknown_versions_are_rejected() {
+    let mut bytes = state().to_bcs();
+    bytes[0] = TRANSACTION_BUILDER_STATE_VERSION + 1;
+    assert!(TransactionBuilderState::from_bcs(&bytes).is_err());
+  }
+
+  /// A transaction whose serialized form doesn't match [Noop]'s.
+  #[derive(Debug, Serialize, Deserialize)]
+  struct Transfer {
+    amount: u64,
+  }
+
+  /// Returns a signed builder for which every part of the state has been set.
+  fn signed_builder() -> TransactionBuilder<Noop> {
+    let client = OfflineClient::new();
+    let limits = ProtocolLimits {
+      max_programmable_tx_commands: 1024,
+      max_pure_argument_size: 16 * 1024,
+      max_input_objects: 2048,
+      max_gas_payment_objects: 256,
+      max_tx_size_bytes: 128 * 1024,
+      max_type_argument_depth: 16,
+    };
+    let builder = TransactionBuilder::new(Noop)
+      .with_sender(client.sender_address())
+      .with_gas_data(GasData {
+        objects: vec![object_ref(9, 1)],
+        owner: address(2),
+        price: 1000,
+        budget: 5_000_000,
+      })
+      .with_expiration(7)
+      .with_retry_policy(RetryPolicy::new().with_max_attempts(3))
+      .with_protocol_limits(limits)
+      .without_protocol_limits_validation();
+
+    block_on(builder.with_signature(&client)).unwrap()
+  }
+
+  fn assert_same_state(restored: &TransactionBuilderState, original: &TransactionBuilderState) {
+    assert_eq!(restored.version, original.version);
+    assert_eq!(restored.programmable_tx, original.programmable_tx);
+    assert_eq!(restored.sender, original.sender);
+    assert_eq!(restored.gas, original.gas);
+    assert_eq!(restored.expiration, original.expiration);
+    assert_eq!(restored.retry_policy, original.retry_policy);
+    assert_eq!(restored.protocol_limits, original.protocol_limits);
+    assert_eq!(restored.validate_protocol_limits, original.validate_protocol_limits);
+    assert_eq!(restored.signatures, original.signatures);
+  }
+
+  #[test]
+  fn builders_resume_from_their_state() {
+    let builder = signed_builder();
+    let state = builder.state();
+    assert!(state.programmable_tx().is_some());
+    assert_eq!(state.signatures().len(), 1);
+
+    let restored = TransactionBuilder::from_state(Noop, builder.state());
+
+    assert_same_state(&restored.state(), &state);
+  }
+
+  #[test]
+  fn builders_roundtrip_through_json_and_bcs() {
+    let builder = signed_builder();
+
+    let from_json: TransactionBuilder<Noop> = serde_json::from_str(&serde_json::to_string(&builder).unwrap()).unwrap();
+    assert_same_state(&from_json.state(), &builder.state());
+
+    let from_bcs: TransactionBuilder<Noop> = bcs::from_bytes(&bcs::to_bytes(&builder).unwrap()).unwrap();
+    assert_same_state(&from_bcs.state(), &builder.state());
+  }
+
+  #[test]
+  fn builders_of_another_transaction_are_rejected() {
+    let builder = signed_builder();
+
+    let json = serde_json::to_string(&builder).unwrap();
+    assert!(serde_json::from_str::<TransactionBuilder<Transfer>>(&json).is_err());
+
+    let bytes = bcs::to_bytes(&builder).unwrap();
+    assert!(bcs::from_bytes::<TransactionBuilder<Transfer>>(&bytes).is_err());
+  }
+}
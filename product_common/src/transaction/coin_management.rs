@@ -0,0 +1,961 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Ready-made transactions to manage coins.
+//!
+//! [SplitCoins], [MergeCoins], [PayCoins] and [SendAll] work with coins of any type - [IOTA_COIN_TYPE] for IOTA - and
+//! output the coins they produced alongside their balance, while [TransferObjects] moves any owned object:
+//! ```ignore
+//! let coins = TransactionBuilder::new(SplitCoins::iota(owner, vec![1_000, 2_000]))
+//!   .build_and_execute(&client)
+//!   .await?
+//!   .output;
+//! assert_eq!(coins[1].balance, 2_000);
+//! ```
+//! Coins are taken out of the owner's coins rather than out of the transaction's gas coin, so that these
+//! transactions can be sponsored - e.g. by a gas station - and batched with other transactions. The owner must be
+//! the transaction's sender.
+//! ## Notes
+//! When the owner pays for gas, the IOTA coins used by a transaction cannot pay for its gas too. Transactions that use
+//! all of the owner's IOTA coins must therefore be told so - see [MergeCoins::using_gas_coin] and
+//! [SendAll::using_gas_coin].
+//!
+//! Each transaction selects the owner's coins when it is built, regardless of the other transactions it may be
+//! batched with: batching several of these transactions for the same owner and coin type makes them select the same
+//! coins. When a transaction consumes coins another one selected too, merging the batch - see
+//! [merge_programmable_transactions](super::batch::merge_programmable_transactions) - fails with
+//! [Error::TransactionBuildingFailed]. Pay several recipients at once with a single [PayCoins] instead.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use iota_interaction::rpc_types::{
+  Coin, IotaGetPastObjectRequest, IotaObjectDataOptions, IotaPastObjectResponse, IotaRawData,
+  IotaTransactionBlockEffects, IotaTransactionBlockEffectsAPI as _, OwnedObjectRef,
+};
+use iota_interaction::types::base_types::{IotaAddress, ObjectRef};
+use iota_interaction::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use iota_interaction::types::transaction::{CallArg, Command, ProgrammableTransaction};
+use iota_interaction::{IotaClientTrait, IotaTransactionBlockEffectsMutAPI as _, OptionalSync};
+use iota_sdk_types::{Argument, ObjectId, Owner, StructTag};
+use serde::Deserialize;
+
+use super::object_arg_resolver::{ObjectAccess, ObjectArgResolver};
+use super::Transaction;
+use crate::coin_selection::{LargestFirst, IOTA_COIN_TYPE};
+use crate::core_client::CoreClientReadOnly;
+use crate::Error;
+
+/// The maximum number of coins a single transaction takes as input.
+/// Owners with more coins than that can merge them over several transactions.
+pub const MAX_INPUT_COINS: usize = 500;
+
+/// A coin produced by a transaction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnedCoin {
+  /// The reference to the coin, after the transaction.
+  pub object_ref: ObjectRef,
+  /// The balance of the coin, after the transaction.
+  pub balance: u64,
+}
+
+/// The BCS layout of a `0x2::coin::Coin<T>`.
+#[derive(Deserialize)]
+pub(crate) struct CoinContents {
+  pub(crate) id: ObjectId,
+  pub(crate) balance: u64,
+}
+
+/// Splits coins of the given amounts out of the owner's coins, keeping them in the owner's possession.
+#[derive(Debug, Clone)]
+pub struct SplitCoins {
+  owner: IotaAddress,
+  coin_type: String,
+  amounts: Vec<u64>,
+}
+
+impl SplitCoins {
+  /// Splits coins of type `coin_type` - e.g. `0x2::iota::IOTA` - of the given amounts out of `owner`'s coins.
+  pub fn new(owner: IotaAddress, coin_type: impl Into<String>, amounts: Vec<u64>) -> Self {
+    Self {
+      owner,
+      coin_type: coin_type.into(),
+      amounts,
+    }
+  }
+
+  /// Splits IOTA coins of the given amounts out of `owner`'s coins.
+  pub fn iota(owner: IotaAddress, amounts: Vec<u64>) -> Self {
+    Self::new(owner, IOTA_COIN_TYPE, amounts)
+  }
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl Transaction for SplitCoins {
+  /// The new coins, in the order of their amounts.
+  type Output = Vec<OwnedCoin>;
+  type Error = Error;
+
+  async fn build_programmable_transaction<C>(&self, client: &C) -> Result<ProgrammableTransaction, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let payments = self
+      .amounts
+      .iter()
+      .map(|&amount| (self.owner, amount))
+      .collect::<Vec<_>>();
+    build_payment(client, self.owner, &self.coin_type, &payments).await
+  }
+
+  async fn apply<C>(self, effects: &mut IotaTransactionBlockEffects, client: &C) -> Result<Self::Output, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let payments = self
+      .amounts
+      .iter()
+      .map(|&amount| (self.owner, amount))
+      .collect::<Vec<_>>();
+    take_paid_coins(effects, client, &self.coin_type, &payments).await
+  }
+}
+
+/// Pays several recipients out of the owner's coins, each recipient receiving a new coin.
+#[derive(Debug, Clone)]
+pub struct PayCoins {
+  owner: IotaAddress,
+  coin_type: String,
+  payments: Vec<(IotaAddress, u64)>,
+}
+
+impl PayCoins {
+  /// Pays each recipient in `payments` the associated amount of coins of type `coin_type` out of `owner`'s coins.
+  pub fn new(owner: IotaAddress, coin_type: impl Into<String>, payments: Vec<(IotaAddress, u64)>) -> Self {
+    Self {
+      owner,
+      coin_type: coin_type.into(),
+      payments,
+    }
+  }
+
+  /// Pays each recipient in `payments` the associated amount of IOTA out of `owner`'s coins.
+  pub fn iota(owner: IotaAddress, payments: Vec<(IotaAddress, u64)>) -> Self {
+    Self::new(owner, IOTA_COIN_TYPE, payments)
+  }
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl Transaction for PayCoins {
+  /// The coins received by the recipients, in the order of the payments.
+  type Output = Vec<OwnedCoin>;
+  type Error = Error;
+
+  async fn build_programmable_transaction<C>(&self, client: &C) -> Result<ProgrammableTransaction, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    build_payment(client, self.owner, &self.coin_type, &self.payments).await
+  }
+
+  async fn apply<C>(self, effects: &mut IotaTransactionBlockEffects, client: &C) -> Result<Self::Output, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    take_paid_coins(effects, client, &self.coin_type, &self.payments).await
+  }
+}
+
+/// Merges all of the owner's coins of a type into a single coin.
+#[derive(Debug, Clone)]
+pub struct MergeCoins {
+  owner: IotaAddress,
+  coin_type: String,
+  use_gas_coin: bool,
+}
+
+impl MergeCoins {
+  /// Merges all of `owner`'s coins of type `coin_type` into its coin with the largest balance.
+  pub fn new(owner: IotaAddress, coin_type: impl Into<String>) -> Self {
+    Self {
+      owner,
+      coin_type: coin_type.into(),
+      use_gas_coin: false,
+    }
+  }
+
+  /// Merges all of `owner`'s IOTA coins into its coin with the largest balance.
+  pub fn iota(owner: IotaAddress) -> Self {
+    Self::new(owner, IOTA_COIN_TYPE)
+  }
+
+  /// Merges the IOTA coins into the transaction's gas coin, leaving out the coin with the largest balance so that it
+  /// can pay for gas.
+  ///
+  /// Required to merge all IOTA coins when the owner pays for gas. Must not be used on sponsored transactions, as
+  /// the gas coin of those belongs to the sponsor.
+  pub fn using_gas_coin(mut self) -> Self {
+    self.use_gas_coin = true;
+    self
+  }
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl Transaction for MergeCoins {
+  /// The coin all others were merged into.
+  type Output = OwnedCoin;
+  type Error = Error;
+
+  async fn build_programmable_transaction<C>(&self, client: &C) -> Result<ProgrammableTransaction, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let coins = owned_coins(client, self.owner, &self.coin_type).await?;
+    if coins.len() < 2 {
+      return Err(Error::TransactionBuildingFailed(format!(
+        "address {} has no {} coins to merge",
+        self.owner, self.coin_type
+      )));
+    }
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    merge_all(&mut ptb, &coins, &self.coin_type, self.use_gas_coin)?;
+
+    Ok(ptb.finish())
+  }
+
+  async fn apply<C>(self, effects: &mut IotaTransactionBlockEffects, client: &C) -> Result<Self::Output, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    take_largest_coin(effects, client, &self.coin_type, self.owner, self.use_gas_coin).await
+  }
+}
+
+/// Sends the owner's whole balance of a coin type to a recipient, as a single coin.
+#[derive(Debug, Clone)]
+pub struct SendAll {
+  owner: IotaAddress,
+  recipient: IotaAddress,
+  coin_type: String,
+  use_gas_coin: bool,
+}
+
+impl SendAll {
+  /// Sends all of `owner`'s coins of type `coin_type` to `recipient`, merged into a single coin.
+  pub fn new(owner: IotaAddress, recipient: IotaAddress, coin_type: impl Into<String>) -> Self {
+    Self {
+      owner,
+      recipient,
+      coin_type: coin_type.into(),
+      use_gas_coin: false,
+    }
+  }
+
+  /// Sends all of `owner`'s IOTA coins to `recipient`, merged into a single coin.
+  pub fn iota(owner: IotaAddress, recipient: IotaAddress) -> Self {
+    Self::new(owner, recipient, IOTA_COIN_TYPE)
+  }
+
+  /// Sends the transaction's gas coin, after merging all other IOTA coins into it - the coin with the largest balance
+  /// being left out to pay for gas.
+  ///
+  /// Required to send the whole IOTA balance, minus the gas fees, when the owner pays for gas. Must not be used on
+  /// sponsored transactions, as the gas coin of those belongs to the sponsor.
+  pub fn using_gas_coin(mut self) -> Self {
+    self.use_gas_coin = true;
+    self
+  }
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl Transaction for SendAll {
+  /// The coin received by the recipient.
+  type Output = OwnedCoin;
+  type Error = Error;
+
+  async fn build_programmable_transaction<C>(&self, client: &C) -> Result<ProgrammableTransaction, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let coins = owned_coins(client, self.owner, &self.coin_type).await?;
+    if coins.is_empty() && !self.use_gas_coin {
+      return Err(Error::TransactionBuildingFailed(format!(
+        "address {} has no {} coins to send",
+        self.owner, self.coin_type
+      )));
+    }
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let coin = merge_all(&mut ptb, &coins, &self.coin_type, self.use_gas_coin)?;
+    ptb.transfer_args(self.recipient, vec![coin]);
+
+    Ok(ptb.finish())
+  }
+
+  async fn apply<C>(self, effects: &mut IotaTransactionBlockEffects, client: &C) -> Result<Self::Output, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    take_largest_coin(effects, client, &self.coin_type, self.recipient, self.use_gas_coin).await
+  }
+}
+
+/// Transfers owned objects - coins included - to a recipient.
+#[derive(Debug, Clone)]
+pub struct TransferObjects {
+  objects: Vec<ObjectId>,
+  recipient: IotaAddress,
+}
+
+impl TransferObjects {
+  /// Transfers the objects with the given IDs to `recipient`.
+  pub fn new(objects: Vec<ObjectId>, recipient: IotaAddress) -> Self {
+    Self { objects, recipient }
+  }
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl Transaction for TransferObjects {
+  /// The references to the transferred objects, in the order they were given.
+  type Output = Vec<ObjectRef>;
+  type Error = Error;
+
+  async fn build_programmable_transaction<C>(&self, client: &C) -> Result<ProgrammableTransaction, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    if self.objects.is_empty() {
+      return Err(Error::TransactionBuildingFailed("no objects to transfer".to_owned()));
+    }
+
+    let mut resolver = ObjectArgResolver::new(client);
+    for &object_id in &self.objects {
+      resolver.add(object_id, ObjectAccess::Write);
+    }
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let mut objects = Vec::with_capacity(self.objects.len());
+    for (object_id, call_arg) in self.objects.iter().zip(resolver.resolve().await?.into_vec()) {
+      if matches!(call_arg, CallArg::Shared(_)) {
+        return Err(Error::TransactionBuildingFailed(format!(
+          "object {object_id} is shared and cannot be transferred"
+        )));
+      }
+      let object = ptb
+        .obj(call_arg)
+        .map_err(|e| Error::TransactionBuildingFailed(format!("invalid object {object_id}: {e}")))?;
+      objects.push(object);
+    }
+    ptb.transfer_args(self.recipient, objects);
+
+    Ok(ptb.finish())
+  }
+
+  async fn apply<C>(self, effects: &mut IotaTransactionBlockEffects, _client: &C) -> Result<Self::Output, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let mutated = effects.mutated_mut();
+    self
+      .objects
+      .into_iter()
+      .map(|object_id| {
+        let idx = mutated
+          .iter()
+          .position(|object| object.object_id() == object_id && object.owner == Owner::Address(self.recipient))
+          .ok_or_else(|| {
+            Error::TransactionUnexpectedResponse(format!(
+              "object {object_id} was not transferred to {}",
+              self.recipient
+            ))
+          })?;
+        Ok(mutated.remove(idx).reference)
+      })
+      .collect()
+  }
+}
+
+/// Builds a transaction paying `payments` out of `owner`'s coins of type `coin_type`.
+///
+/// The coins are selected out of all of `owner`'s coins, including those other transactions of the same batch use.
+async fn build_payment<C>(
+  client: &C,
+  owner: IotaAddress,
+  coin_type: &str,
+  payments: &[(IotaAddress, u64)],
+) -> Result<ProgrammableTransaction, Error>
+where
+  C: CoreClientReadOnly + OptionalSync,
+{
+  if payments.is_empty() {
+    return Err(Error::TransactionBuildingFailed("no amounts to pay".to_owned()));
+  }
+  let total = payments
+    .iter()
+    .try_fold(0u64, |total, &(_, amount)| total.checked_add(amount))
+    .ok_or_else(|| Error::TransactionBuildingFailed("the total amount to pay overflows".to_owned()))?;
+
+  let coins = client
+    .select_coins(owner, coin_type, total, MAX_INPUT_COINS, &LargestFirst)
+    .await
+    .map_err(|e| Error::TransactionBuildingFailed(format!("{e:#}")))?;
+  let (recipients, amounts) = payments.iter().copied().unzip();
+  let mut ptb = ProgrammableTransactionBuilder::new();
+  ptb
+    .pay(coins, recipients, amounts)
+    .map_err(|e| Error::TransactionBuildingFailed(format!("failed to pay {coin_type} coins: {e}")))?;
+
+  Ok(ptb.finish())
+}
+
+/// Returns `owner`'s coins of type `coin_type`, largest balance first, up to [MAX_INPUT_COINS].
+///
+/// These include the coins other transactions of the same batch use.
+async fn owned_coins<C>(client: &C, owner: IotaAddress, coin_type: &str) -> Result<Vec<Coin>, Error>
+where
+  C: CoreClientReadOnly + OptionalSync,
+{
+  let mut coins = client.get_all_coins(owner, coin_type).await.map_err(|e| {
+    Error::TransactionBuildingFailed(format!("failed to fetch the {coin_type} coins of {owner}: {e:#}"))
+  })?;
+  coins.sort_by(|a, b| b.balance.cmp(&a.balance));
+  coins.truncate(MAX_INPUT_COINS);
+
+  Ok(coins)
+}
+
+/// Merges `coins` - largest balance first - into a single coin, returning it.
+/// When `use_gas_coin` is set, all coins but the first are merged into the gas coin instead.
+fn merge_all(
+  ptb: &mut ProgrammableTransactionBuilder,
+  coins: &[Coin],
+  coin_type: &str,
+  use_gas_coin: bool,
+) -> Result<Argument, Error> {
+  if use_gas_coin && !is_iota(coin_type) {
+    return Err(Error::TransactionBuildingFailed(format!(
+      "the gas coin cannot be used for coins of type {coin_type}"
+    )));
+  }
+
+  let mut args = coins
+    .iter()
+    .map(|coin| ptb.obj(CallArg::ImmutableOrOwned(coin.object_ref())))
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| Error::TransactionBuildingFailed(format!("invalid coin: {e}")))?;
+  let primary = if use_gas_coin {
+    // The largest coin is left out of the transaction, to pay for its gas.
+    if !args.is_empty() {
+      args.remove(0);
+    }
+    Argument::GasCoin
+  } else {
+    args.remove(0)
+  };
+  if !args.is_empty() {
+    ptb.command(Command::MergeCoins(primary, args));
+  }
+
+  Ok(primary)
+}
+
+/// Removes from `effects` the coins created by paying `payments` and returns them, in the order of the payments.
+async fn take_paid_coins<C>(
+  effects: &mut IotaTransactionBlockEffects,
+  client: &C,
+  coin_type: &str,
+  payments: &[(IotaAddress, u64)],
+) -> Result<Vec<OwnedCoin>, Error>
+where
+  C: CoreClientReadOnly + OptionalSync,
+{
+  let (mut balances, unknown) = paid_coin_balances(effects.created(), payments);
+  if !unknown.is_empty() {
+    balances.extend(fetch_coin_balances(client, coin_type, &unknown).await?);
+  }
+
+  take_created_coins(effects.created_mut(), coin_type, payments, &balances)
+}
+
+/// Returns the balances of the coins created for the recipients of `payments` among `created`, as far as the
+/// payments tell them, along with the references to the coins whose balance they don't tell.
+///
+/// The created objects don't come in the order of the payments: a recipient's coins are told apart by the payments
+/// only when the recipient received as many objects as payments, all of the same amount.
+fn paid_coin_balances(
+  created: &[OwnedObjectRef],
+  payments: &[(IotaAddress, u64)],
+) -> (HashMap<ObjectId, u64>, Vec<ObjectRef>) {
+  let mut amounts = HashMap::<IotaAddress, Vec<u64>>::new();
+  for &(recipient, amount) in payments {
+    amounts.entry(recipient).or_default().push(amount);
+  }
+
+  let mut balances = HashMap::new();
+  let mut unknown = vec![];
+  for (recipient, amounts) in amounts {
+    let received = created
+      .iter()
+      .filter(|object| object.owner == Owner::Address(recipient))
+      .collect::<Vec<_>>();
+    let same_amount = amounts.iter().all(|&amount| amount == amounts[0]);
+    if received.len() == amounts.len() && same_amount {
+      balances.extend(received.iter().map(|object| (object.object_id(), amounts[0])));
+    } else {
+      unknown.extend(received.iter().map(|object| object.reference));
+    }
+  }
+
+  (balances, unknown)
+}
+
+/// Removes from `created` the coins paid to the recipients of `payments`, telling them apart by their `balances`,
+/// and returns them in the order of the payments.
+fn take_created_coins(
+  created: &mut Vec<OwnedObjectRef>,
+  coin_type: &str,
+  payments: &[(IotaAddress, u64)],
+  balances: &HashMap<ObjectId, u64>,
+) -> Result<Vec<OwnedCoin>, Error> {
+  payments
+    .iter()
+    .map(|&(recipient, amount)| {
+      let idx = created
+        .iter()
+        .position(|object| {
+          object.owner == Owner::Address(recipient) && balances.get(&object.object_id()) == Some(&amount)
+        })
+        .ok_or_else(|| {
+          Error::TransactionUnexpectedResponse(format!("no {coin_type} coin of {amount} was created for {recipient}"))
+        })?;
+      Ok(OwnedCoin {
+        object_ref: created.remove(idx).reference,
+        balance: amount,
+      })
+    })
+    .collect()
+}
+
+/// Removes from `effects` the mutated coin of type `coin_type` owned by `owner` with the largest balance, and
+/// returns it. The gas coin is considered too when `include_gas_coin` is set.
+async fn take_largest_coin<C>(
+  effects: &mut IotaTransactionBlockEffects,
+  client: &C,
+  coin_type: &str,
+  owner: IotaAddress,
+  include_gas_coin: bool,
+) -> Result<OwnedCoin, Error>
+where
+  C: CoreClientReadOnly + OptionalSync,
+{
+  let candidates = coin_candidates(effects, owner, include_gas_coin)
+    .into_iter()
+    .map(|object| object.reference)
+    .collect::<Vec<_>>();
+  let balances = fetch_coin_balances(client, coin_type, &candidates).await?;
+
+  take_largest_of(effects, coin_type, owner, include_gas_coin, &balances)
+}
+
+/// Returns the objects among those `effects` mutated that `owner` owns afterwards, the gas coin included when
+/// `include_gas_coin` is set.
+fn coin_candidates(
+  effects: &IotaTransactionBlockEffects,
+  owner: IotaAddress,
+  include_gas_coin: bool,
+) -> Vec<OwnedObjectRef> {
+  let gas_object = effects.gas_object();
+  let mut candidates = effects
+    .mutated()
+    .iter()
+    .filter(|object| object.owner == Owner::Address(owner))
+    // The gas coin is among the mutated objects too.
+    .filter(|object| include_gas_coin || object.object_id() != gas_object.object_id())
+    .cloned()
+    .collect::<Vec<OwnedObjectRef>>();
+  if include_gas_coin
+    && gas_object.owner == Owner::Address(owner)
+    && !candidates
+      .iter()
+      .any(|object| object.object_id() == gas_object.object_id())
+  {
+    candidates.push(gas_object.clone());
+  }
+
+  candidates
+}
+
+/// Removes from `effects` the candidate coin - see [coin_candidates] - with the largest of `balances`, and returns
+/// it.
+fn take_largest_of(
+  effects: &mut IotaTransactionBlockEffects,
+  coin_type: &str,
+  owner: IotaAddress,
+  include_gas_coin: bool,
+  balances: &HashMap<ObjectId, u64>,
+) -> Result<OwnedCoin, Error> {
+  let (coin, balance) = coin_candidates(effects, owner, include_gas_coin)
+    .into_iter()
+    .filter_map(|object| balances.get(&object.object_id()).map(|&balance| (object, balance)))
+    .max_by_key(|(_, balance)| *balance)
+    .ok_or_else(|| {
+      Error::TransactionUnexpectedResponse(format!(
+        "no {coin_type} coin of {owner} was found among the changed objects"
+      ))
+    })?;
+  effects
+    .mutated_mut()
+    .retain(|object| object.object_id() != coin.object_id());
+
+  Ok(OwnedCoin {
+    object_ref: coin.reference,
+    balance,
+  })
+}
+
+/// Returns the balances of the objects among `object_refs` that are coins of type `coin_type`, at the versions
+/// referenced - i.e. as the transaction left them, whatever happened to them since.
+async fn fetch_coin_balances<C>(
+  client: &C,
+  coin_type: &str,
+  object_refs: &[ObjectRef],
+) -> Result<HashMap<ObjectId, u64>, Error>
+where
+  C: CoreClientReadOnly + OptionalSync,
+{
+  let expected_type = format!("0x2::coin::Coin<{coin_type}>")
+    .parse::<StructTag>()
+    .map_err(|e| Error::TransactionUnexpectedResponse(format!("invalid coin type `{coin_type}`: {e}")))?;
+
+  let past_objects = object_refs
+    .iter()
+    .map(|object_ref| IotaGetPastObjectRequest {
+      object_id: object_ref.object_id,
+      version: object_ref.version,
+    })
+    .collect();
+  let responses = client
+    .client_adapter()
+    .read_api()
+    .multi_get_past_objects(past_objects, IotaObjectDataOptions::default().with_bcs())
+    .await
+    .map_err(|e| Error::Network("failed to fetch the coins changed by the transaction".to_owned(), e))?;
+
+  let mut balances = HashMap::new();
+  for response in responses {
+    let IotaPastObjectResponse::VersionFound(data) = response else {
+      continue;
+    };
+    let Some(IotaRawData::MoveObject(object)) = data.bcs else {
+      continue;
+    };
+    if object.type_.to_string().parse::<StructTag>().ok().as_ref() != Some(&expected_type) {
+      continue;
+    }
+    let coin = object
+      .deserialize::<CoinContents>()
+      .map_err(|e| Error::TransactionUnexpectedResponse(format!("invalid {coin_type} coin: {e}")))?;
+    balances.insert(coin.id, coin.balance);
+  }
+
+  Ok(balances)
+}
+
+/// Returns whether `coin_type` is the IOTA coin type, in any of its representations.
+fn is_iota(coin_type: &str) -> bool {
+  let iota = IOTA_COIN_TYPE.parse::<StructTag>().expect("valid struct tag");
+  coin_type.parse::<StructTag>().is_ok_and(|coin_type| coin_type == iota)
+}
+
+#[cfg(test)]
+mod tests {
+  use futures::executor::block_on;
+  use iota_interaction::types::base_types::SequenceNumber;
+  use iota_interaction::types::digests::{ObjectDigest, TransactionDigest};
+
+  use super::*;
+  use crate::transaction::testing::{address, effects, object_id, object_ref, owned_by, OfflineClient};
+
+  /// Returns the IOTA coin [object_ref]`(byte, 1)` holding `balance`.
+  fn coin(byte: u8, balance: u64) -> Coin {
+    Coin {
+      coin_type: IOTA_COIN_TYPE.to_owned(),
+      coin_object_id: object_id(byte),
+      version: SequenceNumber::from_u64(1),
+      digest: ObjectDigest::new([1; 32]),
+      balance,
+      previous_transaction: TransactionDigest::new([0; 32]),
+    }
+  }
+
+  fn owned_inputs(bytes: &[u8]) -> Vec<CallArg> {
+    bytes
+      .iter()
+      .map(|&byte| CallArg::ImmutableOrOwned(object_ref(byte, 1)))
+      .collect()
+  }
+
+  #[test]
+  fn coins_are_merged_into_the_first_one() {
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let coins = [coin(1, 300), coin(2, 200), coin(3, 100)];
+
+    let merged = merge_all(&mut ptb, &coins, IOTA_COIN_TYPE, false).unwrap();
+    let pt = ptb.finish();
+
+    assert_eq!(merged, Argument::Input(0));
+    assert_eq!(pt.inputs, owned_inputs(&[1, 2, 3]));
+    assert_eq!(
+      pt.commands,
+      vec![Command::MergeCoins(
+        Argument::Input(0),
+        vec![Argument::Input(1), Argument::Input(2)]
+      )]
+    );
+  }
+
+  #[test]
+  fn coins_merged_into_the_gas_coin_leave_out_the_first_one() {
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let coins = [coin(1, 300), coin(2, 200), coin(3, 100)];
+
+    let merged = merge_all(&mut ptb, &coins, IOTA_COIN_TYPE, true).unwrap();
+    let pt = ptb.finish();
+
+    assert_eq!(merged, Argument::GasCoin);
+    assert_eq!(pt.inputs, owned_inputs(&[2, 3]));
+    assert_eq!(
+      pt.commands,
+      vec![Command::MergeCoins(
+        Argument::GasCoin,
+        vec![Argument::Input(0), Argument::Input(1)]
+      )]
+    );
+
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    assert_eq!(
+      merge_all(&mut ptb, &[coin(1, 300)], IOTA_COIN_TYPE, true).unwrap(),
+      Argument::GasCoin
+    );
+    assert!(ptb.finish().commands.is_empty());
+  }
+
+  #[test]
+  fn only_iota_coins_are_merged_into_the_gas_coin() {
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let result = merge_all(&mut ptb, &[coin(1, 300), coin(2, 200)], "0xabc::token::TOKEN", true);
+
+    assert!(matches!(result, Err(Error::TransactionBuildingFailed(_))));
+  }
+
+  #[test]
+  fn paid_coins_are_told_apart_by_their_amounts() {
+    let (alice, bob) = (address(1), address(2));
+    let created = vec![
+      owned_by(bob, object_ref(11, 1)),
+      owned_by(alice, object_ref(12, 1)),
+      owned_by(alice, object_ref(13, 1)),
+    ];
+    let payments = [(alice, 100), (bob, 200), (alice, 100)];
+
+    let (balances, unknown) = paid_coin_balances(&created, &payments);
+
+    assert!(unknown.is_empty());
+    assert_eq!(
+      balances,
+      HashMap::from([(object_id(11), 200), (object_id(12), 100), (object_id(13), 100)])
+    );
+  }
+
+  #[test]
+  fn paid_coins_of_different_amounts_are_looked_up() {
+    let (alice, bob, carol) = (address(1), address(2), address(3));
+    let created = vec![
+      owned_by(alice, object_ref(11, 1)),
+      owned_by(alice, object_ref(12, 1)),
+      owned_by(bob, object_ref(13, 1)),
+      owned_by(carol, object_ref(14, 1)),
+      // Not a payment - e.g. created by another transaction of the same batch.
+      owned_by(carol, object_ref(15, 1)),
+    ];
+    let payments = [(alice, 100), (alice, 200), (bob, 300), (carol, 400)];
+
+    let (balances, mut unknown) = paid_coin_balances(&created, &payments);
+    unknown.sort_by_key(|object_ref| object_ref.object_id);
+
+    assert_eq!(balances, HashMap::from([(object_id(13), 300)]));
+    assert_eq!(
+      unknown,
+      vec![
+        object_ref(11, 1),
+        object_ref(12, 1),
+        object_ref(14, 1),
+        object_ref(15, 1)
+      ]
+    );
+  }
+
+  #[test]
+  fn paid_coins_are_taken_in_the_order_of_the_payments() {
+    let (alice, bob) = (address(1), address(2));
+    let mut created = vec![
+      owned_by(alice, object_ref(11, 1)),
+      owned_by(bob, object_ref(12, 1)),
+      owned_by(alice, object_ref(13, 1)),
+      owned_by(alice, object_ref(14, 1)),
+    ];
+    let balances = HashMap::from([(object_id(11), 200), (object_id(12), 300), (object_id(13), 100)]);
+
+    let coins = take_created_coins(
+      &mut created,
+      IOTA_COIN_TYPE,
+      &[(alice, 100), (bob, 300), (alice, 200)],
+      &balances,
+    )
+    .unwrap();
+
+    assert_eq!(
+      coins,
+      vec![
+        OwnedCoin {
+          object_ref: object_ref(13, 1),
+          balance: 100
+        },
+        OwnedCoin {
+          object_ref: object_ref(12, 1),
+          balance: 300
+        },
+        OwnedCoin {
+          object_ref: object_ref(11, 1),
+          balance: 200
+        },
+      ]
+    );
+    assert_eq!(created, vec![owned_by(alice, object_ref(14, 1))]);
+  }
+
+  #[test]
+  fn missing_paid_coins_are_reported() {
+    let alice = address(1);
+    let mut created = vec![owned_by(alice, object_ref(11, 1))];
+    let balances = HashMap::from([(object_id(11), 100)]);
+
+    let result = take_created_coins(&mut created, IOTA_COIN_TYPE, &[(alice, 100), (alice, 100)], &balances);
+
+    assert!(matches!(result, Err(Error::TransactionUnexpectedResponse(_))));
+  }
+
+  #[test]
+  fn the_gas_coin_is_a_candidate_only_when_used() {
+    let (alice, bob) = (address(1), address(2));
+    let gas_coin = owned_by(alice, object_ref(9, 2));
+    let effects = effects(
+      vec![],
+      vec![
+        owned_by(alice, object_ref(11, 2)),
+        owned_by(bob, object_ref(12, 2)),
+        gas_coin.clone(),
+      ],
+      gas_coin.clone(),
+    );
+
+    assert_eq!(
+      coin_candidates(&effects, alice, true),
+      vec![owned_by(alice, object_ref(11, 2)), gas_coin.clone()]
+    );
+    assert_eq!(
+      coin_candidates(&effects, bob, true),
+      vec![owned_by(bob, object_ref(12, 2))]
+    );
+    assert_eq!(
+      coin_candidates(&effects, alice, false),
+      vec![owned_by(alice, object_ref(11, 2))]
+    );
+
+    let mut effects = effects;
+    effects.mutated_mut().pop();
+    assert_eq!(
+      coin_candidates(&effects, alice, false),
+      vec![owned_by(alice, object_ref(11, 2))]
+    );
+    assert_eq!(
+      coin_candidates(&effects, alice, true),
+      vec![owned_by(alice, object_ref(11, 2)), gas_coin]
+    );
+  }
+
+  #[test]
+  fn the_largest_coin_is_taken() {
+    let alice = address(1);
+    let gas_coin = owned_by(alice, object_ref(9, 2));
+    let mut effects = effects(
+      vec![],
+      vec![
+        owned_by(alice, object_ref(11, 2)),
+        owned_by(alice, object_ref(12, 2)),
+        gas_coin.clone(),
+      ],
+      gas_coin,
+    );
+    let balances = HashMap::from([(object_id(11), 100), (object_id(12), 300), (object_id(9), 500)]);
+
+    let coin = take_largest_of(&mut effects, IOTA_COIN_TYPE, alice, false, &balances).unwrap();
+    assert_eq!(
+      coin,
+      OwnedCoin {
+        object_ref: object_ref(12, 2),
+        balance: 300
+      }
+    );
+
+    let coin = take_largest_of(&mut effects, IOTA_COIN_TYPE, alice, true, &balances).unwrap();
+    assert_eq!(
+      coin,
+      OwnedCoin {
+        object_ref: object_ref(9, 2),
+        balance: 500
+      }
+    );
+    assert_eq!(effects.mutated(), [owned_by(alice, object_ref(11, 2))]);
+
+    let result = take_largest_of(&mut effects, IOTA_COIN_TYPE, address(2), true, &balances);
+    assert!(matches!(result, Err(Error::TransactionUnexpectedResponse(_))));
+  }
+
+  #[test]
+  fn transferred_objects_are_taken_in_the_given_order() {
+    let (alice, bob) = (address(1), address(2));
+    let mut effects = effects(
+      vec![],
+      vec![
+        owned_by(bob, object_ref(11, 2)),
+        owned_by(alice, object_ref(12, 2)),
+        owned_by(bob, object_ref(13, 2)),
+      ],
+      owned_by(alice, object_ref(9, 2)),
+    );
+    let transfer = TransferObjects::new(vec![object_id(13), object_id(11)], bob);
+
+    let transferred = block_on(transfer.apply(&mut effects, &OfflineClient::new())).unwrap();
+
+    assert_eq!(transferred, vec![object_ref(13, 2), object_ref(11, 2)]);
+    assert_eq!(effects.mutated(), [owned_by(alice, object_ref(12, 2))]);
+
+    let transfer = TransferObjects::new(vec![object_id(12)], bob);
+    let result = block_on(transfer.apply(&mut effects, &OfflineClient::new()));
+    assert!(matches!(result, Err(Error::TransactionUnexpectedResponse(_))));
+  }
+
+  #[test]
+  fn iota_coin_type_is_recognized_in_any_form() {
+    assert!(is_iota("0x2::iota::IOTA"));
+    assert!(is_iota(
+      "0x0000000000000000000000000000000000000000000000000000000000000002::iota::IOTA"
+    ));
+    assert!(!is_iota("0x2::coin::Coin<0x2::iota::IOTA>"));
+    assert!(!is_iota("not a type"));
+  }
+}
@@ -0,0 +1,384 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A portable representation of a pending transaction.
+//!
+//! A [TransactionEnvelope] carries everything that is needed to move a transaction between machines,
+//! e.g. to have it signed on an air-gapped host: the [TransactionData] to sign, the signatures collected
+//! so far, the addresses that are required to sign, and a human-readable summary of what the transaction does.
+//!
+//! Envelopes can be encoded as BCS bytes, as a Base64 string of those bytes, or as JSON. The WASM bindings
+//! use the very same encoding, thus envelopes can be freely exchanged between Rust and JS.
+
+use std::fmt::Write as _;
+
+use fastcrypto::encoding::{Base64, Encoding as _};
+use iota_interaction::types::base_types::IotaAddress;
+use iota_interaction::types::crypto::IotaSignature as _;
+use iota_interaction::types::signature::GenericSignature;
+use iota_interaction::types::transaction::{
+  Command, TransactionData, TransactionDataAPI as _, TransactionExpiration, TransactionKind,
+};
+use iota_interaction::{IotaKeySignature, OptionalSend, OptionalSync};
+use iota_sdk_types::crypto::{Intent, IntentMessage};
+use secret_storage::Signer;
+use serde::{Deserialize, Serialize};
+
+use super::hooks::HookChain;
+use super::multisig::verify_multisig;
+use super::retry::RetryPolicy;
+use super::transaction_builder::{address_from_generic_signature, address_from_signature, execute_and_apply};
+use super::Transaction;
+#[cfg(not(target_arch = "wasm32"))]
+use super::TransactionOutput;
+#[cfg(target_arch = "wasm32")]
+use super::TransactionOutputInternal as TransactionOutput;
+use crate::core_client::CoreClientReadOnly;
+use crate::Error;
+
+/// The version of the [TransactionEnvelope] format produced by this library.
+pub const TRANSACTION_ENVELOPE_VERSION: u8 = 1;
+
+/// A versioned, serializable container for a transaction that still needs to be signed and/or submitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionEnvelope {
+  // Dev-comment: must remain the first field, as it's used to detect the format of BCS encoded envelopes.
+  version: u8,
+  tx_data: TransactionData,
+  signatures: Vec<GenericSignature>,
+  required_signers: Vec<IotaAddress>,
+  summary: String,
+}
+
+impl TransactionEnvelope {
+  /// Wraps the given transaction and the signatures collected for it so far into a new envelope.
+  /// ## Notes
+  /// The transaction's sender and gas owner are the addresses required to sign the transaction.
+  pub fn new(tx_data: TransactionData, signatures: Vec<GenericSignature>) -> Self {
+    let mut required_signers = vec![tx_data.sender()];
+    let gas_owner = tx_data.gas_owner();
+    if !required_signers.contains(&gas_owner) {
+      required_signers.push(gas_owner);
+    }
+    let summary = summarize(&tx_data);
+
+    Self {
+      version: TRANSACTION_ENVELOPE_VERSION,
+      tx_data,
+      signatures,
+      required_signers,
+      summary,
+    }
+  }
+
+  /// Returns the version of this envelope's format.
+  pub fn version(&self) -> u8 {
+    self.version
+  }
+
+  /// Returns the transaction to sign.
+  pub fn tx_data(&self) -> &TransactionData {
+    &self.tx_data
+  }
+
+  /// Returns the signatures collected so far.
+  pub fn signatures(&self) -> &[GenericSignature] {
+    &self.signatures
+  }
+
+  /// Returns the addresses whose signature is required for this transaction to be executed.
+  pub fn required_signers(&self) -> &[IotaAddress] {
+    &self.required_signers
+  }
+
+  /// Returns a human-readable summary of this transaction.
+  /// ## Notes
+  /// The summary is always computed out of this envelope's [TransactionData], decoding an envelope
+  /// discards the summary it was encoded with.
+  pub fn summary(&self) -> &str {
+    &self.summary
+  }
+
+  /// Returns the addresses that are required to sign this transaction but didn't do it yet.
+  pub fn missing_signers(&self) -> Vec<IotaAddress> {
+    let signed_by = self
+      .signatures
+      .iter()
+      .filter_map(address_from_generic_signature)
+      .collect::<Vec<_>>();
+    self
+      .required_signers
+      .iter()
+      .copied()
+      .filter(|address| !signed_by.contains(address))
+      .collect()
+  }
+
+  /// Returns whether this transaction has been signed by all the required signers.
+  pub fn is_complete(&self) -> bool {
+    self.missing_signers().is_empty()
+  }
+
+  /// Adds the given signature to this envelope.
+  /// ## Notes
+  /// This method fails if `signature` is not a valid signature for this transaction, or if
+  /// it was produced by an address that is not required to sign it.
+  /// Multisig signatures must carry valid signatures of their members, whose combined weight
+  /// meets the multisig threshold.
+  pub fn add_signature(&mut self, signature: impl Into<GenericSignature>) -> Result<(), Error> {
+    let signature = signature.into();
+    match &signature {
+      GenericSignature::Signature(sig) => {
+        let signer_address = address_from_signature(sig)?;
+        self.ensure_signer_is_missing(signer_address)?;
+
+        let intent_msg = IntentMessage::new(Intent::iota_transaction(), self.tx_data.clone());
+        sig
+          .verify_secure(&intent_msg, signer_address, sig.scheme())
+          .map_err(|e| {
+            Error::TransactionSigningFailed(format!("invalid signature for address {signer_address}: {e}"))
+          })?;
+      }
+      GenericSignature::MultiSig(multisig) => {
+        self.ensure_signer_is_missing(IotaAddress::from(multisig.get_pk()))?;
+        verify_multisig(multisig, &self.tx_data)?;
+      }
+      _ => {
+        return Err(Error::TransactionSigningFailed(
+          "unsupported signature kind; only single key and multisig signatures are supported".to_owned(),
+        ))
+      }
+    }
+
+    self.signatures.push(signature);
+    Ok(())
+  }
+
+  /// Signs this transaction with `signer`, adding the resulting signature to this envelope.
+  /// ## Notes
+  /// This method fails if `signer`'s address is not required to sign this transaction or if it already did.
+  pub async fn sign<S>(&mut self, signer: &S) -> Result<(), Error>
+  where
+    S: Signer<IotaKeySignature> + OptionalSync,
+  {
+    let pk = signer
+      .public_key()
+      .await
+      .map_err(|e| Error::TransactionSigningFailed(e.to_string()))?;
+    self.ensure_signer_is_missing(IotaAddress::from(&pk))?;
+
+    let signature = signer
+      .sign(&self.tx_data)
+      .await
+      .map_err(|e| Error::TransactionSigningFailed(e.to_string()))?;
+    self.signatures.push(signature.into());
+
+    Ok(())
+  }
+
+  fn ensure_signer_is_missing(&self, signer_address: IotaAddress) -> Result<(), Error> {
+    if !self.required_signers.contains(&signer_address) {
+      return Err(Error::TransactionSigningFailed(format!(
+        "address {signer_address} is not required to sign this transaction"
+      )));
+    }
+    if !self.missing_signers().contains(&signer_address) {
+      return Err(Error::TransactionSigningFailed(format!(
+        "address {signer_address} already signed this transaction"
+      )));
+    }
+
+    Ok(())
+  }
+
+  /// Consumes this envelope, returning the transaction and its signatures.
+  pub fn into_parts(self) -> (TransactionData, Vec<GenericSignature>) {
+    (self.tx_data, self.signatures)
+  }
+
+  /// Executes the enveloped transaction, using `tx` to compute its off-chain effects.
+  /// ## Notes
+  /// This method fails without submitting the transaction if some required signatures are missing.
+  /// Only the [TransactionHook::after_execute](super::TransactionHook::after_execute) and
+  /// [TransactionHook::on_apply_error](super::TransactionHook::on_apply_error) hooks registered on `client`
+  /// are run, as the transaction has already been built and signed.
+  pub async fn execute<Tx, C>(self, tx: Tx, client: &C) -> Result<TransactionOutput<Tx::Output>, Error>
+  where
+    Tx: Transaction + OptionalSend,
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    self.execute_with_retry_policy(tx, &RetryPolicy::none(), client).await
+  }
+
+  /// Same as [Self::execute], but submits the transaction according to `retry_policy`.
+  pub async fn execute_with_retry_policy<Tx, C>(
+    self,
+    tx: Tx,
+    retry_policy: &RetryPolicy,
+    client: &C,
+  ) -> Result<TransactionOutput<Tx::Output>, Error>
+  where
+    Tx: Transaction + OptionalSend,
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let missing_signers = self.missing_signers();
+    if !missing_signers.is_empty() {
+      let missing_signers = missing_signers
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+      return Err(Error::TransactionSigningFailed(format!(
+        "transaction is missing the signatures of: {missing_signers}"
+      )));
+    }
+
+    let (tx_data, signatures) = self.into_parts();
+    let hooks = HookChain::new(client, &[]);
+    execute_and_apply(tx_data, signatures, tx, retry_policy, &hooks, None, client).await
+  }
+
+  /// Encodes this envelope as BCS bytes.
+  pub fn to_bcs(&self) -> Vec<u8> {
+    bcs::to_bytes(self).expect("envelope is BCS serializable")
+  }
+
+  /// Decodes an envelope out of its BCS encoding.
+  pub fn from_bcs(bytes: &[u8]) -> Result<Self, Error> {
+    let version = bytes
+      .first()
+      .copied()
+      .ok_or_else(|| Error::TransactionBuildingFailed("empty transaction envelope".to_owned()))?;
+    check_version(version)?;
+
+    bcs::from_bytes(bytes)
+      .map_err(|e| Error::TransactionBuildingFailed(format!("failed to decode transaction envelope: {e}")))
+      .map(Self::recompute_derived_fields)
+  }
+
+  /// Encodes this envelope as a Base64 string of its BCS encoding.
+  pub fn to_base64(&self) -> String {
+    Base64::encode(self.to_bcs())
+  }
+
+  /// Decodes an envelope out of the Base64 string of its BCS encoding.
+  pub fn from_base64(b64: &str) -> Result<Self, Error> {
+    let bytes = Base64::decode(b64)
+      .map_err(|e| Error::TransactionBuildingFailed(format!("invalid base64 transaction envelope: {e}")))?;
+    Self::from_bcs(&bytes)
+  }
+
+  /// Encodes this envelope as a JSON string.
+  pub fn to_json(&self) -> String {
+    serde_json::to_string(self).expect("envelope is JSON serializable")
+  }
+
+  /// Decodes an envelope out of its JSON encoding.
+  pub fn from_json(json: &str) -> Result<Self, Error> {
+    let envelope: Self = serde_json::from_str(json)
+      .map_err(|e| Error::TransactionBuildingFailed(format!("failed to decode transaction envelope: {e}")))?;
+    check_version(envelope.version)?;
+
+    Ok(envelope.recompute_derived_fields())
+  }
+
+  /// Replaces the required signers and the summary of a decoded envelope with the ones derived from its
+  /// [TransactionData], so that they cannot be made to misrepresent the transaction to sign.
+  fn recompute_derived_fields(self) -> Self {
+    Self::new(self.tx_data, self.signatures)
+  }
+}
+
+fn check_version(version: u8) -> Result<(), Error> {
+  if version != TRANSACTION_ENVELOPE_VERSION {
+    return Err(Error::TransactionBuildingFailed(format!(
+      "unsupported transaction envelope version {version}; expected version {TRANSACTION_ENVELOPE_VERSION}"
+    )));
+  }
+
+  Ok(())
+}
+
+/// Returns a human-readable description of `tx_data`.
+fn summarize(tx_data: &TransactionData) -> String {
+  let gas_data = tx_data.gas_data();
+  let mut summary = format!(
+    "sender: {}\ngas owner: {}\ngas budget: {}\ngas price: {}\n",
+    tx_data.sender(),
+    gas_data.owner,
+    gas_data.budget,
+    gas_data.price
+  );
+  if let TransactionExpiration::Epoch(epoch) = tx_data.expiration() {
+    let _ = writeln!(summary, "expires after epoch: {epoch}");
+  }
+
+  #[allow(irrefutable_let_patterns)]
+  if let TransactionKind::Programmable(pt) = tx_data.kind() {
+    let _ = writeln!(summary, "inputs: {}", pt.inputs.len());
+    let _ = writeln!(summary, "commands: {}", pt.commands.len());
+    for (idx, command) in pt.commands.iter().enumerate() {
+      let description = match command {
+        Command::MoveCall(call) => format!("move call {}::{}::{}", call.package, call.module, call.function),
+        Command::TransferObjects(objects, _) => format!("transfer {} object(s)", objects.len()),
+        Command::SplitCoins(_, amounts) => format!("split coin into {} coin(s)", amounts.len()),
+        Command::MergeCoins(_, coins) => format!("merge {} coin(s)", coins.len()),
+        Command::MakeMoveVec(_, elements) => format!("make move vector of {} element(s)", elements.len()),
+        Command::Publish(_, _) => "publish package".to_owned(),
+        Command::Upgrade(_, _, package, _) => format!("upgrade package {package}"),
+      };
+      let _ = writeln!(summary, "  #{idx}: {description}");
+    }
+  }
+
+  summary
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::transaction::testing::{address, transaction_data};
+
+  fn envelope() -> TransactionEnvelope {
+    TransactionEnvelope::new(transaction_data(address(1), address(2)), vec![])
+  }
+
+  #[test]
+  fn envelope_round_trips_through_every_encoding() {
+    let envelope = envelope();
+    assert_eq!(envelope.required_signers(), &[address(1), address(2)]);
+
+    assert_eq!(TransactionEnvelope::from_bcs(&envelope.to_bcs()).unwrap(), envelope);
+    assert_eq!(
+      TransactionEnvelope::from_base64(&envelope.to_base64()).unwrap(),
+      envelope
+    );
+    assert_eq!(TransactionEnvelope::from_json(&envelope.to_json()).unwrap(), envelope);
+  }
+
+  #[test]
+  fn decoding_recomputes_summary_and_required_signers() {
+    let envelope = envelope();
+    let mut tampered = envelope.clone();
+    tampered.summary = "transfer nothing".to_owned();
+    tampered.required_signers = vec![address(4)];
+
+    assert_eq!(TransactionEnvelope::from_bcs(&tampered.to_bcs()).unwrap(), envelope);
+    assert_eq!(
+      TransactionEnvelope::from_base64(&tampered.to_base64()).unwrap(),
+      envelope
+    );
+    assert_eq!(TransactionEnvelope::from_json(&tampered.to_json()).unwrap(), envelope);
+  }
+
+  #[test]
+  fn unsupported_versions_are_rejected() {
+    let mut envelope = envelope();
+    envelope.version = TRANSACTION_ENVELOPE_VERSION + 1;
+
+    assert!(TransactionEnvelope::from_bcs(&envelope.to_bcs()).is_err());
+    assert!(TransactionEnvelope::from_json(&envelope.to_json()).is_err());
+    assert!(TransactionEnvelope::from_bcs(&[]).is_err());
+  }
+}
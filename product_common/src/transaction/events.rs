@@ -0,0 +1,218 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed access to the events emitted by a transaction.
+//!
+//! [TypedEvents] matches events against the Move type of a Rust type - see [MoveType] - for every version of a
+//! package, and decodes them into said Rust type. It's meant to be used in
+//! [Transaction::apply_with_events](super::Transaction::apply_with_events) implementations:
+//! ```ignore
+//! async fn apply_with_events<C>(
+//!   self,
+//!   effects: &mut IotaTransactionBlockEffects,
+//!   events: &mut IotaTransactionBlockEvents,
+//!   client: &C,
+//! ) -> Result<Self::Output, Self::Error>
+//! where
+//!   C: CoreClientReadOnly + OptionalSync,
+//! {
+//!   let created = TypedEvents::new(events, client)
+//!     .take::<TokenCreated>()?
+//!     .ok_or_else(|| Error::TransactionUnexpectedResponse("missing TokenCreated event".to_owned()))?;
+//!   ...
+//! }
+//! ```
+
+use iota_interaction::rpc_types::{IotaEvent, IotaTransactionBlockEvents};
+use iota_interaction::MoveType;
+use iota_sdk_types::{ObjectId, StructTag, TypeTag};
+use serde::de::DeserializeOwned;
+
+use crate::core_client::CoreClientReadOnly;
+use crate::Error;
+
+/// A view over a transaction's events that extracts them as Rust types.
+///
+/// Like [Transaction::apply](super::Transaction::apply) does with effects, matched events are removed from the
+/// underlying [IotaTransactionBlockEvents], so that transactions batched together never handle the same event twice.
+#[derive(Debug)]
+pub struct TypedEvents<'a> {
+  events: &'a mut IotaTransactionBlockEvents,
+  package_history: Vec<ObjectId>,
+}
+
+impl<'a> TypedEvents<'a> {
+  /// Returns a [TypedEvents] that matches events emitted by any version of `client`'s package,
+  /// see [CoreClientReadOnly::package_history].
+  pub fn new<C>(events: &'a mut IotaTransactionBlockEvents, client: &C) -> Self
+  where
+    C: CoreClientReadOnly + ?Sized,
+  {
+    Self::with_package_history(events, client.package_history())
+  }
+
+  /// Returns a [TypedEvents] that matches events whose type was defined in any of the given packages.
+  pub fn with_package_history(events: &'a mut IotaTransactionBlockEvents, package_history: Vec<ObjectId>) -> Self {
+    Self {
+      events,
+      package_history,
+    }
+  }
+
+  /// Removes the first event of type `T` and returns it, if any.
+  /// The event is left in place when it cannot be decoded.
+  pub fn take<T>(&mut self) -> Result<Option<T>, Error>
+  where
+    T: MoveType + DeserializeOwned,
+  {
+    let type_tags = struct_tags::<T>(&self.package_history);
+    let Some(idx) = self.events.data.iter().position(|event| is_of_type(event, &type_tags)) else {
+      return Ok(None);
+    };
+    let decoded = decode_event(&self.events.data[idx])?;
+    self.events.data.remove(idx);
+
+    Ok(Some(decoded))
+  }
+
+  /// Removes all the events of type `T` and returns them, in the order they were emitted.
+  /// No event is removed when any of them cannot be decoded.
+  pub fn find_all<T>(&mut self) -> Result<Vec<T>, Error>
+  where
+    T: MoveType + DeserializeOwned,
+  {
+    let type_tags = struct_tags::<T>(&self.package_history);
+    let decoded = self
+      .events
+      .data
+      .iter()
+      .filter(|event| is_of_type(event, &type_tags))
+      .map(decode_event)
+      .collect::<Result<Vec<T>, _>>()?;
+    self.events.data.retain(|event| !is_of_type(event, &type_tags));
+
+    Ok(decoded)
+  }
+
+  /// Returns whether any event of type `T` is left.
+  pub fn contains<T>(&self) -> bool
+  where
+    T: MoveType,
+  {
+    let type_tags = struct_tags::<T>(&self.package_history);
+    self.events.data.iter().any(|event| is_of_type(event, &type_tags))
+  }
+}
+
+/// Returns the Move type of `T` for every package in `package_history`, if `T` is a struct.
+pub(crate) fn struct_tags<T: MoveType>(package_history: &[ObjectId]) -> Vec<StructTag> {
+  package_history
+    .iter()
+    .filter_map(|package| match T::move_type(*package) {
+      TypeTag::Struct(struct_tag) => Some(*struct_tag),
+      _ => None,
+    })
+    .collect()
+}
+
+pub(crate) fn is_of_type(event: &IotaEvent, struct_tags: &[StructTag]) -> bool {
+  struct_tags.contains(&event.type_)
+}
+
+/// Decodes `event` from its BCS encoding, falling back to its JSON representation.
+pub(crate) fn decode_event<T: DeserializeOwned>(event: &IotaEvent) -> Result<T, Error> {
+  bcs::from_bytes(event.bcs.bytes()).or_else(|bcs_error| {
+    serde_json::from_value(event.parsed_json.clone()).map_err(|json_error| {
+      Error::TransactionUnexpectedResponse(format!(
+        "failed to decode event of type `{}`; BCS: {bcs_error}, JSON: {json_error}",
+        event.type_
+      ))
+    })
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr as _;
+
+  use iota_interaction::rpc_types::BcsEvent;
+  use iota_interaction::types::base_types::IotaAddress;
+  use iota_interaction::types::digests::TransactionDigest;
+  use iota_interaction::types::event::EventID;
+  use serde::{Deserialize, Serialize};
+  use serde_json::json;
+
+  use super::*;
+
+  #[derive(Debug, PartialEq, Serialize, Deserialize)]
+  struct Minted {
+    amount: u64,
+  }
+
+  impl MoveType for Minted {
+    fn move_type(package: ObjectId) -> TypeTag {
+      TypeTag::from_str(&format!("{package}::token::Minted")).expect("valid type tag")
+    }
+  }
+
+  fn package(byte: u8) -> ObjectId {
+    ObjectId::new([byte; 32])
+  }
+
+  /// Returns a `token::Minted` event emitted by `package`, with the given BCS and JSON representations.
+  fn minted(package: ObjectId, bcs: Vec<u8>, parsed_json: serde_json::Value) -> IotaEvent {
+    IotaEvent {
+      id: EventID {
+        tx_digest: TransactionDigest::new([0; 32]),
+        event_seq: 0,
+      },
+      package_id: package,
+      transaction_module: "token".parse().expect("valid identifier"),
+      sender: IotaAddress::ZERO,
+      type_: StructTag::from_str(&format!("{package}::token::Minted")).expect("valid struct tag"),
+      parsed_json,
+      bcs: BcsEvent::new(bcs),
+      timestamp_ms: None,
+    }
+  }
+
+  #[test]
+  fn events_match_any_version_of_their_package() {
+    let event = minted(package(2), vec![], json!({}));
+
+    assert!(is_of_type(&event, &struct_tags::<Minted>(&[package(1), package(2)])));
+    assert!(!is_of_type(&event, &struct_tags::<Minted>(&[package(1), package(3)])));
+    // Only structs can be events.
+    assert!(struct_tags::<u64>(&[package(2)]).is_empty());
+  }
+
+  #[test]
+  fn events_are_decoded_from_bcs_then_json() {
+    let bcs_only = minted(package(1), bcs::to_bytes(&42_u64).unwrap(), json!(null));
+    assert_eq!(decode_event::<Minted>(&bcs_only).unwrap(), Minted { amount: 42 });
+
+    let json_only = minted(package(1), vec![], json!({ "amount": 7 }));
+    assert_eq!(decode_event::<Minted>(&json_only).unwrap(), Minted { amount: 7 });
+
+    let invalid = minted(package(1), vec![], json!({ "amount": "many" }));
+    let error = decode_event::<Minted>(&invalid).unwrap_err().to_string();
+    assert!(error.contains("BCS:") && error.contains("JSON:"), "{error}");
+  }
+
+  #[test]
+  fn only_decoded_events_are_removed() {
+    let mut events = IotaTransactionBlockEvents {
+      data: vec![
+        minted(package(1), vec![], json!({ "amount": 1 })),
+        minted(package(1), vec![], json!({ "amount": "many" })),
+      ],
+    };
+    let mut typed_events = TypedEvents::with_package_history(&mut events, vec![package(1)]);
+
+    assert_eq!(typed_events.take::<Minted>().unwrap(), Some(Minted { amount: 1 }));
+    assert!(typed_events.take::<Minted>().is_err());
+    assert!(typed_events.find_all::<Minted>().is_err());
+    assert!(typed_events.contains::<Minted>());
+    assert_eq!(events.data.len(), 1);
+  }
+}
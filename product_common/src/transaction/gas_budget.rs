@@ -0,0 +1,328 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Strategies to estimate the gas budget of a transaction.
+//!
+//! A [TransactionBuilder](super::TransactionBuilder) that has no explicit gas budget uses a [GasBudgetEstimator]
+//! to compute one - by default a [DryRunEstimator]. Estimators can be combined, e.g. to cap the estimated budget
+//! with [Capped], or to use a different strategy for each transaction type with [PerTransactionType].
+
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use iota_interaction::rpc_types::{DryRunTransactionBlockResponse, IotaTransactionBlockEffectsAPI as _};
+use iota_interaction::types::base_types::{IotaAddress, ObjectRef};
+use iota_interaction::types::gas::GasCostSummary;
+use iota_interaction::types::transaction::{ProgrammableTransaction, TransactionData};
+use iota_interaction::{IotaClientTrait, OptionalSend, OptionalSync};
+
+use crate::iota_interaction_adapter::IotaClientAdapter;
+use crate::Error;
+
+/// The budget used when dry running a transaction to estimate its gas cost.
+pub const DRY_RUN_GAS_BUDGET: u64 = 50_000_000;
+
+/// The gas units [DryRunEstimator] adds by default to a transaction's dry run cost, priced at the transaction's gas
+/// price.
+pub const DEFAULT_OVERHEAD_GAS_UNITS: u64 = 1000;
+
+/// The information available about a transaction whose gas budget must be estimated.
+#[derive(Debug, Clone, Copy)]
+pub struct GasEstimationRequest<'a> {
+  /// The transaction's sender.
+  pub sender: IotaAddress,
+  /// The address paying for the transaction's gas.
+  pub gas_owner: IotaAddress,
+  /// The gas price the transaction will be executed with.
+  pub gas_price: u64,
+  /// The coins the transaction will be paid with, if already known.
+  /// ## Notes
+  /// When empty, the node dry runs transactions using a mock gas coin, thus the gas owner doesn't need
+  /// to own any coin in order to estimate the gas budget - e.g. for sponsored transactions.
+  pub gas_payment: &'a [ObjectRef],
+  /// The programmable transaction to estimate the gas budget for.
+  pub programmable_tx: &'a ProgrammableTransaction,
+  /// The name of the [Transaction](super::Transaction) type the programmable transaction was built from,
+  /// as returned by [std::any::type_name].
+  /// ## Notes
+  /// Type names are meant for diagnostics only and are not guaranteed to be unique, use
+  /// [Self::tx_type_id] to tell transaction types apart.
+  pub tx_type: &'a str,
+  /// The [TypeId] of the [Transaction](super::Transaction) type the programmable transaction was built from.
+  pub tx_type_id: TypeId,
+}
+
+/// A strategy to estimate the gas budget of a transaction.
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+pub trait GasBudgetEstimator: Debug + OptionalSend + OptionalSync {
+  /// Returns the gas budget for the transaction described by `request`.
+  async fn estimate_gas_budget(
+    &self,
+    request: &GasEstimationRequest<'_>,
+    client: &IotaClientAdapter,
+  ) -> Result<u64, Error>;
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl<E: GasBudgetEstimator + ?Sized> GasBudgetEstimator for Box<E> {
+  async fn estimate_gas_budget(
+    &self,
+    request: &GasEstimationRequest<'_>,
+    client: &IotaClientAdapter,
+  ) -> Result<u64, Error> {
+    self.as_ref().estimate_gas_budget(request, client).await
+  }
+}
+
+/// Estimates the gas budget by dry running the transaction, adding a fixed overhead to the resulting cost and
+/// multiplying it by a safety multiplier.
+#[derive(Debug, Clone, Copy)]
+pub struct DryRunEstimator {
+  safety_multiplier: f64,
+  overhead_gas_units: u64,
+}
+
+impl Default for DryRunEstimator {
+  fn default() -> Self {
+    Self {
+      safety_multiplier: 1.0,
+      overhead_gas_units: DEFAULT_OVERHEAD_GAS_UNITS,
+    }
+  }
+}
+
+impl DryRunEstimator {
+  /// Returns a new [DryRunEstimator] that doesn't add any safety margin to the dry run cost.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Multiplies the estimated gas cost by `safety_multiplier`, e.g. `1.2` adds a 20% margin.
+  /// ## Notes
+  /// Multipliers lower than `1.0` are ignored.
+  pub fn with_safety_multiplier(mut self, safety_multiplier: f64) -> Self {
+    self.safety_multiplier = safety_multiplier.max(1.0);
+    self
+  }
+
+  /// Adds `overhead_gas_units` gas units - priced at the transaction's gas price - to the dry run cost,
+  /// instead of [DEFAULT_OVERHEAD_GAS_UNITS].
+  pub fn with_overhead_gas_units(mut self, overhead_gas_units: u64) -> Self {
+    self.overhead_gas_units = overhead_gas_units;
+    self
+  }
+
+  /// Returns the gas budget for a transaction with the given gas price, whose dry run cost `gas_summary`.
+  fn budget_for(&self, gas_price: u64, gas_summary: &GasCostSummary) -> u64 {
+    let overhead = gas_price.saturating_mul(self.overhead_gas_units);
+    let net_used = gas_summary.net_gas_usage().max(0) as u64;
+    let cost = overhead.saturating_add(net_used.max(gas_summary.computation_cost));
+
+    (cost as f64 * self.safety_multiplier).ceil() as u64
+  }
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl GasBudgetEstimator for DryRunEstimator {
+  async fn estimate_gas_budget(
+    &self,
+    request: &GasEstimationRequest<'_>,
+    client: &IotaClientAdapter,
+  ) -> Result<u64, Error> {
+    let tx_data = TransactionData::new_programmable_allow_sponsor(
+      request.sender,
+      request.gas_payment.to_vec(),
+      request.programmable_tx.clone(),
+      DRY_RUN_GAS_BUDGET,
+      request.gas_price,
+      request.gas_owner,
+    );
+    let DryRunTransactionBlockResponse { effects, .. } = client
+      .read_api()
+      .dry_run_transaction_block(tx_data)
+      .await
+      .map_err(|e| Error::GasIssue(format!("failed to dry run transaction: {e}")))?;
+
+    let tx_status = effects.status();
+    if tx_status.is_err() {
+      return Err(Error::GasIssue(format!(
+        "cannot estimate gas budget of a failing transaction: {tx_status}"
+      )));
+    }
+
+    Ok(self.budget_for(request.gas_price, effects.gas_cost_summary()))
+  }
+}
+
+/// Always uses the given gas budget.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedBudget(pub u64);
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl GasBudgetEstimator for FixedBudget {
+  async fn estimate_gas_budget(
+    &self,
+    _request: &GasEstimationRequest<'_>,
+    _client: &IotaClientAdapter,
+  ) -> Result<u64, Error> {
+    Ok(self.0)
+  }
+}
+
+/// Limits the budget estimated by another [GasBudgetEstimator].
+#[derive(Debug, Clone)]
+pub struct Capped<E> {
+  inner: E,
+  max_budget: u64,
+}
+
+impl<E> Capped<E> {
+  /// Caps the gas budget estimated by `inner` to `max_budget`.
+  pub fn new(inner: E, max_budget: u64) -> Self {
+    Self { inner, max_budget }
+  }
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl<E: GasBudgetEstimator> GasBudgetEstimator for Capped<E> {
+  async fn estimate_gas_budget(
+    &self,
+    request: &GasEstimationRequest<'_>,
+    client: &IotaClientAdapter,
+  ) -> Result<u64, Error> {
+    let budget = self.inner.estimate_gas_budget(request, client).await?;
+    Ok(budget.min(self.max_budget))
+  }
+}
+
+/// Uses a different [GasBudgetEstimator] depending on the type of the [Transaction](super::Transaction)
+/// being built.
+#[derive(Debug)]
+pub struct PerTransactionType {
+  overrides: HashMap<TypeId, Box<dyn GasBudgetEstimator>>,
+  fallback: Box<dyn GasBudgetEstimator>,
+}
+
+impl Default for PerTransactionType {
+  fn default() -> Self {
+    Self::new(DryRunEstimator::default())
+  }
+}
+
+impl PerTransactionType {
+  /// Returns a new [PerTransactionType] estimator that uses `fallback` for all transaction types
+  /// without an override.
+  pub fn new(fallback: impl GasBudgetEstimator + 'static) -> Self {
+    Self {
+      overrides: HashMap::default(),
+      fallback: Box::new(fallback),
+    }
+  }
+
+  /// Uses `estimator` for transactions of type `Tx`.
+  pub fn with_override<Tx: 'static>(mut self, estimator: impl GasBudgetEstimator + 'static) -> Self {
+    self.overrides.insert(TypeId::of::<Tx>(), Box::new(estimator));
+    self
+  }
+
+  /// Returns the estimator used for transactions whose type has the given [TypeId].
+  fn estimator_for(&self, tx_type_id: TypeId) -> &dyn GasBudgetEstimator {
+    &**self.overrides.get(&tx_type_id).unwrap_or(&self.fallback)
+  }
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl GasBudgetEstimator for PerTransactionType {
+  async fn estimate_gas_budget(
+    &self,
+    request: &GasEstimationRequest<'_>,
+    client: &IotaClientAdapter,
+  ) -> Result<u64, Error> {
+    self
+      .estimator_for(request.tx_type_id)
+      .estimate_gas_budget(request, client)
+      .await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct Mint;
+  struct Burn;
+
+  fn gas_summary(computation_cost: u64, storage_cost: u64, storage_rebate: u64) -> GasCostSummary {
+    GasCostSummary {
+      computation_cost,
+      storage_cost,
+      storage_rebate,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn dry_run_budget_adds_overhead_and_safety_margin() {
+    let summary = gas_summary(2_000, 10_000, 4_000);
+    // Net usage: 2_000 + 10_000 - 4_000.
+    assert_eq!(DryRunEstimator::new().budget_for(1000, &summary), 1_000_000 + 8_000);
+    assert_eq!(
+      DryRunEstimator::new()
+        .with_overhead_gas_units(10)
+        .budget_for(1000, &summary),
+      10_000 + 8_000
+    );
+    assert_eq!(
+      DryRunEstimator::new()
+        .with_overhead_gas_units(0)
+        .with_safety_multiplier(1.5)
+        .budget_for(1000, &summary),
+      12_000
+    );
+  }
+
+  #[test]
+  fn dry_run_budget_covers_computation_cost_despite_rebates() {
+    let summary = gas_summary(2_000, 1_000, 50_000);
+    assert_eq!(
+      DryRunEstimator::new()
+        .with_overhead_gas_units(0)
+        .budget_for(1000, &summary),
+      2_000
+    );
+  }
+
+  #[test]
+  fn safety_multipliers_lower_than_one_are_ignored() {
+    let summary = gas_summary(2_000, 0, 0);
+    assert_eq!(
+      DryRunEstimator::new()
+        .with_overhead_gas_units(0)
+        .with_safety_multiplier(0.5)
+        .budget_for(1000, &summary),
+      2_000
+    );
+  }
+
+  #[test]
+  fn overrides_are_picked_by_transaction_type() {
+    let estimator = PerTransactionType::new(FixedBudget(100)).with_override::<Mint>(FixedBudget(200));
+
+    assert_eq!(
+      format!("{:?}", estimator.estimator_for(TypeId::of::<Mint>())),
+      "FixedBudget(200)"
+    );
+    assert_eq!(
+      format!("{:?}", estimator.estimator_for(TypeId::of::<Burn>())),
+      "FixedBudget(100)"
+    );
+  }
+}
@@ -0,0 +1,120 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hooks to run cross-cutting logic - e.g. audit logging, policy checks, or metrics - on every transaction.
+//!
+//! A [TransactionHook] can be registered on a whole client, by overriding
+//! [CoreClientReadOnly::transaction_hooks], or on a single transaction, through
+//! [TransactionBuilder::with_hook](super::TransactionBuilder::with_hook). Client hooks run before
+//! transaction hooks, in the order they were registered.
+//!
+//! Hooks called before a transaction is submitted can veto it by returning an error, e.g.
+//! [Error::TransactionVetoed].
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use iota_interaction::types::transaction::{ProgrammableTransaction, TransactionData};
+use iota_interaction::{OptionalSend, OptionalSync};
+
+use crate::core_client::CoreClientReadOnly;
+use crate::iota_interaction_adapter::IotaTransactionBlockResponseAdaptedTraitObj;
+use crate::Error;
+
+/// The response of an executed transaction, as seen by [TransactionHook::after_execute].
+pub type ExecutionResponse = <IotaTransactionBlockResponseAdaptedTraitObj as std::ops::Deref>::Target;
+
+/// Callbacks invoked at the different stages of a transaction's life cycle.
+///
+/// All callbacks do nothing by default.
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+pub trait TransactionHook: Debug + OptionalSend + OptionalSync {
+  /// Called once the programmable transaction has been built, before the rest of the transaction's data - e.g. its
+  /// gas information - is filled in.
+  ///
+  /// `tx_type` is the name of the [Transaction](super::Transaction) type, as returned by [std::any::type_name].
+  /// Returning an error aborts the building of the transaction.
+  async fn before_build(&self, _tx_type: &str, _programmable_tx: &ProgrammableTransaction) -> Result<(), Error> {
+    Ok(())
+  }
+
+  /// Called before the client signs `tx_data`.
+  /// Returning an error prevents the transaction from being signed.
+  async fn before_sign(&self, _tx_data: &TransactionData) -> Result<(), Error> {
+    Ok(())
+  }
+
+  /// Called after the transaction has been executed, before its off-chain effects are applied.
+  /// ## Notes
+  /// The transaction's effects might be unsuccessful.
+  async fn after_execute(&self, _response: &ExecutionResponse) {}
+
+  /// Called when the off-chain effects of an executed transaction couldn't be applied.
+  async fn on_apply_error(&self, _error: &Error) {}
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl<H: TransactionHook + ?Sized> TransactionHook for Arc<H> {
+  async fn before_build(&self, tx_type: &str, programmable_tx: &ProgrammableTransaction) -> Result<(), Error> {
+    self.as_ref().before_build(tx_type, programmable_tx).await
+  }
+
+  async fn before_sign(&self, tx_data: &TransactionData) -> Result<(), Error> {
+    self.as_ref().before_sign(tx_data).await
+  }
+
+  async fn after_execute(&self, response: &ExecutionResponse) {
+    self.as_ref().after_execute(response).await
+  }
+
+  async fn on_apply_error(&self, error: &Error) {
+    self.as_ref().on_apply_error(error).await
+  }
+}
+
+/// The hooks that apply to a given transaction, in the order they must be run.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct HookChain(Vec<Arc<dyn TransactionHook>>);
+
+impl HookChain {
+  /// Returns the hooks registered on `client`, followed by `tx_hooks`.
+  pub(crate) fn new<C>(client: &C, tx_hooks: &[Arc<dyn TransactionHook>]) -> Self
+  where
+    C: CoreClientReadOnly + ?Sized,
+  {
+    Self(client.transaction_hooks().iter().chain(tx_hooks).cloned().collect())
+  }
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl TransactionHook for HookChain {
+  async fn before_build(&self, tx_type: &str, programmable_tx: &ProgrammableTransaction) -> Result<(), Error> {
+    for hook in &self.0 {
+      hook.before_build(tx_type, programmable_tx).await?;
+    }
+    Ok(())
+  }
+
+  async fn before_sign(&self, tx_data: &TransactionData) -> Result<(), Error> {
+    for hook in &self.0 {
+      hook.before_sign(tx_data).await?;
+    }
+    Ok(())
+  }
+
+  async fn after_execute(&self, response: &ExecutionResponse) {
+    for hook in &self.0 {
+      hook.after_execute(response).await;
+    }
+  }
+
+  async fn on_apply_error(&self, error: &Error) {
+    for hook in &self.0 {
+      hook.on_apply_error(error).await;
+    }
+  }
+}
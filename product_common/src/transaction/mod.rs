@@ -1,12 +1,46 @@
 // Copyright 2020-2024 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod batch;
+pub mod builder_state;
+pub mod coin_management;
+pub mod envelope;
+pub mod events;
+pub mod gas_budget;
+pub mod hooks;
+pub mod multisig;
+pub mod object_arg_resolver;
+pub mod object_changes;
+pub mod object_lock;
+pub mod protocol_limits;
+pub mod retry;
+pub mod sponsor_policy;
+#[cfg(test)]
+pub(crate) mod testing;
 pub mod transaction_builder;
+pub mod workflow;
 use std::ops::Deref;
 
+pub use batch::merge_programmable_transactions;
+pub use builder_state::TransactionBuilderState;
+pub use coin_management::{MergeCoins, OwnedCoin, PayCoins, SendAll, SplitCoins, TransferObjects};
+pub use envelope::TransactionEnvelope;
+pub use events::TypedEvents;
+pub use gas_budget::GasBudgetEstimator;
+pub use hooks::TransactionHook;
 #[cfg(not(target_arch = "wasm32"))]
 use iota_interaction::rpc_types::IotaTransactionBlockResponse;
+use iota_interaction::rpc_types::{IotaTransactionBlockEffects, IotaTransactionBlockEvents};
+use iota_interaction::types::gas::GasCostSummary;
+pub use multisig::{multisig_public_key, MultiSigPublicKey, MultisigSignatures};
+pub use object_arg_resolver::{ObjectAccess, ObjectArgResolver};
+pub use object_changes::ObjectChanges;
+pub use object_lock::ObjectLockManager;
+pub use protocol_limits::ProtocolLimits;
+pub use retry::RetryPolicy;
+pub use sponsor_policy::{SenderQuota, SponsorDecision, SponsorPolicy};
 pub use transaction_builder::{Transaction, TransactionBuilder};
+pub use workflow::{Workflow, WorkflowStore};
 
 use crate::iota_interaction_adapter::IotaTransactionBlockResponseAdaptedTraitObj;
 
@@ -28,6 +62,22 @@ pub struct TransactionOutputInternal<T> {
   pub response: IotaTransactionBlockResponseAdaptedTraitObj,
 }
 
+/// The outcome of simulating the execution of a [`Transaction`] through [`TransactionBuilder::dry_run`].
+#[derive(Debug)]
+pub struct DryRunOutput<T> {
+  /// The effects the transaction would have, if executed.
+  pub effects: IotaTransactionBlockEffects,
+  /// The events the transaction would emit, if executed.
+  pub events: IotaTransactionBlockEvents,
+  /// The gas the transaction would cost, if executed.
+  pub gas_cost_summary: GasCostSummary,
+  /// The result of applying the simulated effects through [`Transaction::apply_with_events`].
+  /// ## Notes
+  /// Objects created by a simulated execution do not exist on-chain, therefore applying the
+  /// simulated effects might fail even though an actual execution would succeed.
+  pub output: Result<T, crate::Error>,
+}
+
 impl<T> Deref for TransactionOutputInternal<T> {
   type Target = T;
   fn deref(&self) -> &Self::Target {
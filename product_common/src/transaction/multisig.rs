@@ -0,0 +1,285 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for transactions whose sender or gas owner is a multisig address.
+//!
+//! A multisig address is derived from a [MultiSigPublicKey], that is, a list of weighted member public keys
+//! and a threshold. A transaction from such an address is valid only if the combined weight of the members that
+//! signed it reaches the threshold.
+//!
+//! ## Example
+//! ```ignore
+//! let multisig_pk = multisig_public_key([(alice_pk, 1), (bob_pk, 1), (carol_pk, 1)], 2)?;
+//! let multisig_address = IotaAddress::from(&multisig_pk);
+//!
+//! let output = TransactionBuilder::new(tx)
+//!   .with_sender(multisig_address)
+//!   .with_multisig_signature(&client, multisig_pk, &[alice_signer, bob_signer])
+//!   .await?
+//!   .build_and_execute(&client)
+//!   .await?;
+//! ```
+
+use fastcrypto::traits::ToFromBytes as _;
+use iota_interaction::types::base_types::IotaAddress;
+use iota_interaction::types::crypto::{IotaSignature as _, PublicKey, Signature};
+pub use iota_interaction::types::multisig::MultiSigPublicKey;
+use iota_interaction::types::multisig::{MultiSig, ThresholdUnit, WeightUnit};
+use iota_interaction::types::signature::GenericSignature;
+use iota_interaction::types::transaction::TransactionData;
+use iota_interaction::{IotaKeySignature, OptionalSync};
+use iota_sdk_types::crypto::{Intent, IntentMessage};
+use secret_storage::Signer;
+
+use crate::Error;
+
+/// Creates a new [MultiSigPublicKey] out of its members' public keys and weights, and its threshold.
+pub fn multisig_public_key(
+  members: impl IntoIterator<Item = (PublicKey, WeightUnit)>,
+  threshold: ThresholdUnit,
+) -> Result<MultiSigPublicKey, Error> {
+  let (pks, weights) = members.into_iter().unzip();
+  MultiSigPublicKey::new(pks, weights, threshold)
+    .map_err(|e| Error::TransactionBuildingFailed(format!("invalid multisig public key: {e}")))
+}
+
+/// Collects the signatures of a multisig address' members on a given transaction,
+/// until they can be combined into a single [GenericSignature::MultiSig].
+#[derive(Debug, Clone)]
+pub struct MultisigSignatures {
+  multisig_pk: MultiSigPublicKey,
+  tx_data: TransactionData,
+  signatures: Vec<(Signature, WeightUnit)>,
+}
+
+impl MultisigSignatures {
+  /// Starts collecting signatures on `tx_data` for the multisig address derived from `multisig_pk`.
+  pub fn new(multisig_pk: MultiSigPublicKey, tx_data: TransactionData) -> Self {
+    Self {
+      multisig_pk,
+      tx_data,
+      signatures: vec![],
+    }
+  }
+
+  /// Returns the multisig address these signatures are collected for.
+  pub fn address(&self) -> IotaAddress {
+    IotaAddress::from(&self.multisig_pk)
+  }
+
+  /// Returns the multisig public key these signatures are collected for.
+  pub fn public_key(&self) -> &MultiSigPublicKey {
+    &self.multisig_pk
+  }
+
+  /// Returns the combined weight of the members that signed so far.
+  pub fn weight(&self) -> ThresholdUnit {
+    self
+      .signatures
+      .iter()
+      .map(|(_, weight)| ThresholdUnit::from(*weight))
+      .sum()
+  }
+
+  /// Returns whether enough members signed to reach the multisig threshold.
+  pub fn is_threshold_met(&self) -> bool {
+    self.weight() >= *self.multisig_pk.threshold()
+  }
+
+  /// Adds a member's signature.
+  /// ## Notes
+  /// This method fails if `signature` is not a valid signature for the transaction, if it wasn't produced by
+  /// one of the multisig's members, or if that member already signed.
+  pub fn add_signature(&mut self, signature: Signature) -> Result<(), Error> {
+    let pk = PublicKey::try_from_bytes(signature.scheme(), signature.public_key_bytes())
+      .map_err(|e| Error::TransactionSigningFailed(format!("invalid signature's public key: {e}")))?;
+    let weight = self
+      .multisig_pk
+      .pubkeys()
+      .iter()
+      .find_map(|(member_pk, weight)| (member_pk == &pk).then_some(*weight))
+      .ok_or_else(|| {
+        Error::TransactionSigningFailed(format!(
+          "public key {} is not a member of multisig address {}",
+          IotaAddress::from(&pk),
+          self.address()
+        ))
+      })?;
+
+    if self
+      .signatures
+      .iter()
+      .any(|(sig, _)| sig.public_key_bytes() == signature.public_key_bytes())
+    {
+      return Err(Error::TransactionSigningFailed(format!(
+        "member {} already signed",
+        IotaAddress::from(&pk)
+      )));
+    }
+
+    let intent_msg = IntentMessage::new(Intent::iota_transaction(), self.tx_data.clone());
+    signature
+      .verify_secure(&intent_msg, IotaAddress::from(&pk), signature.scheme())
+      .map_err(|e| Error::TransactionSigningFailed(format!("invalid member signature: {e}")))?;
+
+    self.signatures.push((signature, weight));
+    Ok(())
+  }
+
+  /// Signs the transaction with `signer`, which must be one of the multisig's members.
+  pub async fn sign<S>(&mut self, signer: &S) -> Result<(), Error>
+  where
+    S: Signer<IotaKeySignature> + OptionalSync,
+  {
+    let signature = signer
+      .sign(&self.tx_data)
+      .await
+      .map_err(|e| Error::TransactionSigningFailed(e.to_string()))?;
+
+    self.add_signature(signature)
+  }
+
+  /// Combines the collected signatures into a single multisig signature.
+  /// ## Notes
+  /// This method fails if the collected signatures don't meet the multisig threshold.
+  pub fn combine(self) -> Result<GenericSignature, Error> {
+    if !self.is_threshold_met() {
+      return Err(Error::TransactionSigningFailed(format!(
+        "collected signatures weight {} doesn't meet multisig threshold {}",
+        self.weight(),
+        self.multisig_pk.threshold()
+      )));
+    }
+
+    let signatures = self.signatures.into_iter().map(|(sig, _)| sig).collect();
+    MultiSig::combine(signatures, self.multisig_pk)
+      .map(GenericSignature::MultiSig)
+      .map_err(|e| Error::TransactionSigningFailed(format!("failed to combine multisig signatures: {e}")))
+  }
+}
+
+/// Checks that `multisig` is a valid signature for `tx_data`, that is, that every member's signature in it is valid
+/// and that the combined weight of those members meets the multisig threshold.
+/// ## Notes
+/// Only members whose keys use the Ed25519, Secp256k1, or Secp256r1 schemes are supported.
+pub(crate) fn verify_multisig(multisig: &MultiSig, tx_data: &TransactionData) -> Result<(), Error> {
+  let multisig_pk = multisig.get_pk();
+  let members = multisig_pk.pubkeys();
+  let indices = multisig
+    .get_indices()
+    .map_err(|e| Error::TransactionSigningFailed(format!("invalid multisig: {e}")))?;
+  let intent_msg = IntentMessage::new(Intent::iota_transaction(), tx_data.clone());
+
+  let mut weight: ThresholdUnit = 0;
+  for (compressed_sig, idx) in multisig.get_sigs().iter().zip(indices) {
+    let (member_pk, member_weight) = members
+      .get(idx as usize)
+      .ok_or_else(|| Error::TransactionSigningFailed(format!("invalid multisig: no member at index {idx}")))?;
+    let member_address = IotaAddress::from(member_pk);
+    // A member's signature is stored without its public key, which is taken from the multisig public key.
+    let signature_bytes = [&[member_pk.flag()], compressed_sig.as_ref(), member_pk.as_ref()].concat();
+    let signature = Signature::from_bytes(&signature_bytes).map_err(|e| {
+      Error::TransactionSigningFailed(format!(
+        "unsupported signature of multisig member {member_address}: {e}"
+      ))
+    })?;
+    signature
+      .verify_secure(&intent_msg, member_address, signature.scheme())
+      .map_err(|e| {
+        Error::TransactionSigningFailed(format!("invalid signature of multisig member {member_address}: {e}"))
+      })?;
+
+    weight += ThresholdUnit::from(*member_weight);
+  }
+
+  if weight < *multisig_pk.threshold() {
+    return Err(Error::TransactionSigningFailed(format!(
+      "signatures weight {weight} doesn't meet the threshold {} of multisig address {}",
+      multisig_pk.threshold(),
+      IotaAddress::from(multisig_pk)
+    )));
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use fastcrypto::ed25519::Ed25519KeyPair;
+  use futures::executor::block_on;
+  use iota_interaction::types::crypto::IotaKeyPair;
+  use iota_interaction::KeyPairSigner;
+
+  use super::*;
+  use crate::transaction::testing::{address, transaction_data};
+
+  fn member(seed: u8) -> KeyPairSigner {
+    let keypair = Ed25519KeyPair::from_bytes(&[seed; 32]).expect("valid private key");
+    KeyPairSigner::new(IotaKeyPair::Ed25519(keypair))
+  }
+
+  /// Returns three members weighing 1, 1, and 2, with a threshold of 2.
+  fn members_and_multisig_pk() -> ([KeyPairSigner; 3], MultiSigPublicKey) {
+    let members = [member(1), member(2), member(3)];
+    let multisig_pk = multisig_public_key(
+      [
+        (members[0].public_key(), 1),
+        (members[1].public_key(), 1),
+        (members[2].public_key(), 2),
+      ],
+      2,
+    )
+    .unwrap();
+
+    (members, multisig_pk)
+  }
+
+  #[test]
+  fn weight_accumulates_until_threshold_is_met() {
+    let ([alice, bob, _], multisig_pk) = members_and_multisig_pk();
+    let tx_data = transaction_data(IotaAddress::from(&multisig_pk), address(2));
+    let mut signatures = MultisigSignatures::new(multisig_pk, tx_data);
+
+    block_on(signatures.sign(&alice)).unwrap();
+    assert_eq!(signatures.weight(), 1);
+    assert!(!signatures.is_threshold_met());
+    assert!(signatures.clone().combine().is_err());
+
+    block_on(signatures.sign(&bob)).unwrap();
+    assert_eq!(signatures.weight(), 2);
+    assert!(signatures.is_threshold_met());
+  }
+
+  #[test]
+  fn duplicate_foreign_and_invalid_signatures_are_rejected() {
+    let ([alice, _, _], multisig_pk) = members_and_multisig_pk();
+    let tx_data = transaction_data(IotaAddress::from(&multisig_pk), address(2));
+    let other_tx_data = transaction_data(IotaAddress::from(&multisig_pk), address(4));
+    let mut signatures = MultisigSignatures::new(multisig_pk, tx_data);
+
+    let foreign_signature = block_on(signatures.sign(&member(4)));
+    assert!(foreign_signature.is_err());
+
+    let signature_on_other_tx = block_on(Signer::sign(&alice, &other_tx_data)).unwrap();
+    assert!(signatures.add_signature(signature_on_other_tx).is_err());
+
+    block_on(signatures.sign(&alice)).unwrap();
+    assert!(block_on(signatures.sign(&alice)).is_err());
+    assert_eq!(signatures.weight(), 1);
+  }
+
+  #[test]
+  fn combined_signature_is_only_valid_for_its_transaction() {
+    let ([_, _, carol], multisig_pk) = members_and_multisig_pk();
+    let tx_data = transaction_data(IotaAddress::from(&multisig_pk), address(2));
+    let other_tx_data = transaction_data(IotaAddress::from(&multisig_pk), address(4));
+    let mut signatures = MultisigSignatures::new(multisig_pk, tx_data.clone());
+    block_on(signatures.sign(&carol)).unwrap();
+
+    let GenericSignature::MultiSig(multisig) = signatures.combine().unwrap() else {
+      panic!("expected a multisig signature");
+    };
+    verify_multisig(&multisig, &tx_data).unwrap();
+    assert!(verify_multisig(&multisig, &other_tx_data).is_err());
+  }
+}
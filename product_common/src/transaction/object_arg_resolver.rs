@@ -0,0 +1,302 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resolution of the [CallArg]s of the objects used by a programmable transaction.
+//!
+//! Passing an object to a Move call requires knowing how it is owned: owned and immutable objects are referenced
+//! by their latest version, shared objects by the version they were shared at, and objects sent to another object
+//! must be received. [ObjectArgResolver] fetches the ownership of all the objects a transaction needs at once, and
+//! builds the right [CallArg] for each of them:
+//! ```ignore
+//! let mut resolver = client.object_arg_resolver();
+//! let identity = resolver.add(identity_id, ObjectAccess::Write);
+//! let controller_cap = resolver.add(controller_cap_id, ObjectAccess::Read);
+//! let args = resolver.resolve().await?;
+//!
+//! let identity = ptb.obj(args[identity].clone())?;
+//! let controller_cap = ptb.obj(args[controller_cap].clone())?;
+//! ```
+
+use std::collections::HashMap;
+use std::ops::Index;
+
+use iota_interaction::rpc_types::{IotaObjectDataOptions, IotaObjectResponse};
+use iota_interaction::types::transaction::{CallArg, SharedObjectRef};
+use iota_interaction::IotaClientTrait;
+use iota_sdk_types::{ObjectId, Owner};
+
+use crate::core_client::CoreClientReadOnly;
+use crate::Error;
+
+/// How a Move call accesses an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObjectAccess {
+  /// The object is passed by immutable reference.
+  Read,
+  /// The object is passed by mutable reference or by value.
+  Write,
+  /// The object was sent to another object and is received through `iota::transfer::receive`.
+  Receive,
+}
+
+impl ObjectAccess {
+  /// Returns the access that satisfies both `self` and `other`, if any.
+  fn merge(self, other: Self) -> Option<Self> {
+    match (self, other) {
+      (a, b) if a == b => Some(a),
+      (Self::Read, Self::Write) | (Self::Write, Self::Read) => Some(Self::Write),
+      _ => None,
+    }
+  }
+}
+
+/// A handle to an object added to an [ObjectArgResolver], used to retrieve its [CallArg] once resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectArgKey(usize);
+
+/// Collects the objects used by a programmable transaction and resolves their [CallArg]s with a single
+/// multi-get request.
+#[derive(Debug)]
+pub struct ObjectArgResolver<'c, C: ?Sized> {
+  client: &'c C,
+  requests: Vec<(ObjectId, ObjectAccess)>,
+}
+
+impl<'c, C> ObjectArgResolver<'c, C>
+where
+  C: CoreClientReadOnly + ?Sized,
+{
+  /// Returns an empty [ObjectArgResolver] that uses `client` to fetch objects.
+  pub fn new(client: &'c C) -> Self {
+    Self {
+      client,
+      requests: vec![],
+    }
+  }
+
+  /// Adds the object with the given ID, accessed as specified by `access`.
+  /// ## Notes
+  /// The same object can be added more than once: all its keys resolve to the same [CallArg], shared objects being
+  /// passed mutably if any of the accesses is [ObjectAccess::Write].
+  pub fn add(&mut self, object_id: ObjectId, access: ObjectAccess) -> ObjectArgKey {
+    self.requests.push((object_id, access));
+    ObjectArgKey(self.requests.len() - 1)
+  }
+
+  /// Fetches all the added objects and builds their [CallArg]s.
+  /// ## Errors
+  /// Fails if any of the objects doesn't exist, or cannot be accessed as requested - e.g. an immutable object that
+  /// is written, or a shared object that is received.
+  pub async fn resolve(self) -> Result<ResolvedObjectArgs, Error> {
+    let mut accesses = HashMap::<ObjectId, ObjectAccess>::new();
+    let mut object_ids = vec![];
+    for &(object_id, access) in &self.requests {
+      match accesses.get(&object_id).copied() {
+        None => {
+          accesses.insert(object_id, access);
+          object_ids.push(object_id);
+        }
+        Some(prev_access) => {
+          let merged = prev_access.merge(access).ok_or_else(|| {
+            Error::TransactionBuildingFailed(format!(
+              "object {object_id} cannot be accessed both as {prev_access:?} and {access:?}"
+            ))
+          })?;
+          accesses.insert(object_id, merged);
+        }
+      }
+    }
+
+    let responses = self
+      .client
+      .client_adapter()
+      .read_api()
+      .multi_get_object_with_options(object_ids.clone(), IotaObjectDataOptions::default().with_owner())
+      .await
+      .map_err(|e| Error::Network("failed to fetch the objects of the transaction".to_owned(), e))?;
+    if responses.len() != object_ids.len() {
+      return Err(Error::TransactionUnexpectedResponse(format!(
+        "requested {} objects but received {}",
+        object_ids.len(),
+        responses.len()
+      )));
+    }
+
+    let mut call_args = HashMap::with_capacity(object_ids.len());
+    for (object_id, response) in object_ids.into_iter().zip(responses) {
+      let call_arg = call_arg_for(object_id, response, accesses[&object_id])?;
+      call_args.insert(object_id, call_arg);
+    }
+
+    let call_args = self
+      .requests
+      .into_iter()
+      .map(|(object_id, _)| call_args[&object_id].clone())
+      .collect();
+
+    Ok(ResolvedObjectArgs(call_args))
+  }
+}
+
+/// The [CallArg]s resolved by an [ObjectArgResolver], indexed by [ObjectArgKey].
+#[derive(Debug, Clone)]
+pub struct ResolvedObjectArgs(Vec<CallArg>);
+
+impl ResolvedObjectArgs {
+  /// Returns the [CallArg] of the object identified by `key`.
+  pub fn get(&self, key: ObjectArgKey) -> &CallArg {
+    &self[key]
+  }
+
+  /// Returns all the resolved [CallArg]s, in the order their objects were added.
+  pub fn into_vec(self) -> Vec<CallArg> {
+    self.0
+  }
+}
+
+impl Index<ObjectArgKey> for ResolvedObjectArgs {
+  type Output = CallArg;
+
+  fn index(&self, key: ObjectArgKey) -> &Self::Output {
+    &self.0[key.0]
+  }
+}
+
+/// Builds the [CallArg] to access the given object as specified by `access`.
+fn call_arg_for(object_id: ObjectId, response: IotaObjectResponse, access: ObjectAccess) -> Result<CallArg, Error> {
+  let data = match (response.data, response.error) {
+    (Some(data), _) => data,
+    (None, Some(e)) => {
+      return Err(Error::TransactionBuildingFailed(format!(
+        "cannot use object {object_id}; {e}"
+      )))
+    }
+    (None, None) => {
+      return Err(Error::TransactionBuildingFailed(format!(
+        "object {object_id} does not exist"
+      )))
+    }
+  };
+  let object_ref = data.object_ref();
+  let owner = data
+    .owner
+    .ok_or_else(|| Error::TransactionUnexpectedResponse(format!("missing owner of object {object_id} in response")))?;
+
+  let invalid_access = |reason: &str| {
+    Err(Error::TransactionBuildingFailed(format!(
+      "object {object_id} cannot be accessed as {access:?}; {reason}"
+    )))
+  };
+  match (owner, access) {
+    (Owner::Shared(initial_shared_version), ObjectAccess::Read | ObjectAccess::Write) => {
+      Ok(CallArg::Shared(SharedObjectRef {
+        object_id,
+        initial_shared_version,
+        mutable: access == ObjectAccess::Write,
+      }))
+    }
+    (Owner::Shared(_), ObjectAccess::Receive) => invalid_access("it is a shared object"),
+    (Owner::Immutable, ObjectAccess::Read) => Ok(CallArg::ImmutableOrOwned(object_ref)),
+    (Owner::Immutable, _) => invalid_access("it is an immutable object"),
+    (Owner::Address(_), ObjectAccess::Receive) => Ok(CallArg::Receiving(object_ref)),
+    (Owner::Address(_), _) => Ok(CallArg::ImmutableOrOwned(object_ref)),
+    (Owner::Object(parent), _) => invalid_access(&format!("it is a dynamic field of object {parent}")),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use iota_interaction::rpc_types::IotaObjectData;
+
+  use super::*;
+  use crate::transaction::testing::{address, object_id, object_ref};
+
+  /// Returns the response of a node for object [object_id]`(1)`, owned by `owner`.
+  fn response(owner: Option<Owner>) -> IotaObjectResponse {
+    let object_ref = object_ref(1, 3);
+    IotaObjectResponse::new_with_data(IotaObjectData {
+      object_id: object_ref.object_id,
+      version: object_ref.version,
+      digest: object_ref.digest,
+      type_: None,
+      owner,
+      previous_transaction: None,
+      storage_rebate: None,
+      display: None,
+      content: None,
+      bcs: None,
+    })
+  }
+
+  fn call_arg(owner: Owner, access: ObjectAccess) -> Result<CallArg, Error> {
+    call_arg_for(object_id(1), response(Some(owner)), access)
+  }
+
+  #[test]
+  fn shared_objects_are_referenced_by_their_initial_version() {
+    let shared = Owner::Shared(2);
+    for (access, mutable) in [(ObjectAccess::Read, false), (ObjectAccess::Write, true)] {
+      let Ok(CallArg::Shared(shared_ref)) = call_arg(shared, access) else {
+        panic!("expected a shared object for {access:?}");
+      };
+      assert_eq!(shared_ref.object_id, object_id(1));
+      assert_eq!(shared_ref.initial_shared_version, 2);
+      assert_eq!(shared_ref.mutable, mutable);
+    }
+    assert!(call_arg(shared, ObjectAccess::Receive).is_err());
+  }
+
+  #[test]
+  fn owned_objects_are_referenced_by_their_latest_version() {
+    let owned = Owner::Address(address(1));
+    for access in [ObjectAccess::Read, ObjectAccess::Write] {
+      assert!(matches!(
+        call_arg(owned, access),
+        Ok(CallArg::ImmutableOrOwned(reference)) if reference == object_ref(1, 3)
+      ));
+    }
+    assert!(matches!(
+      call_arg(owned, ObjectAccess::Receive),
+      Ok(CallArg::Receiving(reference)) if reference == object_ref(1, 3)
+    ));
+  }
+
+  #[test]
+  fn immutable_objects_can_only_be_read() {
+    assert!(matches!(
+      call_arg(Owner::Immutable, ObjectAccess::Read),
+      Ok(CallArg::ImmutableOrOwned(_))
+    ));
+    assert!(call_arg(Owner::Immutable, ObjectAccess::Write).is_err());
+    assert!(call_arg(Owner::Immutable, ObjectAccess::Receive).is_err());
+  }
+
+  #[test]
+  fn dynamic_fields_cannot_be_used() {
+    let error = call_arg(Owner::Object(object_id(2)), ObjectAccess::Read).unwrap_err();
+    assert!(error.to_string().contains("dynamic field"), "{error}");
+  }
+
+  #[test]
+  fn missing_objects_and_owners_are_reported() {
+    let missing = IotaObjectResponse {
+      data: None,
+      error: None,
+    };
+    assert!(matches!(
+      call_arg_for(object_id(1), missing, ObjectAccess::Read),
+      Err(Error::TransactionBuildingFailed(_))
+    ));
+    assert!(matches!(
+      call_arg_for(object_id(1), response(None), ObjectAccess::Read),
+      Err(Error::TransactionUnexpectedResponse(_))
+    ));
+  }
+
+  #[test]
+  fn writes_supersede_reads() {
+    assert_eq!(ObjectAccess::Read.merge(ObjectAccess::Write), Some(ObjectAccess::Write));
+    assert_eq!(ObjectAccess::Read.merge(ObjectAccess::Read), Some(ObjectAccess::Read));
+    assert_eq!(ObjectAccess::Receive.merge(ObjectAccess::Read), None);
+  }
+}
@@ -0,0 +1,327 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed access to the objects created or mutated by a transaction.
+//!
+//! Transaction effects only list the references and owners of the objects a transaction touched. [ObjectChanges]
+//! resolves the Move type of those objects - fetching the types of all candidates in a single request - matching them
+//! against the Move type of a Rust type - see [MoveType] - for every version of a package. It's meant to be used in
+//! [Transaction::apply](super::Transaction::apply) implementations:
+//! ```ignore
+//! async fn apply<C>(self, effects: &mut IotaTransactionBlockEffects, client: &C) -> Result<Self::Output, Self::Error>
+//! where
+//!   C: CoreClientReadOnly + OptionalSync,
+//! {
+//!   let token_ref = ObjectChanges::new(effects, client)
+//!     .take_created::<Token>()
+//!     .await?
+//!     .ok_or_else(|| Error::TransactionUnexpectedResponse("no Token was created".to_owned()))?;
+//!   ...
+//! }
+//! ```
+//! Matched objects are removed from the effects, so that transactions batched together never claim the same object.
+
+use std::collections::{HashMap, HashSet};
+
+use iota_interaction::rpc_types::{IotaObjectDataOptions, IotaTransactionBlockEffects, OwnedObjectRef};
+use iota_interaction::types::base_types::IotaAddress;
+use iota_interaction::{IotaClientTrait, IotaTransactionBlockEffectsMutAPI as _, MoveType, OptionalSync};
+use iota_sdk_types::{ObjectId, Owner, StructTag};
+
+use super::events::struct_tags;
+use crate::core_client::CoreClientReadOnly;
+use crate::Error;
+
+/// The kind of change an object went through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChangeKind {
+  Created,
+  Mutated,
+}
+
+/// A view over a transaction's effects that extracts the objects it changed by their Move type.
+///
+/// The types of the objects are fetched from the network the first time they are needed, all at once.
+#[derive(Debug)]
+pub struct ObjectChanges<'a, C: ?Sized> {
+  effects: &'a mut IotaTransactionBlockEffects,
+  client: &'a C,
+  package_history: Vec<ObjectId>,
+  object_types: HashMap<ObjectId, Option<StructTag>>,
+}
+
+impl<'a, C> ObjectChanges<'a, C>
+where
+  C: CoreClientReadOnly + OptionalSync + ?Sized,
+{
+  /// Returns an [ObjectChanges] that matches objects whose type was defined in any version of `client`'s package,
+  /// see [CoreClientReadOnly::package_history].
+  pub fn new(effects: &'a mut IotaTransactionBlockEffects, client: &'a C) -> Self {
+    let package_history = client.package_history();
+    Self::with_package_history(effects, client, package_history)
+  }
+
+  /// Returns an [ObjectChanges] that matches objects whose type was defined in any of the given packages.
+  pub fn with_package_history(
+    effects: &'a mut IotaTransactionBlockEffects,
+    client: &'a C,
+    package_history: Vec<ObjectId>,
+  ) -> Self {
+    Self {
+      effects,
+      client,
+      package_history,
+      object_types: HashMap::new(),
+    }
+  }
+
+  /// Removes the first created object of type `T` from the effects and returns it, if any.
+  pub async fn take_created<T: MoveType>(&mut self) -> Result<Option<OwnedObjectRef>, Error> {
+    self.take_first::<T>(ChangeKind::Created, |_| true).await
+  }
+
+  /// Removes the first created shared object of type `T` from the effects and returns it, if any.
+  pub async fn take_created_shared<T: MoveType>(&mut self) -> Result<Option<OwnedObjectRef>, Error> {
+    self
+      .take_first::<T>(ChangeKind::Created, |owner| matches!(owner, Owner::Shared(_)))
+      .await
+  }
+
+  /// Removes all the created objects of type `T` from the effects and returns them.
+  pub async fn take_all_created<T: MoveType>(&mut self) -> Result<Vec<OwnedObjectRef>, Error> {
+    self.take_all::<T>(ChangeKind::Created).await
+  }
+
+  /// Removes the first mutated object of type `T` from the effects and returns it, if any.
+  pub async fn take_mutated<T: MoveType>(&mut self) -> Result<Option<OwnedObjectRef>, Error> {
+    self.take_first::<T>(ChangeKind::Mutated, |_| true).await
+  }
+
+  /// Removes all the mutated objects of type `T` from the effects and returns them.
+  pub async fn take_all_mutated<T: MoveType>(&mut self) -> Result<Vec<OwnedObjectRef>, Error> {
+    self.take_all::<T>(ChangeKind::Mutated).await
+  }
+
+  /// Removes all the mutated objects owned by `owner` from the effects and returns them, regardless of their type.
+  pub fn take_mutated_owned_by(&mut self, owner: IotaAddress) -> Vec<OwnedObjectRef> {
+    let (owned, others) = std::mem::take(self.effects.mutated_mut())
+      .into_iter()
+      .partition(|object| object.owner == Owner::Address(owner));
+    *self.effects.mutated_mut() = others;
+
+    owned
+  }
+
+  fn changes_mut(&mut self, kind: ChangeKind) -> &mut Vec<OwnedObjectRef> {
+    match kind {
+      ChangeKind::Created => self.effects.created_mut(),
+      ChangeKind::Mutated => self.effects.mutated_mut(),
+    }
+  }
+
+  async fn take_first<T: MoveType>(
+    &mut self,
+    kind: ChangeKind,
+    owner_filter: impl Fn(&Owner) -> bool,
+  ) -> Result<Option<OwnedObjectRef>, Error> {
+    let expected_types = struct_tags::<T>(&self.package_history);
+    let candidates = self
+      .changes_mut(kind)
+      .iter()
+      .filter(|object| owner_filter(&object.owner))
+      .map(|object| object.object_id())
+      .collect::<Vec<_>>();
+
+    self.resolve_types(&candidates).await?;
+
+    let Some(object_id) = candidates
+      .into_iter()
+      .find(|object_id| self.is_of_type(*object_id, &expected_types))
+    else {
+      return Ok(None);
+    };
+    let changes = self.changes_mut(kind);
+    let idx = changes
+      .iter()
+      .position(|object| object.object_id() == object_id)
+      .expect("candidate comes from this list");
+
+    Ok(Some(changes.remove(idx)))
+  }
+
+  async fn take_all<T: MoveType>(&mut self, kind: ChangeKind) -> Result<Vec<OwnedObjectRef>, Error> {
+    let expected_types = struct_tags::<T>(&self.package_history);
+    let object_ids = self
+      .changes_mut(kind)
+      .iter()
+      .map(|object| object.object_id())
+      .collect::<Vec<_>>();
+    // Resolve all types beforehand, so that no object is removed if any type cannot be resolved.
+    self.resolve_types(&object_ids).await?;
+    let matching = object_ids
+      .into_iter()
+      .filter(|object_id| self.is_of_type(*object_id, &expected_types))
+      .collect::<HashSet<_>>();
+
+    let (matched, others) = std::mem::take(self.changes_mut(kind))
+      .into_iter()
+      .partition(|object| matching.contains(&object.object_id()));
+    *self.changes_mut(kind) = others;
+
+    Ok(matched)
+  }
+
+  /// Returns whether the object with the given ID - whose type must have been resolved - has any of the expected
+  /// types.
+  fn is_of_type(&self, object_id: ObjectId, expected_types: &[StructTag]) -> bool {
+    self
+      .object_types
+      .get(&object_id)
+      .and_then(Option::as_ref)
+      .is_some_and(|object_type| expected_types.contains(object_type))
+  }
+
+  /// Fetches the types of the given objects that haven't been resolved yet, in a single request.
+  async fn resolve_types(&mut self, object_ids: &[ObjectId]) -> Result<(), Error> {
+    let unresolved = object_ids
+      .iter()
+      .copied()
+      .filter(|object_id| !self.object_types.contains_key(object_id))
+      .collect::<Vec<_>>();
+    if unresolved.is_empty() {
+      return Ok(());
+    }
+
+    let responses = self
+      .client
+      .client_adapter()
+      .read_api()
+      .multi_get_object_with_options(unresolved.clone(), IotaObjectDataOptions::default().with_type())
+      .await
+      .map_err(|e| Error::Network("failed to fetch the types of the changed objects".to_owned(), e))?;
+    if responses.len() != unresolved.len() {
+      return Err(Error::TransactionUnexpectedResponse(format!(
+        "requested {} objects but received {}",
+        unresolved.len(),
+        responses.len()
+      )));
+    }
+
+    for (object_id, response) in unresolved.into_iter().zip(responses) {
+      let object_type = match response.data.and_then(|data| data.type_) {
+        Some(object_type) => parse_object_type(object_id, &object_type.to_string())?,
+        None => None,
+      };
+      self.object_types.insert(object_id, object_type);
+    }
+
+    Ok(())
+  }
+}
+
+/// Parses the type of the object with the given ID, as returned by a node; `None` means the object is a package.
+fn parse_object_type(object_id: ObjectId, object_type: &str) -> Result<Option<StructTag>, Error> {
+  if object_type == "package" {
+    return Ok(None);
+  }
+
+  object_type.parse().map(Some).map_err(|e| {
+    Error::TransactionUnexpectedResponse(format!("invalid type `{object_type}` of object {object_id}: {e}"))
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr as _;
+
+  use futures::executor::block_on;
+  use iota_interaction::rpc_types::IotaTransactionBlockEffectsAPI as _;
+  use iota_sdk_types::TypeTag;
+  use serde::Serialize;
+
+  use super::*;
+  use crate::transaction::testing::{address, effects, object_id, object_ref, owned_by, OfflineClient};
+
+  #[derive(Serialize)]
+  struct Token;
+
+  impl MoveType for Token {
+    fn move_type(package: ObjectId) -> TypeTag {
+      TypeTag::from_str(&format!("{package}::token::Token")).expect("valid type tag")
+    }
+  }
+
+  fn struct_tag(package: ObjectId, name: &str) -> StructTag {
+    StructTag::from_str(&format!("{package}::token::{name}")).expect("valid struct tag")
+  }
+
+  /// Returns the effects of a transaction that created objects 1 - a `Token` -, 2 - a shared `Token` - and 3 - a
+  /// `Cap` - alongside the types of those objects.
+  fn effects_with_types(package: ObjectId) -> (IotaTransactionBlockEffects, HashMap<ObjectId, Option<StructTag>>) {
+    let mut shared_token = owned_by(address(1), object_ref(2, 1));
+    shared_token.owner = Owner::Shared(1);
+    let created = vec![
+      owned_by(address(1), object_ref(1, 1)),
+      shared_token,
+      owned_by(address(1), object_ref(3, 1)),
+    ];
+    let object_types = HashMap::from([
+      (object_id(1), Some(struct_tag(package, "Token"))),
+      (object_id(2), Some(struct_tag(package, "Token"))),
+      (object_id(3), Some(struct_tag(package, "Cap"))),
+    ]);
+
+    (
+      effects(created, vec![], owned_by(address(1), object_ref(9, 2))),
+      object_types,
+    )
+  }
+
+  #[test]
+  fn objects_are_matched_by_type_and_owner() {
+    let (package, client) = (object_id(0xaa), OfflineClient::new());
+    let (mut effects, object_types) = effects_with_types(package);
+    let mut changes = ObjectChanges::with_package_history(&mut effects, &client, vec![object_id(0xbb), package]);
+    // The offline client would panic if any type was fetched.
+    changes.object_types = object_types;
+
+    let shared = block_on(changes.take_created_shared::<Token>()).unwrap().unwrap();
+    assert_eq!(shared.object_id(), object_id(2));
+    let owned = block_on(changes.take_all_created::<Token>()).unwrap();
+    assert_eq!(
+      owned.iter().map(|object| object.object_id()).collect::<Vec<_>>(),
+      [object_id(1)]
+    );
+    assert!(block_on(changes.take_created::<Token>()).unwrap().is_none());
+
+    // Only the `Cap` is left.
+    assert_eq!(effects.created().len(), 1);
+  }
+
+  #[test]
+  fn objects_of_other_packages_are_not_matched() {
+    let client = OfflineClient::new();
+    let (mut effects, object_types) = effects_with_types(object_id(0xaa));
+    let mut changes = ObjectChanges::with_package_history(&mut effects, &client, vec![object_id(0xbb)]);
+    changes.object_types = object_types;
+
+    assert!(block_on(changes.take_all_created::<Token>()).unwrap().is_empty());
+    assert_eq!(effects.created().len(), 3);
+  }
+
+  #[test]
+  fn object_types_are_parsed() {
+    let package = object_id(0xaa);
+    let object_type = format!("{package}::token::Token");
+
+    assert_eq!(
+      parse_object_type(object_id(1), &object_type).unwrap(),
+      Some(struct_tag(package, "Token"))
+    );
+    assert_eq!(parse_object_type(object_id(1), "package").unwrap(), None);
+    assert!(matches!(
+      parse_object_type(object_id(1), "not a type"),
+      Err(Error::TransactionUnexpectedResponse(_))
+    ));
+  }
+}
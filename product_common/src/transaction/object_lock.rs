@@ -0,0 +1,490 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Client-side locking of owned objects, to safely submit several transactions concurrently.
+//!
+//! Two transactions that use the same owned object - e.g. the same gas coin - at the same version cannot both be
+//! executed, and submitting them concurrently might lock the object until the end of the epoch. An
+//! [ObjectLockManager] keeps track of the owned objects - gas coins included - used by in-flight transactions, so
+//! that a [TransactionBuilder](super::TransactionBuilder) using it never picks an object that is already in use.
+//!
+//! An [ObjectLockManager] can also hold a pool of gas coins for a given address. Splitting a coin into several
+//! smaller ones through [ObjectLockManager::split_gas_coins] allows that many transactions to be executed in parallel.
+//!
+//! ## Example
+//! ```ignore
+//! let lock_manager = ObjectLockManager::new();
+//! lock_manager.split_gas_coins(&client, 8, 500_000_000).await?;
+//!
+//! let outputs = futures::future::try_join_all(txs.into_iter().map(|tx| {
+//!   TransactionBuilder::new(tx)
+//!     .with_object_lock_manager(lock_manager.clone())
+//!     .build_and_execute(&client)
+//! }))
+//! .await?;
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use iota_interaction::rpc_types::{
+  IotaObjectDataOptions, IotaRawData, IotaTransactionBlockEffects, IotaTransactionBlockEffectsAPI as _,
+};
+use iota_interaction::types::base_types::{IotaAddress, ObjectRef};
+use iota_interaction::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use iota_interaction::types::transaction::{CallArg, ProgrammableTransaction, TransactionData, TransactionKind};
+use iota_interaction::{IotaClientTrait, IotaKeySignature, IotaTransactionBlockEffectsMutAPI as _, OptionalSync};
+use iota_sdk_types::{Argument, ObjectId, Owner};
+use secret_storage::Signer;
+
+use super::coin_management::CoinContents;
+use super::retry::sleep;
+use super::sponsor_policy::command_arguments;
+use super::{Transaction, TransactionBuilder};
+use crate::core_client::{CoreClient, CoreClientReadOnly};
+use crate::Error;
+
+/// The maximum time a [TransactionBuilder](super::TransactionBuilder) waits for its input objects to be unlocked.
+pub const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone)]
+struct PooledCoin {
+  object_ref: ObjectRef,
+  balance: u64,
+}
+
+#[derive(Debug, Default)]
+struct LockState {
+  locked: HashSet<ObjectId>,
+  gas_pools: HashMap<IotaAddress, Vec<PooledCoin>>,
+}
+
+/// Keeps track of the owned objects used by in-flight transactions, see the [module level documentation](self).
+///
+/// Cloning an [ObjectLockManager] returns a handle to the same set of locks.
+#[derive(Debug, Clone, Default)]
+pub struct ObjectLockManager(Arc<Mutex<LockState>>);
+
+impl ObjectLockManager {
+  /// Returns a new [ObjectLockManager], with no locked objects nor pooled gas coins.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  fn state(&self) -> std::sync::MutexGuard<'_, LockState> {
+    self.0.lock().expect("not poisoned")
+  }
+
+  /// Returns whether the object with the given ID is locked.
+  pub fn is_locked(&self, object_id: ObjectId) -> bool {
+    self.state().locked.contains(&object_id)
+  }
+
+  /// Locks all the given objects, or none of them if any of them is already locked.
+  pub fn try_lock(&self, objects: impl IntoIterator<Item = ObjectId>) -> Option<ObjectLockGuard> {
+    let objects = objects.into_iter().collect::<HashSet<_>>();
+    let mut state = self.state();
+    if objects.iter().any(|object| state.locked.contains(object)) {
+      return None;
+    }
+    state.locked.extend(objects.iter().copied());
+
+    Some(ObjectLockGuard {
+      manager: self.clone(),
+      objects: objects.into_iter().collect(),
+    })
+  }
+
+  /// Locks all the given objects, waiting up to `timeout` for the ones that are already locked to be released.
+  pub async fn lock(&self, objects: Vec<ObjectId>, timeout: Duration) -> Result<ObjectLockGuard, Error> {
+    let max_polls = timeout.as_millis().div_ceil(LOCK_POLL_INTERVAL.as_millis());
+    for poll in 0..=max_polls {
+      if poll > 0 {
+        sleep(LOCK_POLL_INTERVAL).await;
+      }
+      if let Some(guard) = self.try_lock(objects.iter().copied()) {
+        return Ok(guard);
+      }
+    }
+
+    Err(Error::TransactionBuildingFailed(format!(
+      "timed out waiting for objects [{}] to be unlocked",
+      objects.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )))
+  }
+
+  /// Forcibly unlocks the given objects, e.g. objects of a transaction whose outcome is unknown that
+  /// turned out not to be executed.
+  pub fn release(&self, objects: &[ObjectId]) {
+    let mut state = self.state();
+    for object in objects {
+      state.locked.remove(object);
+    }
+  }
+
+  /// Adds the given coins, alongside their balances, to `owner`'s gas coin pool.
+  /// ## Notes
+  /// Pooled coins are preferred over any other coin when paying for the gas of `owner`'s transactions. They are
+  /// expected to be used exclusively as gas payment, as their balance is tracked locally.
+  pub fn add_gas_coins(&self, owner: IotaAddress, coins: impl IntoIterator<Item = (ObjectRef, u64)>) {
+    let mut state = self.state();
+    let pool = state.gas_pools.entry(owner).or_default();
+    for (object_ref, balance) in coins {
+      pool.retain(|coin| coin.object_ref.object_id != object_ref.object_id);
+      pool.push(PooledCoin { object_ref, balance });
+    }
+  }
+
+  /// Returns the number of coins in `owner`'s gas coin pool that are not currently locked.
+  pub fn available_gas_coins(&self, owner: IotaAddress) -> usize {
+    let state = self.state();
+    state.gas_pools.get(&owner).map_or(0, |pool| {
+      pool
+        .iter()
+        .filter(|coin| !state.locked.contains(&coin.object_ref.object_id))
+        .count()
+    })
+  }
+
+  /// Splits `count` coins of `amount` each out of the client's coins, adding them to the client's gas coin pool.
+  pub async fn split_gas_coins<C, S>(&self, client: &C, count: usize, amount: u64) -> Result<Vec<ObjectRef>, Error>
+  where
+    C: CoreClient<S> + OptionalSync,
+    S: Signer<IotaKeySignature> + OptionalSync,
+  {
+    let tx = SplitGasCoins {
+      owner: client.sender_address(),
+      count,
+      amount,
+      lock_manager: self.clone(),
+    };
+    let output = TransactionBuilder::new(tx)
+      .with_object_lock_manager(self.clone())
+      .build_and_execute(client)
+      .await?;
+
+    Ok(output.output)
+  }
+
+  /// Locks a coin from `owner`'s gas coin pool whose balance covers `budget`, picking the smallest one.
+  pub(crate) fn lock_pooled_gas_coin(&self, owner: IotaAddress, budget: u64) -> Option<(ObjectRef, ObjectLockGuard)> {
+    let object_ref = {
+      let state = self.state();
+      state
+        .gas_pools
+        .get(&owner)?
+        .iter()
+        .filter(|coin| coin.balance >= budget && !state.locked.contains(&coin.object_ref.object_id))
+        .min_by_key(|coin| coin.balance)?
+        .object_ref
+        .clone()
+    };
+
+    let object_id = object_ref.object_id;
+    self.try_lock([object_id]).map(|guard| (object_ref, guard))
+  }
+
+  /// Returns whether the coin with the given ID is part of any gas coin pool.
+  fn is_pooled_gas_coin(&self, coin_id: ObjectId) -> bool {
+    self
+      .state()
+      .gas_pools
+      .values()
+      .flatten()
+      .any(|coin| coin.object_ref.object_id == coin_id)
+  }
+
+  /// Updates the pooled gas coins that were used by the executed transaction with the given effects.
+  /// `gas_coin_balance`, when known, is the balance of the transaction's gas coin after its execution; otherwise
+  /// it's computed out of the gas the transaction used.
+  fn update_gas_pools(&self, effects: &IotaTransactionBlockEffects, gas_coin_balance: Option<u64>) {
+    let gas_object = effects.gas_object();
+    let net_gas_usage = effects.gas_cost_summary().net_gas_usage();
+    let deleted = effects
+      .deleted()
+      .iter()
+      .map(|object_ref| object_ref.object_id)
+      .collect::<HashSet<_>>();
+
+    let mut state = self.state();
+    for (owner, pool) in state.gas_pools.iter_mut() {
+      pool.retain(|coin| !deleted.contains(&coin.object_ref.object_id));
+      let Some(idx) = pool
+        .iter()
+        .position(|coin| coin.object_ref.object_id == gas_object.reference.object_id)
+      else {
+        continue;
+      };
+      // The gas coin might have been transferred away through `Argument::GasCoin`.
+      if gas_object.owner != Owner::Address(*owner) {
+        pool.remove(idx);
+        continue;
+      }
+
+      let coin = &mut pool[idx];
+      coin.object_ref = gas_object.reference.clone();
+      coin.balance = match gas_coin_balance {
+        Some(balance) => balance,
+        None if net_gas_usage >= 0 => coin.balance.saturating_sub(net_gas_usage as u64),
+        None => coin.balance.saturating_add(net_gas_usage.unsigned_abs()),
+      };
+    }
+  }
+
+  /// Removes the coin with the given ID from any gas coin pool.
+  fn remove_gas_coin(&self, coin_id: ObjectId) {
+    for pool in self.state().gas_pools.values_mut() {
+      pool.retain(|coin| coin.object_ref.object_id != coin_id);
+    }
+  }
+}
+
+/// The objects locked for a single transaction. They are unlocked when this guard is dropped.
+#[derive(Debug)]
+#[must_use = "objects are unlocked as soon as the guard is dropped"]
+pub struct ObjectLockGuard {
+  manager: ObjectLockManager,
+  objects: Vec<ObjectId>,
+}
+
+impl ObjectLockGuard {
+  /// Returns the IDs of the locked objects.
+  pub fn objects(&self) -> &[ObjectId] {
+    &self.objects
+  }
+
+  /// Consumes this guard without unlocking its objects, which will remain locked until
+  /// explicitly released through [ObjectLockManager::release].
+  pub fn keep_locked(mut self) {
+    self.objects.clear();
+  }
+
+  /// Moves the objects locked by `other` into this guard.
+  pub(crate) fn merge(&mut self, mut other: ObjectLockGuard) {
+    self.objects.append(&mut other.objects);
+  }
+
+  /// Unlocks this guard's objects after the transaction using them has been executed with the given effects.
+  /// `gas_coin_used` tells whether the transaction used its gas coin as an argument - see [uses_gas_coin].
+  /// ## Notes
+  /// The balance of a pooled gas coin used as an argument cannot be told from the transaction's effects, thus it's
+  /// fetched anew. Should that fail, the coin is removed from its pool.
+  pub(crate) async fn settle<C>(self, effects: &IotaTransactionBlockEffects, gas_coin_used: bool, client: &C)
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let gas_coin_id = effects.gas_object().reference.object_id;
+    if !gas_coin_used || effects.status().is_err() || !self.manager.is_pooled_gas_coin(gas_coin_id) {
+      self.manager.update_gas_pools(effects, None);
+      return;
+    }
+
+    match fetch_coin_balance(client, gas_coin_id).await {
+      Ok(balance) => self.manager.update_gas_pools(effects, Some(balance)),
+      Err(_) => {
+        self.manager.update_gas_pools(effects, None);
+        self.manager.remove_gas_coin(gas_coin_id);
+      }
+    }
+  }
+}
+
+impl Drop for ObjectLockGuard {
+  fn drop(&mut self) {
+    if !self.objects.is_empty() {
+      self.manager.release(&self.objects);
+    }
+  }
+}
+
+/// Returns the IDs of the owned objects used as inputs by `pt`.
+pub(crate) fn owned_input_objects(pt: &ProgrammableTransaction) -> Vec<ObjectId> {
+  pt.inputs
+    .iter()
+    .filter_map(|input| match input {
+      CallArg::ImmutableOrOwned(object_ref) | CallArg::Receiving(object_ref) => Some(object_ref.object_id),
+      _ => None,
+    })
+    .collect()
+}
+
+/// Returns whether `tx_data` uses its gas coin beyond paying for gas, i.e. through [Argument::GasCoin].
+pub(crate) fn uses_gas_coin(tx_data: &TransactionData) -> bool {
+  #[allow(irrefutable_let_patterns)]
+  let TransactionKind::Programmable(pt) = tx_data.kind() else {
+    return false;
+  };
+
+  pt.commands
+    .iter()
+    .any(|command| command_arguments(command).contains(&&Argument::GasCoin))
+}
+
+/// Fetches the balance of the IOTA coin with the given ID.
+async fn fetch_coin_balance<C>(client: &C, coin_id: ObjectId) -> Result<u64, Error>
+where
+  C: CoreClientReadOnly + OptionalSync,
+{
+  let response = client
+    .client_adapter()
+    .read_api()
+    .get_object_with_options(coin_id, IotaObjectDataOptions::default().with_bcs())
+    .await
+    .map_err(|e| Error::Network(format!("failed to fetch coin {coin_id}"), e))?;
+  let Some(IotaRawData::MoveObject(object)) = response.data.and_then(|data| data.bcs) else {
+    return Err(Error::TransactionUnexpectedResponse(format!(
+      "missing content of coin {coin_id}"
+    )));
+  };
+
+  object
+    .deserialize::<CoinContents>()
+    .map(|coin| coin.balance)
+    .map_err(|e| Error::TransactionUnexpectedResponse(format!("invalid coin {coin_id}: {e}")))
+}
+
+/// Splits the sender's gas coin into several coins of the same amount, adding them to a gas coin pool.
+#[derive(Debug)]
+struct SplitGasCoins {
+  owner: IotaAddress,
+  count: usize,
+  amount: u64,
+  lock_manager: ObjectLockManager,
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl Transaction for SplitGasCoins {
+  type Output = Vec<ObjectRef>;
+  type Error = Error;
+
+  async fn build_programmable_transaction<C>(&self, _client: &C) -> Result<ProgrammableTransaction, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    ptb
+      .pay_iota(vec![self.owner; self.count], vec![self.amount; self.count])
+      .map_err(|e| Error::TransactionBuildingFailed(format!("failed to split gas coins: {e}")))?;
+
+    Ok(ptb.finish())
+  }
+
+  async fn apply<C>(self, effects: &mut IotaTransactionBlockEffects, _client: &C) -> Result<Self::Output, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    // This transaction is never batched, thus all created objects are the split coins.
+    let coins = std::mem::take(effects.created_mut())
+      .into_iter()
+      .map(|created| created.reference)
+      .collect::<Vec<_>>();
+    if coins.len() != self.count {
+      return Err(Error::TransactionUnexpectedResponse(format!(
+        "expected {} coins to be created, but {} were",
+        self.count,
+        coins.len()
+      )));
+    }
+    self
+      .lock_manager
+      .add_gas_coins(self.owner, coins.iter().map(|coin| (coin.clone(), self.amount)));
+
+    Ok(coins)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use futures::executor::block_on;
+
+  use super::*;
+  use crate::transaction::testing::{
+    address, effects, object_ref, owned_by, programmable_transaction_data, OfflineClient,
+  };
+
+  #[test]
+  fn locking_is_all_or_nothing() {
+    let manager = ObjectLockManager::new();
+    let (a, b, c) = (ObjectId::new([1; 32]), ObjectId::new([2; 32]), ObjectId::new([3; 32]));
+
+    let guard = manager.try_lock([a, b]).expect("nothing is locked");
+    assert!(manager.try_lock([b, c]).is_none());
+    assert!(!manager.is_locked(c));
+
+    drop(guard);
+    assert!(manager.try_lock([a, b, c]).is_some());
+  }
+
+  #[test]
+  fn kept_locks_must_be_released() {
+    let manager = ObjectLockManager::new();
+    let object = ObjectId::new([1; 32]);
+
+    manager.try_lock([object]).expect("nothing is locked").keep_locked();
+    assert!(manager.is_locked(object));
+
+    manager.release(&[object]);
+    assert!(!manager.is_locked(object));
+  }
+
+  #[test]
+  fn splitting_gas_coins_uses_the_gas_coin() {
+    let owner = address(1);
+    let split = SplitGasCoins {
+      owner,
+      count: 4,
+      amount: 1_000,
+      lock_manager: ObjectLockManager::new(),
+    };
+    let pt = block_on(split.build_programmable_transaction(&OfflineClient::new())).unwrap();
+    assert!(uses_gas_coin(&programmable_transaction_data(pt, owner, owner)));
+
+    let empty_pt = ProgrammableTransactionBuilder::new().finish();
+    assert!(!uses_gas_coin(&programmable_transaction_data(empty_pt, owner, owner)));
+  }
+
+  #[test]
+  fn pooled_gas_coin_balance_is_refreshed() {
+    let manager = ObjectLockManager::new();
+    let owner = address(1);
+    manager.add_gas_coins(owner, [(object_ref(1, 1), 1_000)]);
+
+    // Only 400 are left after splitting some IOTA out of the gas coin.
+    manager.update_gas_pools(&effects(vec![], vec![], owned_by(owner, object_ref(1, 2))), Some(400));
+
+    assert!(manager.lock_pooled_gas_coin(owner, 500).is_none());
+    let (coin, _guard) = manager.lock_pooled_gas_coin(owner, 400).expect("enough balance");
+    assert_eq!(coin, object_ref(1, 2));
+  }
+
+  #[test]
+  fn gas_coin_balance_is_tracked_when_only_paying_for_gas() {
+    let manager = ObjectLockManager::new();
+    let owner = address(1);
+    manager.add_gas_coins(owner, [(object_ref(1, 1), 1_000)]);
+    let (_, guard) = manager.lock_pooled_gas_coin(owner, 1_000).expect("enough balance");
+
+    // The offline client would panic if the coin's balance was fetched.
+    let effects = effects(vec![], vec![], owned_by(owner, object_ref(1, 2)));
+    block_on(guard.settle(&effects, false, &OfflineClient::new()));
+
+    let (coin, _guard) = manager.lock_pooled_gas_coin(owner, 1_000).expect("no gas was used");
+    assert_eq!(coin, object_ref(1, 2));
+  }
+
+  #[test]
+  fn gas_coins_transferred_away_leave_the_pool() {
+    let manager = ObjectLockManager::new();
+    let owner = address(1);
+    manager.add_gas_coins(owner, [(object_ref(1, 1), 1_000)]);
+
+    manager.update_gas_pools(&effects(vec![], vec![], owned_by(address(2), object_ref(1, 2))), None);
+
+    assert_eq!(manager.available_gas_coins(owner), 0);
+  }
+}
@@ -0,0 +1,398 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Validation of transactions against the limits of the protocol the network runs.
+//!
+//! A transaction exceeding any protocol limit is rejected by the network, with an error that hardly points to what
+//! is wrong with it - and only after it has been signed. [ProtocolLimits] checks a transaction beforehand, reporting
+//! the exact limit it exceeds and what exceeds it, e.g. `command 37 exceeds max_pure_argument_size (16384 > 16000)`.
+//!
+//! [TransactionBuilder](super::TransactionBuilder) validates transactions against the limits of the network's current
+//! protocol version before signing them.
+
+use std::fmt;
+
+use iota_interaction::types::transaction::{
+  CallArg, Command, TransactionData, TransactionDataAPI as _, TransactionKind,
+};
+use iota_protocol_config::{Chain, ProtocolConfig, ProtocolVersion};
+use iota_sdk_types::{Argument, TypeTag};
+use serde::{Deserialize, Serialize};
+
+/// A protocol limit a transaction must comply with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+#[non_exhaustive]
+pub enum ProtocolLimit {
+  /// The maximum number of commands of a programmable transaction.
+  MaxProgrammableTxCommands,
+  /// The maximum size, in bytes, of a pure argument.
+  MaxPureArgumentSize,
+  /// The maximum number of objects a transaction takes as input, gas payment included.
+  MaxInputObjects,
+  /// The maximum number of coins used to pay for gas.
+  MaxGasPaymentObjects,
+  /// The maximum size, in bytes, of a transaction.
+  MaxTxSizeBytes,
+  /// The maximum nesting depth of a type argument.
+  MaxTypeArgumentDepth,
+}
+
+/// The part of a transaction that exceeds a [ProtocolLimit].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitScope {
+  /// The transaction as a whole.
+  Transaction,
+  /// The command at the given index.
+  Command(usize),
+  /// The input at the given index, which no command uses.
+  Input(usize),
+}
+
+impl fmt::Display for LimitScope {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::Transaction => write!(f, "transaction"),
+      Self::Command(idx) => write!(f, "command {idx}"),
+      Self::Input(idx) => write!(f, "input {idx}"),
+    }
+  }
+}
+
+/// A transaction exceeding a [ProtocolLimit].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{scope} exceeds {limit} ({value} > {max})")]
+pub struct ProtocolLimitViolation {
+  /// The exceeded limit.
+  pub limit: ProtocolLimit,
+  /// What exceeds the limit.
+  pub scope: LimitScope,
+  /// The value that exceeds the limit.
+  pub value: u64,
+  /// The value of the limit.
+  pub max: u64,
+}
+
+/// The limits of a protocol version that are checked before submitting a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolLimits {
+  /// See [ProtocolLimit::MaxProgrammableTxCommands].
+  pub max_programmable_tx_commands: u64,
+  /// See [ProtocolLimit::MaxPureArgumentSize].
+  pub max_pure_argument_size: u64,
+  /// See [ProtocolLimit::MaxInputObjects].
+  pub max_input_objects: u64,
+  /// See [ProtocolLimit::MaxGasPaymentObjects].
+  pub max_gas_payment_objects: u64,
+  /// See [ProtocolLimit::MaxTxSizeBytes].
+  pub max_tx_size_bytes: u64,
+  /// See [ProtocolLimit::MaxTypeArgumentDepth].
+  pub max_type_argument_depth: u64,
+}
+
+impl ProtocolLimits {
+  /// Returns the limits of the given protocol version, if it is known to this library.
+  pub fn for_version(protocol_version: u64) -> Option<Self> {
+    let config = ProtocolConfig::get_for_version_if_supported(ProtocolVersion::new(protocol_version), Chain::Unknown)?;
+
+    Some(Self {
+      max_programmable_tx_commands: config.max_programmable_tx_commands().into(),
+      max_pure_argument_size: config.max_pure_argument_size().into(),
+      max_input_objects: config.max_input_objects(),
+      max_gas_payment_objects: config.max_gas_payment_objects().into(),
+      max_tx_size_bytes: config.max_tx_size_bytes(),
+      max_type_argument_depth: config.max_type_argument_depth().into(),
+    })
+  }
+
+  /// Checks `tx_data` against these limits, returning the first limit it exceeds.
+  /// ## Notes
+  /// A transaction's size is checked without its signatures, which count towards [ProtocolLimit::MaxTxSizeBytes]
+  /// too.
+  pub fn validate(&self, tx_data: &TransactionData) -> Result<(), ProtocolLimitViolation> {
+    let check = |limit, scope, value: u64, max: u64| {
+      if value > max {
+        Err(ProtocolLimitViolation {
+          limit,
+          scope,
+          value,
+          max,
+        })
+      } else {
+        Ok(())
+      }
+    };
+
+    let tx_size = bcs::serialized_size(tx_data).unwrap_or(usize::MAX);
+    check(
+      ProtocolLimit::MaxTxSizeBytes,
+      LimitScope::Transaction,
+      tx_size as u64,
+      self.max_tx_size_bytes,
+    )?;
+
+    let gas_payment_objects = tx_data.gas_data().objects.len() as u64;
+    check(
+      ProtocolLimit::MaxGasPaymentObjects,
+      LimitScope::Transaction,
+      gas_payment_objects,
+      self.max_gas_payment_objects,
+    )?;
+
+    #[allow(irrefutable_let_patterns)]
+    let TransactionKind::Programmable(pt) = tx_data.kind() else {
+      return Ok(());
+    };
+
+    check(
+      ProtocolLimit::MaxProgrammableTxCommands,
+      LimitScope::Transaction,
+      pt.commands.len() as u64,
+      self.max_programmable_tx_commands,
+    )?;
+
+    let input_objects = pt
+      .inputs
+      .iter()
+      .filter(|input| !matches!(input, CallArg::Pure(_)))
+      .count() as u64;
+    check(
+      ProtocolLimit::MaxInputObjects,
+      LimitScope::Transaction,
+      input_objects + gas_payment_objects,
+      self.max_input_objects,
+    )?;
+
+    for (input_idx, input) in pt.inputs.iter().enumerate() {
+      let CallArg::Pure(bytes) = input else {
+        continue;
+      };
+      // Report the first command using this input, as that's what users wrote.
+      let scope = pt
+        .commands
+        .iter()
+        .position(|command| uses_input(command, input_idx))
+        .map_or(LimitScope::Input(input_idx), LimitScope::Command);
+      check(
+        ProtocolLimit::MaxPureArgumentSize,
+        scope,
+        bytes.len() as u64,
+        self.max_pure_argument_size,
+      )?;
+    }
+
+    for (command_idx, command) in pt.commands.iter().enumerate() {
+      let type_args = match command {
+        Command::MoveCall(call) => call.type_arguments.iter().collect(),
+        Command::MakeMoveVec(Some(type_arg), _) => vec![type_arg],
+        _ => vec![],
+      };
+      for type_arg in type_args {
+        check(
+          ProtocolLimit::MaxTypeArgumentDepth,
+          LimitScope::Command(command_idx),
+          type_depth(type_arg),
+          self.max_type_argument_depth,
+        )?;
+      }
+    }
+
+    Ok(())
+  }
+}
+
+/// Returns whether `command` takes the input at index `input_idx` as argument.
+fn uses_input(command: &Command, input_idx: usize) -> bool {
+  let input = Argument::Input(input_idx as u16);
+  match command {
+    Command::MoveCall(call) => call.arguments.contains(&input),
+    Command::TransferObjects(objects, address) => objects.contains(&input) || *address == input,
+    Command::SplitCoins(coin, amounts) => *coin == input || amounts.contains(&input),
+    Command::MergeCoins(coin, coins) => *coin == input || coins.contains(&input),
+    Command::MakeMoveVec(_, elements) => elements.contains(&input),
+    Command::Upgrade(_, _, _, ticket) => *ticket == input,
+    Command::Publish(_, _) => false,
+  }
+}
+
+/// Returns the nesting depth of a Move type - e.g. `u8` has depth 1, while
+/// `vector<0x2::coin::Coin<0x2::iota::IOTA>>` has depth 3.
+fn type_depth(type_tag: &TypeTag) -> u64 {
+  match type_tag {
+    TypeTag::Vector(element) => 1 + type_depth(element),
+    TypeTag::Struct(struct_tag) => 1 + struct_tag.type_params().iter().map(type_depth).max().unwrap_or(0),
+    _ => 1,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::str::FromStr as _;
+
+  use iota_interaction::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+  use iota_interaction::types::transaction::ProgrammableTransaction;
+  use iota_sdk_types::Identifier;
+
+  use super::*;
+  use crate::transaction::testing::{address, object_id, object_ref, programmable_transaction_data};
+
+  const LIMITS: ProtocolLimits = ProtocolLimits {
+    max_programmable_tx_commands: 2,
+    max_pure_argument_size: 64,
+    max_input_objects: 3,
+    max_gas_payment_objects: 1,
+    max_tx_size_bytes: 4096,
+    max_type_argument_depth: 2,
+  };
+
+  fn type_tag(type_tag: &str) -> TypeTag {
+    TypeTag::from_str(type_tag).expect("valid type tag")
+  }
+
+  /// Adds a call to `0xaa..::module::function` to `ptb`, with the given type arguments and arguments.
+  fn move_call(ptb: &mut ProgrammableTransactionBuilder, type_args: Vec<TypeTag>, args: Vec<Argument>) {
+    let module = Identifier::new("module").expect("valid identifier");
+    let function = Identifier::new("function").expect("valid identifier");
+    ptb.programmable_move_call(object_id(0xaa), module, function, type_args, args);
+  }
+
+  fn validate(pt: ProgrammableTransaction) -> Result<(), ProtocolLimitViolation> {
+    LIMITS.validate(&programmable_transaction_data(pt, address(1), address(1)))
+  }
+
+  fn violation(limit: ProtocolLimit, scope: LimitScope, value: u64, max: u64) -> Result<(), ProtocolLimitViolation> {
+    Err(ProtocolLimitViolation {
+      limit,
+      scope,
+      value,
+      max,
+    })
+  }
+
+  #[test]
+  fn type_depth_counts_nested_type_params() {
+    assert_eq!(type_depth(&type_tag("u8")), 1);
+    assert_eq!(type_depth(&type_tag("vector<u8>")), 2);
+    assert_eq!(type_depth(&type_tag("0x2::coin::Coin<0x2::iota::IOTA>")), 2);
+    assert_eq!(
+      type_depth(&type_tag("0x2::vec_map::VecMap<vector<u8>, vector<vector<u64>>>")),
+      4
+    );
+  }
+
+  #[test]
+  fn transactions_within_limits_are_valid() {
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let amount = ptb.pure(42_u64).unwrap();
+    move_call(&mut ptb, vec![type_tag("vector<u8>")], vec![amount]);
+
+    assert_eq!(validate(ptb.finish()), Ok(()));
+  }
+
+  #[test]
+  fn too_many_commands_are_rejected() {
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    for _ in 0..3 {
+      move_call(&mut ptb, vec![], vec![]);
+    }
+
+    assert_eq!(
+      validate(ptb.finish()),
+      violation(ProtocolLimit::MaxProgrammableTxCommands, LimitScope::Transaction, 3, 2)
+    );
+  }
+
+  #[test]
+  fn oversized_pure_arguments_are_reported_where_used() {
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let small = ptb.pure(1_u8).unwrap();
+    let large = ptb.pure(vec![0_u8; 100]).unwrap();
+    move_call(&mut ptb, vec![], vec![small]);
+    move_call(&mut ptb, vec![], vec![small, large]);
+    // 100 bytes, prefixed by their length.
+    assert_eq!(
+      validate(ptb.finish()),
+      violation(ProtocolLimit::MaxPureArgumentSize, LimitScope::Command(1), 101, 64)
+    );
+
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    ptb.pure(vec![0_u8; 100]).unwrap();
+    assert_eq!(
+      validate(ptb.finish()),
+      violation(ProtocolLimit::MaxPureArgumentSize, LimitScope::Input(0), 101, 64)
+    );
+  }
+
+  #[test]
+  fn input_objects_include_the_gas_payment() {
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    let objects = (1..=3)
+      .map(|byte| ptb.obj(CallArg::ImmutableOrOwned(object_ref(byte, 1))).unwrap())
+      .collect();
+    move_call(&mut ptb, vec![], objects);
+
+    assert_eq!(
+      validate(ptb.finish()),
+      violation(ProtocolLimit::MaxInputObjects, LimitScope::Transaction, 4, 3)
+    );
+  }
+
+  #[test]
+  fn too_many_gas_coins_are_rejected() {
+    let mut tx_data =
+      programmable_transaction_data(ProgrammableTransactionBuilder::new().finish(), address(1), address(1));
+    tx_data.gas_data_mut().objects.push(object_ref(8, 1));
+
+    assert_eq!(
+      LIMITS.validate(&tx_data),
+      violation(ProtocolLimit::MaxGasPaymentObjects, LimitScope::Transaction, 2, 1)
+    );
+  }
+
+  #[test]
+  fn oversized_transactions_are_rejected() {
+    let tx_data = programmable_transaction_data(ProgrammableTransactionBuilder::new().finish(), address(1), address(1));
+    let tx_size = bcs::serialized_size(&tx_data).unwrap() as u64;
+    let limits = ProtocolLimits {
+      max_tx_size_bytes: tx_size - 1,
+      ..LIMITS
+    };
+
+    assert_eq!(
+      limits.validate(&tx_data),
+      violation(
+        ProtocolLimit::MaxTxSizeBytes,
+        LimitScope::Transaction,
+        tx_size,
+        tx_size - 1
+      )
+    );
+  }
+
+  #[test]
+  fn deeply_nested_type_arguments_are_rejected() {
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    move_call(&mut ptb, vec![], vec![]);
+    move_call(&mut ptb, vec![type_tag("u8"), type_tag("vector<vector<u8>>")], vec![]);
+
+    assert_eq!(
+      validate(ptb.finish()),
+      violation(ProtocolLimit::MaxTypeArgumentDepth, LimitScope::Command(1), 3, 2)
+    );
+  }
+
+  #[test]
+  fn violations_name_the_exceeded_limit() {
+    let violation = ProtocolLimitViolation {
+      limit: ProtocolLimit::MaxPureArgumentSize,
+      scope: LimitScope::Command(37),
+      value: 16_384,
+      max: 16_000,
+    };
+    assert_eq!(
+      violation.to_string(),
+      "command 37 exceeds max_pure_argument_size (16384 > 16000)"
+    );
+  }
+}
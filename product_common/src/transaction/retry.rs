@@ -0,0 +1,290 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resilient submission of signed transactions.
+//!
+//! The digest of a transaction is known before it is submitted, and submitting the very same signed transaction
+//! more than once never executes it more than once. This allows a transaction whose submission failed because of
+//! a transient issue - e.g. a timeout or a connection reset - to be safely resubmitted, after making sure that it
+//! didn't land in the meantime.
+//!
+//! When a transaction cannot be executed, the returned error tells apart transactions that were rejected by the
+//! network - [Error::TransactionRejected] - from transactions whose outcome is unknown -
+//! [Error::TransactionOutcomeUnknown] - which might still be executed later on.
+
+use std::time::Duration;
+
+use iota_interaction::error::Error as IotaRpcError;
+use iota_interaction::rpc_types::IotaTransactionBlockResponseOptions;
+use iota_interaction::types::digests::TransactionDigest;
+use iota_interaction::types::quorum_driver_types::ExecuteTransactionRequestType;
+use iota_interaction::types::signature::GenericSignature;
+use iota_interaction::types::transaction::TransactionData;
+use iota_interaction::IotaClientTrait;
+use jsonrpsee::core::ClientError;
+use serde::{Deserialize, Serialize};
+
+use crate::core_client::CoreClientReadOnly;
+use crate::iota_interaction_adapter::IotaTransactionBlockResponseAdaptedTraitObj;
+use crate::Error;
+
+/// Controls how many times, and how often, a signed transaction is submitted before giving up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+  max_attempts: u32,
+  initial_backoff: Duration,
+  max_backoff: Duration,
+  finality_poll_interval: Duration,
+  finality_timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+  /// Submits a transaction up to 5 times, waiting up to 10 seconds for it to be finalized after each failure.
+  fn default() -> Self {
+    Self {
+      max_attempts: 5,
+      initial_backoff: Duration::from_millis(500),
+      max_backoff: Duration::from_secs(8),
+      finality_poll_interval: Duration::from_secs(1),
+      finality_timeout: Duration::from_secs(10),
+    }
+  }
+}
+
+impl RetryPolicy {
+  /// Returns the default [RetryPolicy].
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns a [RetryPolicy] that submits transactions only once, without waiting for their finality
+  /// upon failures.
+  pub fn none() -> Self {
+    Self {
+      max_attempts: 1,
+      initial_backoff: Duration::ZERO,
+      max_backoff: Duration::ZERO,
+      finality_poll_interval: Duration::ZERO,
+      finality_timeout: Duration::ZERO,
+    }
+  }
+
+  /// Sets the maximum number of times a transaction is submitted.
+  /// ## Notes
+  /// A transaction is always submitted at least once.
+  pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+    self.max_attempts = max_attempts.max(1);
+    self
+  }
+
+  /// Sets the time to wait before the first resubmission. The wait time doubles after each
+  /// resubmission, up to `max_backoff`.
+  pub fn with_backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+    self.initial_backoff = initial_backoff;
+    self.max_backoff = max_backoff.max(initial_backoff);
+    self
+  }
+
+  /// Sets for how long, and how often, the network is polled for a transaction whose submission failed.
+  pub fn with_finality_polling(mut self, poll_interval: Duration, timeout: Duration) -> Self {
+    self.finality_poll_interval = poll_interval;
+    self.finality_timeout = timeout;
+    self
+  }
+
+  /// Returns the maximum number of times a transaction is submitted.
+  pub fn max_attempts(&self) -> u32 {
+    self.max_attempts
+  }
+
+  fn backoff(&self, attempt: u32) -> Duration {
+    let factor = 1u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+    self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+  }
+}
+
+/// Submits the given signed transaction according to `policy`, returning the response of its execution.
+/// ## Notes
+/// The returned response always comes from a finalized transaction; its effects, however, might be unsuccessful.
+pub(crate) async fn execute_with_retry<C>(
+  tx_data: TransactionData,
+  signatures: Vec<GenericSignature>,
+  policy: &RetryPolicy,
+  client: &C,
+) -> Result<IotaTransactionBlockResponseAdaptedTraitObj, Error>
+where
+  C: CoreClientReadOnly,
+{
+  let digest = tx_data.digest();
+  let mut last_error = None;
+
+  for attempt in 1..=policy.max_attempts {
+    if attempt > 1 {
+      sleep(policy.backoff(attempt - 1)).await;
+    }
+
+    let submission_error = match client
+      .client_adapter()
+      .quorum_driver_api()
+      .execute_transaction_block(
+        tx_data.clone(),
+        signatures.clone(),
+        Some(IotaTransactionBlockResponseOptions::full_content()),
+        Some(ExecuteTransactionRequestType::WaitForLocalExecution),
+      )
+      .await
+    {
+      Ok(response) if response.effects().is_some() => return Ok(response),
+      Ok(_) => "missing effects in response".to_owned(),
+      Err(e) if !is_transient(&e) => {
+        // A previous attempt might have landed - e.g. making this one fail because its objects have already
+        // been used - so give it as long to be finalized as after a transient failure before reporting the
+        // transaction as rejected.
+        if attempt > 1 {
+          if let Some(response) =
+            wait_for_finality(digest, policy.finality_poll_interval, policy.finality_timeout, client).await
+          {
+            return Ok(response);
+          }
+        }
+        return Err(Error::TransactionRejected {
+          digest,
+          reason: e.to_string(),
+        });
+      }
+      Err(e) => e.to_string(),
+    };
+
+    // The transaction might have been executed regardless of the error.
+    if let Some(response) =
+      wait_for_finality(digest, policy.finality_poll_interval, policy.finality_timeout, client).await
+    {
+      return Ok(response);
+    }
+    last_error = Some(submission_error);
+  }
+
+  Err(Error::TransactionOutcomeUnknown {
+    digest,
+    reason: last_error.unwrap_or_default(),
+  })
+}
+
+/// Polls the network for the finalized transaction with the given digest, until `timeout` elapses.
+async fn wait_for_finality<C>(
+  digest: TransactionDigest,
+  poll_interval: Duration,
+  timeout: Duration,
+  client: &C,
+) -> Option<IotaTransactionBlockResponseAdaptedTraitObj>
+where
+  C: CoreClientReadOnly,
+{
+  let polls = if poll_interval.is_zero() {
+    0
+  } else {
+    timeout.as_millis().div_ceil(poll_interval.as_millis())
+  };
+
+  for poll in 0..=polls {
+    if poll > 0 {
+      sleep(poll_interval).await;
+    }
+
+    let response = client
+      .client_adapter()
+      .read_api()
+      .get_transaction_with_options(digest, IotaTransactionBlockResponseOptions::full_content())
+      .await;
+    if let Ok(response) = response {
+      if response.effects().is_some() {
+        return Some(response);
+      }
+    }
+  }
+
+  None
+}
+
+/// The JSON-RPC error codes nodes return for requests that can be retried: `-32009` when a node is too busy to
+/// process requests, and `-32050` when it hits a transient issue.
+const TRANSIENT_ERROR_CODES: &[i32] = &[-32009, -32050];
+
+/// Returns whether `error` might be caused by a temporary issue, e.g. a network failure or a timeout,
+/// rather than by the network rejecting the transaction.
+fn is_transient(error: &IotaRpcError) -> bool {
+  match error {
+    IotaRpcError::FailToConfirmTransactionStatus(..) => true,
+    IotaRpcError::Rpc(ClientError::Transport(_) | ClientError::RestartNeeded(_) | ClientError::RequestTimeout) => true,
+    // Errors returned by a node that processed the request are final, unless the node explicitly asks for the
+    // request to be retried.
+    IotaRpcError::Rpc(ClientError::Call(error)) => TRANSIENT_ERROR_CODES.contains(&error.code()),
+    _ => false,
+  }
+}
+
+/// Pauses the current task for `duration`.
+pub(crate) async fn sleep(duration: Duration) {
+  if duration.is_zero() {
+    return;
+  }
+
+  cfg_if::cfg_if! {
+    if #[cfg(target_arch = "wasm32")] {
+      let duration_ms = i32::try_from(duration.as_millis()).unwrap_or(i32::MAX);
+      let _ = iota_interaction_ts::bindings::sleep(duration_ms).await;
+    } else {
+      tokio::time::sleep(duration).await;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn backoff_doubles_up_to_max() {
+    let policy = RetryPolicy::new().with_backoff(Duration::from_millis(100), Duration::from_millis(500));
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    assert_eq!(policy.backoff(4), Duration::from_millis(500));
+    assert_eq!(policy.backoff(40), Duration::from_millis(500));
+  }
+
+  fn call_error(code: i32) -> IotaRpcError {
+    let error_object = serde_json::from_value(serde_json::json!({ "code": code, "message": "error" })).unwrap();
+    IotaRpcError::Rpc(ClientError::Call(error_object))
+  }
+
+  #[test]
+  fn transport_failures_are_transient() {
+    assert!(is_transient(&IotaRpcError::Rpc(ClientError::RequestTimeout)));
+    assert!(is_transient(&IotaRpcError::Rpc(ClientError::Transport(
+      anyhow::anyhow!("connection reset by peer").into()
+    ))));
+    assert!(is_transient(&IotaRpcError::FailToConfirmTransactionStatus(
+      TransactionDigest::new([0; 32]),
+      60
+    )));
+  }
+
+  #[test]
+  fn node_errors_are_transient_only_when_the_node_says_so() {
+    assert!(is_transient(&call_error(-32009)));
+    assert!(is_transient(&call_error(-32050)));
+    // Transaction execution errors, e.g. "Transaction validator signing failed".
+    assert!(!is_transient(&call_error(-32002)));
+    assert!(!is_transient(&call_error(-32602)));
+  }
+
+  #[test]
+  fn other_errors_are_not_transient() {
+    assert!(!is_transient(&IotaRpcError::Data("invalid response".to_owned())));
+    assert!(!is_transient(&IotaRpcError::Rpc(ClientError::Custom(
+      "request timed out".to_owned()
+    ))));
+  }
+}
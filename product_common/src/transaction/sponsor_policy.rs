@@ -0,0 +1,552 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Sponsor-side evaluation of the transactions to sponsor.
+//!
+//! A sponsor pays for the gas of transactions it didn't build. [SponsorPolicy] decides whether a transaction is
+//! worth sponsoring - according to the Move functions it calls, its gas budget, its sender and how much the sender
+//! already spent - and never lets a transaction spend the sponsor's own coins. It can be used by a sponsor service
+//! through [SponsorPolicy::approve], or directly as a
+//! [TransactionBuilder::with_sponsor](super::TransactionBuilder::with_sponsor) implementation:
+//! ```ignore
+//! let policy = SponsorPolicy::new()
+//!   .allow_package(package_id)
+//!   .with_max_gas_budget(50_000_000)
+//!   .with_sender_quota(SenderQuota::new().with_max_transactions(100));
+//!
+//! let tx = tx_builder
+//!   .with_sponsor(&client, policy.sponsor(&client, &sponsor_signer, sponsor_gas_coins))
+//!   .await?;
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use anyhow::anyhow;
+use iota_interaction::types::base_types::{EpochId, IotaAddress, ObjectRef};
+use iota_interaction::types::crypto::Signature;
+use iota_interaction::types::transaction::{
+  CallArg, Command, TransactionData, TransactionDataAPI as _, TransactionKind,
+};
+use iota_interaction::{IotaClientTrait, IotaKeySignature, OptionalSync};
+use iota_sdk_types::{Argument, ObjectId};
+use secret_storage::Signer;
+
+use super::transaction_builder::MutGasDataRef;
+use crate::core_client::CoreClientReadOnly;
+
+/// Limits on what a single sender can have sponsored within an epoch.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SenderQuota {
+  max_transactions: Option<u64>,
+  max_gas_budget: Option<u64>,
+}
+
+impl SenderQuota {
+  /// Returns a [SenderQuota] without any limit.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Sets the maximum number of transactions a sender can have sponsored within an epoch.
+  pub fn with_max_transactions(mut self, max_transactions: u64) -> Self {
+    self.max_transactions = Some(max_transactions);
+    self
+  }
+
+  /// Sets the maximum gas budget - summed over all its transactions - a sender can have sponsored within an epoch.
+  pub fn with_max_gas_budget(mut self, max_gas_budget: u64) -> Self {
+    self.max_gas_budget = Some(max_gas_budget);
+    self
+  }
+}
+
+/// Why a [SponsorPolicy] refused to sponsor a transaction.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum RejectionReason {
+  /// Only programmable transactions can be sponsored.
+  #[error("only programmable transactions can be sponsored")]
+  NotProgrammable,
+  /// The sender is explicitly denied.
+  #[error("sender {0} is denied")]
+  SenderDenied(IotaAddress),
+  /// The sender is not among the allowed ones.
+  #[error("sender {0} is not allowed")]
+  SenderNotAllowed(IotaAddress),
+  /// The gas budget of the transaction is too high.
+  #[error("gas budget {budget} exceeds the maximum of {max}")]
+  GasBudgetTooHigh {
+    /// The gas budget of the transaction.
+    budget: u64,
+    /// The maximum gas budget allowed.
+    max: u64,
+  },
+  /// The transaction calls a Move function that is not allowed.
+  #[error("calls to {package}::{module}::{function} are not allowed")]
+  MoveCallNotAllowed {
+    /// The package of the called function.
+    package: ObjectId,
+    /// The module of the called function.
+    module: String,
+    /// The called function.
+    function: String,
+  },
+  /// The transaction publishes or upgrades a package, while only some Move calls are allowed.
+  #[error("publishing or upgrading packages is not allowed")]
+  PackageManagementNotAllowed,
+  /// The transaction uses the sponsor's coins for something other than paying for gas.
+  #[error("the sponsor's coins can only be used to pay for gas")]
+  SponsorCoinUsed,
+  /// The sender already had as many transactions sponsored as allowed within the current epoch.
+  #[error("sender {sender} already had {max} transactions sponsored in the current epoch")]
+  TransactionQuotaExceeded {
+    /// The sender of the transaction.
+    sender: IotaAddress,
+    /// The maximum number of transactions per epoch.
+    max: u64,
+  },
+  /// Sponsoring the transaction would exceed the gas budget its sender is allowed within the current epoch.
+  #[error("sender {sender} would exceed its gas budget quota of {max} for the current epoch")]
+  BudgetQuotaExceeded {
+    /// The sender of the transaction.
+    sender: IotaAddress,
+    /// The maximum gas budget per epoch.
+    max: u64,
+  },
+}
+
+/// The outcome of evaluating a transaction against a [SponsorPolicy].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SponsorDecision {
+  /// The transaction can be sponsored.
+  Approved,
+  /// The transaction must not be sponsored, for all the given reasons.
+  Rejected(Vec<RejectionReason>),
+}
+
+impl SponsorDecision {
+  /// Returns whether the transaction can be sponsored.
+  pub fn is_approved(&self) -> bool {
+    matches!(self, Self::Approved)
+  }
+
+  /// Returns the reasons why the transaction was rejected, if any.
+  pub fn rejection_reasons(&self) -> &[RejectionReason] {
+    match self {
+      Self::Approved => &[],
+      Self::Rejected(reasons) => reasons,
+    }
+  }
+}
+
+/// How much a sender had sponsored within an epoch.
+#[derive(Debug, Default, Clone, Copy)]
+struct SenderUsage {
+  epoch: EpochId,
+  transactions: u64,
+  gas_budget: u64,
+}
+
+/// The rules a transaction must comply with to be sponsored.
+///
+/// A new [SponsorPolicy] sponsors any transaction that doesn't use the sponsor's coins; each rule restricts it
+/// further. Clones of a [SponsorPolicy] share the usage of each sender, which is tracked in memory.
+#[derive(Debug, Clone, Default)]
+pub struct SponsorPolicy {
+  allowed_packages: HashSet<ObjectId>,
+  allowed_modules: HashSet<(ObjectId, String)>,
+  allowed_functions: HashSet<(ObjectId, String, String)>,
+  max_gas_budget: Option<u64>,
+  allowed_senders: Option<HashSet<IotaAddress>>,
+  denied_senders: HashSet<IotaAddress>,
+  sender_quota: SenderQuota,
+  usage: Arc<Mutex<HashMap<IotaAddress, SenderUsage>>>,
+}
+
+impl SponsorPolicy {
+  /// Returns a [SponsorPolicy] that sponsors any transaction that doesn't use the sponsor's coins.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Allows calls to any function of `package`.
+  /// ## Notes
+  /// Once any package, module or function is allowed, all calls to other functions are rejected, as well as
+  /// package publishing and upgrades.
+  pub fn allow_package(mut self, package: ObjectId) -> Self {
+    self.allowed_packages.insert(package);
+    self
+  }
+
+  /// Allows calls to any function of `package::module`.
+  pub fn allow_module(mut self, package: ObjectId, module: impl Into<String>) -> Self {
+    self.allowed_modules.insert((package, module.into()));
+    self
+  }
+
+  /// Allows calls to `package::module::function`.
+  pub fn allow_function(mut self, package: ObjectId, module: impl Into<String>, function: impl Into<String>) -> Self {
+    self.allowed_functions.insert((package, module.into(), function.into()));
+    self
+  }
+
+  /// Sets the maximum gas budget of a sponsored transaction.
+  pub fn with_max_gas_budget(mut self, max_gas_budget: u64) -> Self {
+    self.max_gas_budget = Some(max_gas_budget);
+    self
+  }
+
+  /// Allows `sender`'s transactions to be sponsored.
+  /// ## Notes
+  /// Once any sender is allowed, transactions from all other senders are rejected.
+  pub fn allow_sender(mut self, sender: IotaAddress) -> Self {
+    self.allowed_senders.get_or_insert_with(HashSet::new).insert(sender);
+    self
+  }
+
+  /// Denies the sponsorship of `sender`'s transactions.
+  pub fn deny_sender(mut self, sender: IotaAddress) -> Self {
+    self.denied_senders.insert(sender);
+    self
+  }
+
+  /// Limits what each sender can have sponsored within an epoch.
+  pub fn with_sender_quota(mut self, quota: SenderQuota) -> Self {
+    self.sender_quota = quota;
+    self
+  }
+
+  /// Evaluates `tx_data` - whose gas data must already be set by the sponsor - against this policy, without
+  /// accounting for it in its sender's quota.
+  pub fn evaluate(&self, tx_data: &TransactionData, current_epoch: EpochId) -> SponsorDecision {
+    let usage = self.usage.lock().expect("not poisoned");
+    self.evaluate_with_usage(tx_data, current_epoch, &usage)
+  }
+
+  /// Evaluates `tx_data` against this policy and, if approved, accounts for it in its sender's quota.
+  /// ## Notes
+  /// Approved transactions that end up not being sponsored - e.g. because signing them failed - must be
+  /// [released](Self::release) not to count towards their sender's quota.
+  pub fn approve(&self, tx_data: &TransactionData, current_epoch: EpochId) -> SponsorDecision {
+    let mut usage = self.usage.lock().expect("not poisoned");
+    // Usage of past epochs is never looked at again.
+    usage.retain(|_, sender_usage| sender_usage.epoch >= current_epoch);
+    let decision = self.evaluate_with_usage(tx_data, current_epoch, &usage);
+    if decision.is_approved() {
+      let sender_usage = usage.entry(tx_data.sender()).or_default();
+      if sender_usage.epoch != current_epoch {
+        *sender_usage = SenderUsage {
+          epoch: current_epoch,
+          ..Default::default()
+        };
+      }
+      sender_usage.transactions += 1;
+      sender_usage.gas_budget = sender_usage.gas_budget.saturating_add(tx_data.gas_budget());
+    }
+
+    decision
+  }
+
+  /// Removes `tx_data` - approved within `epoch` - from its sender's quota.
+  pub fn release(&self, tx_data: &TransactionData, epoch: EpochId) {
+    let mut usage = self.usage.lock().expect("not poisoned");
+    if let Some(sender_usage) = usage
+      .get_mut(&tx_data.sender())
+      .filter(|sender_usage| sender_usage.epoch == epoch)
+    {
+      sender_usage.transactions = sender_usage.transactions.saturating_sub(1);
+      sender_usage.gas_budget = sender_usage.gas_budget.saturating_sub(tx_data.gas_budget());
+    }
+  }
+
+  /// Returns a [TransactionBuilder::with_sponsor](super::TransactionBuilder::with_sponsor) implementation that pays
+  /// for the transaction's gas with `gas_payment` - coins owned by `signer` - and signs it, if this policy approves
+  /// of it.
+  pub fn sponsor<'a, C, S>(
+    &'a self,
+    client: &'a C,
+    signer: &'a S,
+    gas_payment: Vec<ObjectRef>,
+  ) -> impl AsyncFnOnce(MutGasDataRef<'_>) -> anyhow::Result<Signature> + 'a
+  where
+    C: CoreClientReadOnly + OptionalSync,
+    S: Signer<IotaKeySignature> + OptionalSync,
+  {
+    async move |mut tx_data: MutGasDataRef<'_>| {
+      let sponsor_pk = signer
+        .public_key()
+        .await
+        .map_err(|e| anyhow!("failed to get the sponsor's public key: {e}"))?;
+      let sponsor_address = IotaAddress::from(&sponsor_pk);
+      let gas_data = tx_data.gas_data_mut();
+      gas_data.owner = sponsor_address;
+      gas_data.objects = gas_payment;
+
+      let current_epoch = client
+        .client_adapter()
+        .read_api()
+        .get_current_epoch()
+        .await
+        .map_err(|e| anyhow!("failed to fetch current epoch: {e}"))?;
+      if let SponsorDecision::Rejected(reasons) = self.approve(&tx_data, current_epoch) {
+        let reasons = reasons.iter().map(ToString::to_string).collect::<Vec<_>>();
+        return Err(anyhow!(
+          "transaction rejected by sponsor policy: {}",
+          reasons.join("; ")
+        ));
+      }
+
+      signer.sign(&tx_data).await.map_err(|e| {
+        self.release(&tx_data, current_epoch);
+        anyhow!("failed to sign as sponsor: {e}")
+      })
+    }
+  }
+
+  fn evaluate_with_usage(
+    &self,
+    tx_data: &TransactionData,
+    current_epoch: EpochId,
+    usage: &HashMap<IotaAddress, SenderUsage>,
+  ) -> SponsorDecision {
+    let mut reasons = vec![];
+    let sender = tx_data.sender();
+    let budget = tx_data.gas_budget();
+
+    if self.denied_senders.contains(&sender) {
+      reasons.push(RejectionReason::SenderDenied(sender));
+    } else if self
+      .allowed_senders
+      .as_ref()
+      .is_some_and(|allowed| !allowed.contains(&sender))
+    {
+      reasons.push(RejectionReason::SenderNotAllowed(sender));
+    }
+
+    if let Some(max) = self.max_gas_budget.filter(|max| budget > *max) {
+      reasons.push(RejectionReason::GasBudgetTooHigh { budget, max });
+    }
+
+    let sender_usage = usage
+      .get(&sender)
+      .filter(|sender_usage| sender_usage.epoch == current_epoch)
+      .copied()
+      .unwrap_or_default();
+    if let Some(max) = self
+      .sender_quota
+      .max_transactions
+      .filter(|max| sender_usage.transactions >= *max)
+    {
+      reasons.push(RejectionReason::TransactionQuotaExceeded { sender, max });
+    }
+    if let Some(max) = self
+      .sender_quota
+      .max_gas_budget
+      .filter(|max| sender_usage.gas_budget.saturating_add(budget) > *max)
+    {
+      reasons.push(RejectionReason::BudgetQuotaExceeded { sender, max });
+    }
+
+    #[allow(irrefutable_let_patterns)]
+    let TransactionKind::Programmable(pt) = tx_data.kind() else {
+      reasons.push(RejectionReason::NotProgrammable);
+      return SponsorDecision::Rejected(reasons);
+    };
+
+    let gas_payment = tx_data
+      .gas_data()
+      .objects
+      .iter()
+      .map(|object_ref| object_ref.object_id)
+      .collect::<HashSet<_>>();
+    let uses_gas_payment_input = pt.inputs.iter().any(|input| match input {
+      CallArg::ImmutableOrOwned(object_ref) | CallArg::Receiving(object_ref) => {
+        gas_payment.contains(&object_ref.object_id)
+      }
+      _ => false,
+    });
+    let uses_gas_coin = pt
+      .commands
+      .iter()
+      .any(|command| command_arguments(command).contains(&&Argument::GasCoin));
+    if uses_gas_payment_input || uses_gas_coin {
+      reasons.push(RejectionReason::SponsorCoinUsed);
+    }
+
+    if self.restricts_calls() {
+      for command in &pt.commands {
+        match command {
+          Command::MoveCall(call) => {
+            let (module, function) = (call.module.to_string(), call.function.to_string());
+            if !self.is_call_allowed(call.package, &module, &function) {
+              reasons.push(RejectionReason::MoveCallNotAllowed {
+                package: call.package,
+                module,
+                function,
+              });
+            }
+          }
+          Command::Publish(..) | Command::Upgrade(..) => reasons.push(RejectionReason::PackageManagementNotAllowed),
+          _ => (),
+        }
+      }
+    }
+
+    if reasons.is_empty() {
+      SponsorDecision::Approved
+    } else {
+      reasons.dedup();
+      SponsorDecision::Rejected(reasons)
+    }
+  }
+
+  fn restricts_calls(&self) -> bool {
+    !(self.allowed_packages.is_empty() && self.allowed_modules.is_empty() && self.allowed_functions.is_empty())
+  }
+
+  fn is_call_allowed(&self, package: ObjectId, module: &str, function: &str) -> bool {
+    self.allowed_packages.contains(&package)
+      || self.allowed_modules.contains(&(package, module.to_owned()))
+      || self
+        .allowed_functions
+        .contains(&(package, module.to_owned(), function.to_owned()))
+  }
+}
+
+/// Returns the arguments used by `command`.
+pub(crate) fn command_arguments(command: &Command) -> Vec<&Argument> {
+  match command {
+    Command::MoveCall(call) => call.arguments.iter().collect(),
+    Command::TransferObjects(objects, address) => objects.iter().chain(std::iter::once(address)).collect(),
+    Command::SplitCoins(coin, amounts) => std::iter::once(coin).chain(amounts).collect(),
+    Command::MergeCoins(coin, coins) => std::iter::once(coin).chain(coins).collect(),
+    Command::MakeMoveVec(_, elements) => elements.iter().collect(),
+    Command::Upgrade(_, _, _, ticket) => vec![ticket],
+    Command::Publish(_, _) => vec![],
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use iota_interaction::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+
+  use super::*;
+  use crate::transaction::testing::{address, object_ref, programmable_transaction_data, transaction_data};
+
+  /// Returns a transaction from `sender` - sponsored by [address]`(7)` - that transfers one of the sender's objects.
+  fn transfer_tx(sender: IotaAddress) -> TransactionData {
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    ptb.transfer_object(address(3), object_ref(4, 1)).unwrap();
+    programmable_transaction_data(ptb.finish(), sender, address(7))
+  }
+
+  #[test]
+  fn sponsor_coins_can_only_pay_for_gas() {
+    let policy = SponsorPolicy::new();
+    assert!(policy.evaluate(&transfer_tx(address(1)), 0).is_approved());
+
+    // Splits some IOTA out of the gas coin.
+    let gas_coin_tx = transaction_data(address(1), address(7));
+    assert_eq!(
+      policy.evaluate(&gas_coin_tx, 0).rejection_reasons(),
+      [RejectionReason::SponsorCoinUsed]
+    );
+
+    // Transfers the coin paying for gas.
+    let mut ptb = ProgrammableTransactionBuilder::new();
+    ptb
+      .transfer_object(address(3), gas_coin_tx.gas_data().objects[0].clone())
+      .unwrap();
+    let gas_payment_tx = programmable_transaction_data(ptb.finish(), address(1), address(7));
+    assert_eq!(
+      policy.evaluate(&gas_payment_tx, 0).rejection_reasons(),
+      [RejectionReason::SponsorCoinUsed]
+    );
+  }
+
+  #[test]
+  fn denied_senders_take_precedence_over_allowed_ones() {
+    let policy = SponsorPolicy::new()
+      .allow_sender(address(1))
+      .allow_sender(address(2))
+      .deny_sender(address(2));
+
+    assert!(policy.evaluate(&transfer_tx(address(1)), 0).is_approved());
+    assert_eq!(
+      policy.evaluate(&transfer_tx(address(2)), 0).rejection_reasons(),
+      [RejectionReason::SenderDenied(address(2))]
+    );
+    assert_eq!(
+      policy.evaluate(&transfer_tx(address(3)), 0).rejection_reasons(),
+      [RejectionReason::SenderNotAllowed(address(3))]
+    );
+  }
+
+  #[test]
+  fn quotas_are_per_sender_and_epoch() {
+    let policy = SponsorPolicy::new().with_sender_quota(SenderQuota::new().with_max_transactions(1));
+    let tx = transfer_tx(address(1));
+
+    assert!(policy.approve(&tx, 1).is_approved());
+    assert_eq!(
+      policy.approve(&tx, 1).rejection_reasons(),
+      [RejectionReason::TransactionQuotaExceeded {
+        sender: address(1),
+        max: 1
+      }]
+    );
+    assert!(policy.evaluate(&transfer_tx(address(2)), 1).is_approved());
+    // The quota rolls over with the epoch.
+    assert!(policy.approve(&tx, 2).is_approved());
+  }
+
+  #[test]
+  fn budget_quotas_sum_the_budgets_of_all_transactions() {
+    let tx = transfer_tx(address(1));
+    let max = tx.gas_budget() * 3 / 2;
+    let policy = SponsorPolicy::new().with_sender_quota(SenderQuota::new().with_max_gas_budget(max));
+
+    assert!(policy.approve(&tx, 1).is_approved());
+    assert_eq!(
+      policy.evaluate(&tx, 1).rejection_reasons(),
+      [RejectionReason::BudgetQuotaExceeded {
+        sender: address(1),
+        max
+      }]
+    );
+  }
+
+  #[test]
+  fn released_transactions_do_not_count_towards_quotas() {
+    let policy = SponsorPolicy::new().with_sender_quota(SenderQuota::new().with_max_transactions(1));
+    let tx = transfer_tx(address(1));
+
+    assert!(policy.approve(&tx, 1).is_approved());
+    policy.release(&tx, 1);
+    assert!(policy.approve(&tx, 1).is_approved());
+  }
+
+  #[test]
+  fn usage_of_past_epochs_is_pruned() {
+    let policy = SponsorPolicy::new();
+    assert!(policy.approve(&transfer_tx(address(1)), 1).is_approved());
+    assert!(policy.approve(&transfer_tx(address(2)), 2).is_approved());
+
+    let usage = policy.usage.lock().unwrap();
+    assert_eq!(usage.keys().collect::<Vec<_>>(), [&address(2)]);
+  }
+
+  #[test]
+  fn calls_are_unrestricted_until_something_is_allowed() {
+    let package = ObjectId::new([1; 32]);
+    let policy = SponsorPolicy::new();
+    assert!(!policy.restricts_calls());
+
+    let policy = policy.allow_module(package, "token");
+    assert!(policy.restricts_calls());
+    assert!(policy.is_call_allowed(package, "token", "mint"));
+    assert!(!policy.is_call_allowed(package, "vault", "withdraw"));
+    assert!(!policy.is_call_allowed(ObjectId::new([2; 32]), "token", "mint"));
+  }
+}
@@ -0,0 +1,183 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test doubles shared by the unit tests of this module.
+
+use async_trait::async_trait;
+use fastcrypto::ed25519::Ed25519KeyPair;
+use fastcrypto::traits::ToFromBytes as _;
+use iota_interaction::rpc_types::{
+  IotaExecutionStatus, IotaTransactionBlockEffects, IotaTransactionBlockEffectsV1, OwnedObjectRef,
+};
+use iota_interaction::types::base_types::{IotaAddress, ObjectRef, SequenceNumber};
+use iota_interaction::types::crypto::{IotaKeyPair, PublicKey};
+use iota_interaction::types::digests::{ObjectDigest, TransactionDigest};
+use iota_interaction::types::gas::GasCostSummary;
+use iota_interaction::types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+use iota_interaction::types::transaction::{
+  GasData, ProgrammableTransaction, TransactionData, TransactionDataV1, TransactionExpiration, TransactionKind,
+};
+use iota_interaction::{KeyPairSigner, OptionalSync};
+use iota_sdk_types::{ObjectId, Owner};
+use serde::{Deserialize, Serialize};
+
+use crate::core_client::{CoreClient, CoreClientReadOnly};
+use crate::iota_interaction_adapter::IotaClientAdapter;
+use crate::network_name::NetworkName;
+use crate::transaction::Transaction;
+use crate::Error;
+
+/// A [CoreClient] for logic that doesn't reach the network.
+/// ## Panics
+/// [OfflineClient::client_adapter] panics, as there is no node to talk to.
+pub(crate) struct OfflineClient {
+  network_name: NetworkName,
+  signer: KeyPairSigner,
+  public_key: PublicKey,
+}
+
+impl OfflineClient {
+  pub(crate) fn new() -> Self {
+    let keypair = Ed25519KeyPair::from_bytes(&[1; 32]).expect("valid private key");
+    let signer = KeyPairSigner::new(IotaKeyPair::Ed25519(keypair));
+    let public_key = signer.public_key();
+
+    Self {
+      network_name: NetworkName::try_from("iota").expect("valid network name"),
+      signer,
+      public_key,
+    }
+  }
+}
+
+impl CoreClientReadOnly for OfflineClient {
+  fn package_id(&self) -> ObjectId {
+    ObjectId::new([0xaa; 32])
+  }
+
+  fn network_name(&self) -> &NetworkName {
+    &self.network_name
+  }
+
+  fn client_adapter(&self) -> &IotaClientAdapter {
+    unreachable!("unit tests have no node to talk to")
+  }
+}
+
+impl CoreClient<KeyPairSigner> for OfflineClient {
+  fn signer(&self) -> &KeyPairSigner {
+    &self.signer
+  }
+
+  fn sender_address(&self) -> IotaAddress {
+    IotaAddress::from(&self.public_key)
+  }
+
+  fn sender_public_key(&self) -> &PublicKey {
+    &self.public_key
+  }
+}
+
+/// A transaction that does nothing, neither on-chain nor off-chain.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct Noop;
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl Transaction for Noop {
+  type Error = Error;
+  type Output = ();
+
+  async fn build_programmable_transaction<C>(&self, _client: &C) -> Result<ProgrammableTransaction, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    Ok(ProgrammableTransactionBuilder::new().finish())
+  }
+
+  async fn apply<C>(self, _effects: &mut IotaTransactionBlockEffects, _client: &C) -> Result<Self::Output, Self::Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    Ok(())
+  }
+}
+
+/// Returns the ID whose bytes are all `byte`.
+pub(crate) fn object_id(byte: u8) -> ObjectId {
+  ObjectId::new([byte; 32])
+}
+
+/// Returns the address whose bytes are all `byte`.
+pub(crate) fn address(byte: u8) -> IotaAddress {
+  IotaAddress::new([byte; 32])
+}
+
+/// Returns a reference to the object with ID [object_id]`(byte)` at `version`.
+pub(crate) fn object_ref(byte: u8, version: u64) -> ObjectRef {
+  ObjectRef::new(
+    object_id(byte),
+    SequenceNumber::from_u64(version),
+    ObjectDigest::new([version as u8; 32]),
+  )
+}
+
+/// Returns `object_ref` as owned by `owner`.
+pub(crate) fn owned_by(owner: IotaAddress, object_ref: ObjectRef) -> OwnedObjectRef {
+  OwnedObjectRef {
+    owner: Owner::Address(owner),
+    reference: object_ref,
+  }
+}
+
+/// Returns the effects of a successful transaction that created and mutated the given objects.
+pub(crate) fn effects(
+  created: Vec<OwnedObjectRef>,
+  mutated: Vec<OwnedObjectRef>,
+  gas_object: OwnedObjectRef,
+) -> IotaTransactionBlockEffects {
+  IotaTransactionBlockEffects::V1(IotaTransactionBlockEffectsV1 {
+    status: IotaExecutionStatus::Success,
+    executed_epoch: 0,
+    gas_used: GasCostSummary::default(),
+    modified_at_versions: vec![],
+    shared_objects: vec![],
+    transaction_digest: TransactionDigest::new([0; 32]),
+    created,
+    mutated,
+    unwrapped: vec![],
+    deleted: vec![],
+    unwrapped_then_deleted: vec![],
+    wrapped: vec![],
+    gas_object,
+    events_digest: None,
+    dependencies: vec![],
+  })
+}
+
+/// Returns a transaction from `sender`, paid by `gas_owner`, transferring some IOTA to [address]`(3)`.
+pub(crate) fn transaction_data(sender: IotaAddress, gas_owner: IotaAddress) -> TransactionData {
+  let mut ptb = ProgrammableTransactionBuilder::new();
+  ptb.transfer_iota(address(3), Some(42));
+
+  programmable_transaction_data(ptb.finish(), sender, gas_owner)
+}
+
+/// Returns a transaction executing `pt`, from `sender`, paid by `gas_owner`.
+pub(crate) fn programmable_transaction_data(
+  pt: ProgrammableTransaction,
+  sender: IotaAddress,
+  gas_owner: IotaAddress,
+) -> TransactionData {
+  TransactionData::V1(TransactionDataV1 {
+    kind: TransactionKind::Programmable(pt),
+    sender,
+    gas_payment: GasData {
+      objects: vec![object_ref(9, 1)],
+      owner: gas_owner,
+      price: 1000,
+      budget: 5_000_000,
+    },
+    expiration: TransactionExpiration::Epoch(7),
+  })
+}
@@ -1,18 +1,20 @@
 // Copyright 2020-2025 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+use std::any::TypeId;
 use std::ops::Deref;
+use std::sync::Arc;
 
 use anyhow::Context as _;
 use async_trait::async_trait;
 use cfg_if::cfg_if;
 use iota_interaction::rpc_types::{
-  IotaTransactionBlockEffects, IotaTransactionBlockEffectsAPI as _, IotaTransactionBlockEvents,
-  IotaTransactionBlockResponseOptions,
+  DryRunTransactionBlockResponse, IotaTransactionBlockEffects, IotaTransactionBlockEffectsAPI as _,
+  IotaTransactionBlockEvents,
 };
-use iota_interaction::types::base_types::{IotaAddress, ObjectRef};
+use iota_interaction::types::base_types::{EpochId, IotaAddress, ObjectRef};
 use iota_interaction::types::crypto::{IotaSignature as _, PublicKey, Signature};
-use iota_interaction::types::quorum_driver_types::ExecuteTransactionRequestType;
+use iota_interaction::types::signature::GenericSignature;
 use iota_interaction::types::transaction::{
   GasData, ProgrammableTransaction, TransactionData, TransactionDataAPI as _, TransactionDataV1, TransactionExpiration,
   TransactionKind,
@@ -21,11 +23,24 @@ use iota_interaction::{IotaClientTrait, IotaKeySignature, OptionalSend, Optional
 use iota_sdk_types::crypto::{Intent, IntentMessage};
 use itertools::Itertools;
 use secret_storage::Signer;
-
+use serde::{Deserialize, Serialize};
+
+use super::builder_state::TransactionBuilderState;
+use super::envelope::TransactionEnvelope;
+use super::gas_budget::{DryRunEstimator, GasBudgetEstimator, GasEstimationRequest, DRY_RUN_GAS_BUDGET};
+use super::hooks::{HookChain, TransactionHook};
+use super::multisig::{verify_multisig, MultiSigPublicKey, MultisigSignatures};
+use super::object_lock::{
+  owned_input_objects, uses_gas_coin, ObjectLockGuard, ObjectLockManager, DEFAULT_LOCK_TIMEOUT,
+};
+use super::protocol_limits::ProtocolLimits;
+use super::retry::{execute_with_retry, RetryPolicy};
+use super::DryRunOutput;
 #[cfg(not(target_arch = "wasm32"))]
 use super::TransactionOutput;
 #[cfg(target_arch = "wasm32")]
 use super::TransactionOutputInternal as TransactionOutput;
+use crate::coin_selection::{CoinSelector, LargestFirst, IOTA_COIN_TYPE, MAX_GAS_PAYMENT_OBJECTS};
 use crate::core_client::{CoreClient, CoreClientReadOnly};
 use crate::Error;
 
@@ -49,7 +64,7 @@ pub trait Transaction: Sized {
   /// the parts of `effects` that are needed for the transaction - e.g., removing
   /// the ID of the object the transaction created from the `effects`'s list of
   /// created objects.
-  /// This is particularly important to enable the batching of transactions.
+  /// This is particularly important to enable the batching of transactions - see [batch](super::batch).
   async fn apply<C>(self, effects: &mut IotaTransactionBlockEffects, client: &C) -> Result<Self::Output, Self::Error>
   where
     C: CoreClientReadOnly + OptionalSync;
@@ -63,7 +78,7 @@ pub trait Transaction: Sized {
   /// ## Handling Events
   ///
   /// If you need to handle events in your transaction logic, override this
-  /// method and process the effects and events in this function. Also make
+  /// method and process the effects and events in this function - see [TypedEvents](super::TypedEvents). Also make
   /// sure to return an appropriate error in your [`apply`] function implementation, since users  could still call
   /// `apply` directly in their own code.
   ///
@@ -90,8 +105,8 @@ pub trait Transaction: Sized {
   }
 }
 
-#[derive(Debug, Default, Clone)]
-struct PartialGasData {
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct PartialGasData {
   objects: Vec<ObjectRef>,
   owner: Option<IotaAddress>,
   price: Option<u64>,
@@ -159,12 +174,76 @@ impl MutGasDataRef<'_> {
   }
 }
 
-fn new_with_gas_data(sender: IotaAddress, gas_data: GasData, pt: ProgrammableTransaction) -> TransactionData {
+/// When a transaction being built should expire.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum PartialExpiration {
+  /// The transaction never expires.
+  #[default]
+  Never,
+  /// The transaction expires at the end of the given epoch.
+  Epoch(EpochId),
+  /// The transaction expires at the end of the epoch that comes the given amount of epochs after the current one.
+  InEpochs(u64),
+}
+
+impl From<TransactionExpiration> for PartialExpiration {
+  fn from(value: TransactionExpiration) -> Self {
+    match value {
+      TransactionExpiration::Epoch(epoch) => Self::Epoch(epoch),
+      _ => Self::Never,
+    }
+  }
+}
+
+impl PartialExpiration {
+  /// Turns a relative expiration into an absolute one, by querying the current epoch from `client`.
+  async fn resolve<C>(&mut self, client: &C) -> Result<TransactionExpiration, Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    if let Self::InEpochs(_) = self {
+      let current_epoch = client
+        .client_adapter()
+        .read_api()
+        .get_current_epoch()
+        .await
+        .map_err(|e| Error::TransactionBuildingFailed(format!("failed to fetch current epoch: {e}")))?;
+      self.resolve_in(current_epoch)?;
+    }
+
+    Ok(match *self {
+      Self::Epoch(epoch) => TransactionExpiration::Epoch(epoch),
+      _ => TransactionExpiration::None,
+    })
+  }
+
+  /// Turns a relative expiration into the absolute one it amounts to during `current_epoch`.
+  fn resolve_in(&mut self, current_epoch: EpochId) -> Result<(), Error> {
+    if let Self::InEpochs(epochs) = *self {
+      let epoch = current_epoch.checked_add(epochs).ok_or_else(|| {
+        Error::TransactionBuildingFailed(format!(
+          "expiring {epochs} epochs after epoch {current_epoch} is out of range"
+        ))
+      })?;
+      // Resolve it once, so that all signatures are produced over the same expiration.
+      *self = Self::Epoch(epoch);
+    }
+
+    Ok(())
+  }
+}
+
+fn new_with_gas_data(
+  sender: IotaAddress,
+  gas_data: GasData,
+  pt: ProgrammableTransaction,
+  expiration: TransactionExpiration,
+) -> TransactionData {
   TransactionData::V1(TransactionDataV1 {
     sender,
     gas_payment: gas_data,
     kind: TransactionKind::Programmable(pt),
-    expiration: TransactionExpiration::default(),
+    expiration,
   })
 }
 
@@ -174,7 +253,17 @@ pub struct TransactionBuilder<Tx> {
   programmable_tx: Option<ProgrammableTransaction>,
   sender: Option<IotaAddress>,
   gas: PartialGasData,
-  signatures: Vec<Signature>,
+  expiration: PartialExpiration,
+  coin_selector: Option<Box<dyn CoinSelector>>,
+  gas_budget_estimator: Option<Box<dyn GasBudgetEstimator>>,
+  retry_policy: RetryPolicy,
+  hooks: Vec<Arc<dyn TransactionHook>>,
+  // Whether the programmable transaction passed the `before_build` hooks.
+  build_hooks_ran: bool,
+  object_lock_manager: Option<ObjectLockManager>,
+  protocol_limits: Option<ProtocolLimits>,
+  validate_protocol_limits: bool,
+  signatures: Vec<GenericSignature>,
   tx: Tx,
 }
 
@@ -186,7 +275,7 @@ impl<Tx> AsRef<Tx> for TransactionBuilder<Tx> {
 
 impl<Tx> TransactionBuilder<Tx>
 where
-  Tx: Transaction + OptionalSend,
+  Tx: Transaction + OptionalSend + 'static,
 {
   async fn transaction_data<C>(&mut self, client: &C) -> anyhow::Result<TransactionData>
   where
@@ -209,8 +298,9 @@ where
     let sender = self.sender.context("missing sender")?;
     let gas_data = self.gas.clone().into_gas_data_with_defaults();
     let pt = self.get_or_init_programmable_tx(client).await?.clone();
+    let expiration = self.expiration.resolve(client).await?;
 
-    Ok(new_with_gas_data(sender, gas_data, pt))
+    Ok(new_with_gas_data(sender, gas_data, pt, expiration))
   }
 
   /// Adds `signer`'s signature to this transaction's signatures' list.
@@ -242,13 +332,56 @@ where
       .transaction_data(client)
       .await
       .map_err(|e| Error::TransactionBuildingFailed(e.to_string()))?;
+    self.check_protocol_limits(&tx_data, client).await?;
+    HookChain::new(client, &self.hooks).before_sign(&tx_data).await?;
 
     let sig = client
       .signer()
       .sign(&tx_data)
       .await
       .map_err(|e| Error::TransactionSigningFailed(e.to_string()))?;
-    self.signatures.push(sig);
+    self.signatures.push(sig.into());
+
+    Ok(self)
+  }
+
+  /// Adds a multisig signature, combining the signatures of `signers` - members of the multisig address derived from
+  /// `multisig_pk`.
+  /// # Notes
+  /// This method asserts that the multisig address matches the address of either this transaction's sender or the
+  /// gas owner, and that the combined weight of `signers` meets the multisig threshold - failing otherwise.
+  pub async fn with_multisig_signature<C, S>(
+    mut self,
+    client: &C,
+    multisig_pk: MultiSigPublicKey,
+    signers: &[S],
+  ) -> Result<Self, Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+    S: Signer<IotaKeySignature> + OptionalSync,
+  {
+    let multisig_address = IotaAddress::from(&multisig_pk);
+    let matches_sender = self.sender.map_or(true, |sender| sender == multisig_address);
+    let matches_gas_owner = self.gas.owner.map_or(true, |owner| owner == multisig_address);
+
+    if !(matches_sender || matches_gas_owner) {
+      return Err(Error::TransactionBuildingFailed(format!(
+        "multisig address {multisig_address} doesn't match the address of either the transaction sender or the gas owner"
+      )));
+    }
+
+    let tx_data = self
+      .transaction_data(client)
+      .await
+      .map_err(|e| Error::TransactionBuildingFailed(e.to_string()))?;
+    self.check_protocol_limits(&tx_data, client).await?;
+    HookChain::new(client, &self.hooks).before_sign(&tx_data).await?;
+
+    let mut multisig_signatures = MultisigSignatures::new(multisig_pk, tx_data);
+    for signer in signers {
+      multisig_signatures.sign(signer).await?;
+    }
+    self.signatures.push(multisig_signatures.combine()?);
 
     Ok(self)
   }
@@ -286,12 +419,16 @@ where
       },
     );
 
-    self.signatures.push(signature);
+    self.signatures.push(signature.into());
     self.gas = gas_data.into();
 
     Ok(self)
   }
 
+  /// Returns this transaction's programmable transaction, building it if needed.
+  /// ## Notes
+  /// The programmable transaction is checked by the [TransactionHook::before_build] hooks before it is first returned,
+  /// failing if any of them vetoes it.
   async fn get_or_init_programmable_tx<C>(&mut self, client: &C) -> Result<&ProgrammableTransaction, Error>
   where
     C: CoreClientReadOnly + OptionalSync,
@@ -305,12 +442,142 @@ where
           .map_err(|e| Error::Transaction(Box::new(e)))?,
       );
     }
+    let programmable_tx = self.programmable_tx.as_ref().unwrap();
+    if !self.build_hooks_ran {
+      HookChain::new(client, &self.hooks)
+        .before_build(std::any::type_name::<Tx>(), programmable_tx)
+        .await?;
+      self.build_hooks_ran = true;
+    }
+
+    Ok(programmable_tx)
+  }
+
+  /// Builds this transaction's programmable transaction and locks the owned objects it uses as inputs through
+  /// `lock_manager`.
+  /// When some of those objects are in use by other transactions, the programmable transaction is built anew once
+  /// they are unlocked, as their versions have likely changed - unless this transaction has already been signed.
+  async fn lock_owned_inputs<C>(
+    &mut self,
+    lock_manager: &ObjectLockManager,
+    client: &C,
+  ) -> Result<ObjectLockGuard, Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let mut locks = lock_manager.try_lock(std::iter::empty()).expect("nothing to lock");
+    loop {
+      let unlocked_inputs = owned_input_objects(self.get_or_init_programmable_tx(client).await?)
+        .into_iter()
+        .filter(|object| !locks.objects().contains(object))
+        .collect::<Vec<_>>();
+      if let Some(input_locks) = lock_manager.try_lock(unlocked_inputs.iter().copied()) {
+        locks.merge(input_locks);
+        return Ok(locks);
+      }
 
-    Ok(self.programmable_tx.as_ref().unwrap())
+      locks.merge(lock_manager.lock(unlocked_inputs, DEFAULT_LOCK_TIMEOUT).await?);
+      if !self.signatures.is_empty() {
+        return Ok(locks);
+      }
+      self.programmable_tx = None;
+      self.build_hooks_ran = false;
+    }
+  }
+
+  /// Makes sure `tx_data` doesn't exceed the limits of the protocol the network runs - fetched once - unless
+  /// validation was disabled through [Self::without_protocol_limits_validation].
+  /// ## Notes
+  /// Transactions are not validated against protocol versions this library doesn't know of.
+  async fn check_protocol_limits<C>(&mut self, tx_data: &TransactionData, client: &C) -> Result<(), Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    match self.protocol_limits(client).await? {
+      Some(limits) => limits.validate(tx_data).map_err(Error::ProtocolLimitExceeded),
+      None => Ok(()),
+    }
+  }
+
+  /// Returns the limits of the protocol the network runs - fetched once - if they are known to this library.
+  /// When validation was disabled through [Self::without_protocol_limits_validation], limits are never fetched and
+  /// only those supplied through [Self::with_protocol_limits] are returned.
+  async fn protocol_limits<C>(&mut self, client: &C) -> Result<Option<ProtocolLimits>, Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    if !self.validate_protocol_limits {
+      return Ok(self.protocol_limits);
+    }
+    if self.protocol_limits.is_none() {
+      let protocol_version = client
+        .client_adapter()
+        .read_api()
+        .get_protocol_version()
+        .await
+        .map_err(|e| Error::Network("failed to fetch the protocol version".to_owned(), e))?;
+      self.protocol_limits = ProtocolLimits::for_version(protocol_version);
+    }
+
+    Ok(self.protocol_limits)
+  }
+
+  /// Estimates this transaction's gas budget through its [GasBudgetEstimator], as if `sender` was also paying
+  /// for gas, without making use of any of `sender`'s coins.
+  async fn estimate_gas_budget_with_mock_gas<C>(&mut self, sender: IotaAddress, client: &C) -> Result<u64, Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    self.get_or_init_programmable_tx(client).await?;
+    let gas_price = self.gas_price(client).await?;
+    let request = GasEstimationRequest {
+      sender,
+      gas_owner: sender,
+      gas_price,
+      gas_payment: &[],
+      programmable_tx: self.programmable_tx.as_ref().expect("just computed it"),
+      tx_type: std::any::type_name::<Tx>(),
+      tx_type_id: TypeId::of::<Tx>(),
+    };
+
+    let default_estimator = DryRunEstimator::default();
+    let estimator = self.gas_budget_estimator.as_deref().unwrap_or(&default_estimator);
+    estimator.estimate_gas_budget(&request, client.client_adapter()).await
+  }
+
+  /// Returns the gas price set for this transaction, or the network's reference gas price.
+  async fn gas_price<C>(&self, client: &C) -> Result<u64, Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    match self.gas.price {
+      Some(price) => Ok(price),
+      None => client
+        .client_adapter()
+        .read_api()
+        .get_reference_gas_price()
+        .await
+        .map_err(|e| Error::GasIssue(e.to_string())),
+    }
+  }
+
+  /// Builds this transaction's programmable transaction and resolves its expiration, if relative, so that they
+  /// are part of this builder's [state](Self::state).
+  pub async fn prepare<C>(mut self, client: &C) -> Result<Self, Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    self.get_or_init_programmable_tx(client).await?;
+    self.expiration.resolve(client).await?;
+
+    Ok(self)
   }
 
   /// Similar to [Self::build] but missing values are replaced by defaults.
-  pub async fn build_with_defaults<C>(mut self, client: &C) -> Result<(TransactionData, Vec<Signature>, Tx), Error>
+  pub async fn build_with_defaults<C>(
+    mut self,
+    client: &C,
+  ) -> Result<(TransactionData, Vec<GenericSignature>, Tx), Error>
   where
     C: CoreClientReadOnly + OptionalSync,
   {
@@ -334,35 +601,175 @@ where
   /// ## Notes
   /// This method *DOES NOT* remove nor checks for invalid signatures.
   /// Transaction with invalid signatures will fail after attempting to execute them.
-  pub async fn build<C, S>(mut self, client: &C) -> Result<(TransactionData, Vec<Signature>, Tx), Error>
+  pub async fn build<C, S>(self, client: &C) -> Result<(TransactionData, Vec<GenericSignature>, Tx), Error>
   where
     C: CoreClient<S> + OptionalSync,
     S: Signer<IotaKeySignature> + OptionalSync,
   {
-    self.get_or_init_programmable_tx(client).await?;
-    let programmable_tx = self.programmable_tx.expect("just computed it");
-    let client_address = client.sender_address();
-    let sender = self.sender.unwrap_or(client_address);
-    let gas_data = complete_gas_data_for_tx(&programmable_tx, self.gas, client)
-      .await
-      .map_err(|e| Error::GasIssue(e.to_string()))?;
-
-    let tx_data = new_with_gas_data(sender, gas_data, programmable_tx);
+    let (tx_data, signatures, tx, _) = self.build_locking(client).await?;
+    Ok((tx_data, signatures, tx))
+  }
 
-    let mut signatures = self.signatures;
-    let needs_client_signature = client_address == sender
+  /// Same as [Self::build] but the owned objects used by the transaction - if any [ObjectLockManager] is in use -
+  /// are kept locked until the returned [ObjectLockGuard] is dropped.
+  async fn build_locking<C, S>(
+    self,
+    client: &C,
+  ) -> Result<(TransactionData, Vec<GenericSignature>, Tx, Option<ObjectLockGuard>), Error>
+  where
+    C: CoreClient<S> + OptionalSync,
+    S: Signer<IotaKeySignature> + OptionalSync,
+  {
+    let client_address = client.sender_address();
+    let hooks = HookChain::new(client, &self.hooks);
+    let (tx_data, mut signatures, tx, locks) = self.build_unsigned(client, client_address).await?;
+    let needs_client_signature = client_address == tx_data.sender()
       || client_address == tx_data.gas_data().owner
-        && !signatures.iter().map(address_from_signature).contains(&client_address);
+        && !signatures
+          .iter()
+          .filter_map(address_from_generic_signature)
+          .contains(&client_address);
     if needs_client_signature {
+      hooks.before_sign(&tx_data).await?;
       let signature = client
         .signer()
         .sign(&tx_data)
         .await
         .map_err(|e| Error::TransactionSigningFailed(e.to_string()))?;
-      signatures.push(signature);
+      signatures.push(signature.into());
+    }
+
+    Ok((tx_data, signatures, tx, locks))
+  }
+
+  /// Same as [Self::build_locking] but no signature is ever added.
+  /// `client_address` is used as sender and gas owner when those haven't been supplied.
+  async fn build_unsigned<C>(
+    mut self,
+    client: &C,
+    client_address: IotaAddress,
+  ) -> Result<(TransactionData, Vec<GenericSignature>, Tx, Option<ObjectLockGuard>), Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let sender = self.sender.unwrap_or(client_address);
+    let lock_manager = self
+      .object_lock_manager
+      .take()
+      .or_else(|| client.object_lock_manager().cloned());
+    let mut locks = match &lock_manager {
+      Some(manager) => Some(self.lock_owned_inputs(manager, client).await?),
+      None => {
+        self.get_or_init_programmable_tx(client).await?;
+        None
+      }
+    };
+    let programmable_tx = self.programmable_tx.take().expect("just computed it");
+    let max_gas_coins = self
+      .protocol_limits(client)
+      .await?
+      .map_or(MAX_GAS_PAYMENT_OBJECTS, |limits| {
+        limits.max_gas_payment_objects as usize
+      });
+    let coin_selector = self.coin_selector.as_deref().unwrap_or(&LargestFirst);
+    let default_estimator = DryRunEstimator::default();
+    let gas_budget_estimator = self.gas_budget_estimator.as_deref().unwrap_or(&default_estimator);
+    let (gas_data, gas_locks) = complete_gas_data_for_tx(
+      &programmable_tx,
+      sender,
+      client_address,
+      std::any::type_name::<Tx>(),
+      TypeId::of::<Tx>(),
+      std::mem::take(&mut self.gas),
+      coin_selector,
+      max_gas_coins,
+      gas_budget_estimator,
+      lock_manager.as_ref(),
+      client,
+    )
+    .await
+    .map_err(|e| Error::GasIssue(e.to_string()))?;
+    if let (Some(locks), Some(gas_locks)) = (locks.as_mut(), gas_locks) {
+      locks.merge(gas_locks);
     }
+    let expiration = self.expiration.resolve(client).await?;
+
+    let tx_data = new_with_gas_data(sender, gas_data, programmable_tx, expiration);
+    self.check_protocol_limits(&tx_data, client).await?;
 
-    Ok((tx_data, signatures, self.tx))
+    Ok((tx_data, self.signatures, self.tx, locks))
+  }
+
+  /// Simulates the execution of this transaction, without signing nor submitting it.
+  ///
+  /// As no client's address is available, this transaction's sender must have been set through [Self::with_sender].
+  /// Missing gas information is not filled with actual coins: unless a gas payment was supplied, the node pays for
+  /// the dry run with a mock gas coin of the gas owner - the sender, when no gas owner had been supplied - with a
+  /// budget of [DRY_RUN_GAS_BUDGET], unless one was set. No object is locked through an [ObjectLockManager].
+  /// The returned [DryRunOutput] contains the predicted effects, events, and gas costs, alongside the result of
+  /// applying this transaction's off-chain logic to the simulated effects.
+  pub async fn dry_run<C>(mut self, client: &C) -> Result<DryRunOutput<Tx::Output>, Error>
+  where
+    C: CoreClientReadOnly + OptionalSync,
+  {
+    let sender = self
+      .sender
+      .ok_or_else(|| Error::TransactionBuildingFailed("missing sender".to_owned()))?;
+    let programmable_tx = self.get_or_init_programmable_tx(client).await?.clone();
+    let gas_data = GasData {
+      objects: self.gas.objects.clone(),
+      owner: self.gas.owner.unwrap_or(sender),
+      price: self.gas_price(client).await?,
+      budget: self.gas.budget.unwrap_or(DRY_RUN_GAS_BUDGET),
+    };
+    let expiration = self.expiration.resolve(client).await?;
+    let tx_data = new_with_gas_data(sender, gas_data, programmable_tx, expiration);
+    self.check_protocol_limits(&tx_data, client).await?;
+    let tx = self.tx;
+
+    let DryRunTransactionBlockResponse { effects, events, .. } = client
+      .client_adapter()
+      .read_api()
+      .dry_run_transaction_block(tx_data)
+      .await
+      .map_err(|e| Error::TransactionBuildingFailed(format!("failed to dry run transaction: {e}")))?;
+
+    let gas_cost_summary = effects.gas_cost_summary().clone();
+    let tx_status = effects.status();
+    let output = if tx_status.is_ok() {
+      let mut effects = effects.clone();
+      let mut events = events.clone();
+      tx.apply_with_events(&mut effects, &mut events, client)
+        .await
+        .map_err(|e| Error::Transaction(Box::new(e)))
+    } else {
+      Err(Error::TransactionUnexpectedResponse(format!(
+        "errors in transaction's effects: {tx_status}"
+      )))
+    };
+
+    Ok(DryRunOutput {
+      effects,
+      events,
+      gas_cost_summary,
+      output,
+    })
+  }
+
+  /// Builds this transaction without signing it, wrapping it in a [TransactionEnvelope] that can be handed
+  /// over to other parties for signing.
+  ///
+  /// Missing information is filled in the same best effort manner as [Self::build] does, except for
+  /// the client's signature, which is never added. Signatures that were already supplied are kept.
+  /// Once all the required signatures have been collected, the envelope can be executed through
+  /// [TransactionEnvelope::execute].
+  pub async fn into_envelope<C, S>(self, client: &C) -> Result<(TransactionEnvelope, Tx), Error>
+  where
+    C: CoreClient<S> + OptionalSync,
+    S: Signer<IotaKeySignature> + OptionalSync,
+  {
+    let (tx_data, signatures, tx, _) = self.build_unsigned(client, client.sender_address()).await?;
+    Ok((TransactionEnvelope::new(tx_data, signatures), tx))
   }
 
   /// Attempts to build and execute this transaction using `client` in a best effort manner:
@@ -371,6 +778,7 @@ where
   ///   has, if possible;
   /// - when signatures are missing, the client will provide its own if possible;
   ///
+  /// The transaction is submitted according to this builder's [RetryPolicy], see [Self::with_retry_policy].
   /// After the transaction has been successfully executed, the transaction's effect will be computed.
   /// ## Notes
   /// This method *DOES NOT* remove nor checks for invalid signatures.
@@ -380,68 +788,12 @@ where
     C: CoreClient<S> + OptionalSync,
     S: Signer<IotaKeySignature> + OptionalSync,
   {
+    let retry_policy = self.retry_policy;
+    let hooks = HookChain::new(client, &self.hooks);
     // Build the transaction into its parts.
-    let (tx_data, signatures, tx) = self.build(client).await?;
-
-    // Execute and wait for the transaction to be confirmed.
-    let dyn_tx_block = client
-      .client_adapter()
-      .quorum_driver_api()
-      .execute_transaction_block(
-        tx_data,
-        signatures,
-        Some(IotaTransactionBlockResponseOptions::full_content()),
-        Some(ExecuteTransactionRequestType::WaitForLocalExecution),
-      )
-      .await
-      .map_err(|e| Error::TransactionBuildingFailed(e.to_string()))?;
-
-    // Get the transaction's effects, making sure they are successful.
-    let mut tx_effects = dyn_tx_block
-      .effects()
-      .ok_or_else(|| Error::TransactionUnexpectedResponse("missing effects in response".to_owned()))?
-      .clone();
-    let tx_status = tx_effects.status();
-    if tx_status.is_err() {
-      return Err(Error::TransactionUnexpectedResponse(format!(
-        "errors in transaction's effects: {tx_status}"
-      )));
-    }
-
-    let application_result = tx
-      .apply_with_events(
-        &mut tx_effects,
-        &mut dyn_tx_block.events().cloned().unwrap_or_default(),
-        client,
-      )
-      .await;
-    let response = {
-      cfg_if! {
-        if #[cfg(target_arch = "wasm32")] {
-          dyn_tx_block
-        } else {
-          dyn_tx_block.clone_native_response()
-        }
-      }
-    };
-    // Apply the off-chain logic of the transaction by parsing the transaction's effects.
-    // If the application goes awry, salvage the response by returning it alongside the error.
-    let output = match application_result {
-      Ok(output) => output,
-      Err(e) => {
-        #[cfg(not(target_arch = "wasm32"))]
-        let response = Box::new(response);
-        #[cfg(target_arch = "wasm32")]
-        // For WASM the response is passed in the error as its JSON-encoded string representation.
-        let response = response.as_native_response().to_string();
-        return Err(Error::TransactionOffChainApplicationFailure {
-          source: Box::new(Error::Transaction(Box::new(e))),
-          response,
-        });
-      }
-    };
+    let (tx_data, signatures, tx, locks) = self.build_locking(client).await?;
 
-    Ok(TransactionOutput { output, response })
+    execute_and_apply(tx_data, signatures, tx, &retry_policy, &hooks, locks, client).await
   }
 }
 
@@ -451,12 +803,56 @@ impl<Tx> TransactionBuilder<Tx> {
     Self {
       tx,
       gas: PartialGasData::default(),
+      expiration: PartialExpiration::default(),
+      coin_selector: None,
+      gas_budget_estimator: None,
+      retry_policy: RetryPolicy::none(),
+      hooks: vec![],
+      build_hooks_ran: false,
+      object_lock_manager: None,
+      protocol_limits: None,
+      validate_protocol_limits: true,
       signatures: vec![],
       sender: None,
       programmable_tx: None,
     }
   }
 
+  /// Returns a [TransactionBuilder] that resumes from the given `state`, wrapping `tx`.
+  /// ## Notes
+  /// `tx` is expected to be the transaction `state` was taken from; it's only used to apply the transaction's
+  /// effects, unless `state` doesn't contain the programmable transaction yet.
+  pub fn from_state(tx: Tx, state: TransactionBuilderState) -> Self {
+    Self {
+      programmable_tx: state.programmable_tx,
+      sender: state.sender,
+      gas: state.gas,
+      expiration: state.expiration,
+      retry_policy: state.retry_policy,
+      protocol_limits: state.protocol_limits,
+      validate_protocol_limits: state.validate_protocol_limits,
+      signatures: state.signatures,
+      ..Self::new(tx)
+    }
+  }
+
+  /// Returns a snapshot of this builder's progress, which can be persisted and resumed through
+  /// [Self::from_state].
+  /// ## Notes
+  /// The programmable transaction is only part of the state once built, see [Self::prepare].
+  pub fn state(&self) -> TransactionBuilderState {
+    TransactionBuilderState::new(
+      self.programmable_tx.clone(),
+      self.sender,
+      self.gas.clone(),
+      self.expiration,
+      self.retry_policy,
+      self.protocol_limits,
+      self.validate_protocol_limits,
+      self.signatures.clone(),
+    )
+  }
+
   /// Returns the partial [Transaction] wrapped by this builder, consuming it.
   pub fn into_inner(self) -> Tx {
     self.tx
@@ -480,6 +876,85 @@ impl<Tx> TransactionBuilder<Tx> {
     self
   }
 
+  /// Sets the epoch at the end of which this transaction expires, i.e. can no longer be executed.
+  pub fn with_expiration(mut self, epoch: EpochId) -> Self {
+    self.expiration = PartialExpiration::Epoch(epoch);
+    self
+  }
+
+  /// Makes this transaction expire `epochs` epochs after the current one.
+  /// ## Notes
+  /// The current epoch is fetched from the network when this transaction's data is first built.
+  /// `with_expiration_in_epochs(0)` makes this transaction expire at the end of the current epoch.
+  pub fn with_expiration_in_epochs(mut self, epochs: u64) -> Self {
+    self.expiration = PartialExpiration::InEpochs(epochs);
+    self
+  }
+
+  /// Sets the [CoinSelector] used to pick the gas coins, when no gas payment is explicitly set.
+  /// Defaults to [LargestFirst].
+  pub fn with_coin_selector(mut self, coin_selector: impl CoinSelector + 'static) -> Self {
+    self.coin_selector = Some(Box::new(coin_selector));
+    self
+  }
+
+  /// Sets the [GasBudgetEstimator] used to compute the gas budget, when no gas budget is explicitly set.
+  /// Defaults to [DryRunEstimator].
+  pub fn with_gas_budget_estimator(mut self, estimator: impl GasBudgetEstimator + 'static) -> Self {
+    self.gas_budget_estimator = Some(Box::new(estimator));
+    self
+  }
+
+  /// Sets the [RetryPolicy] used to submit this transaction when executing it through [Self::build_and_execute].
+  /// Defaults to [RetryPolicy::none], i.e. the transaction is submitted only once.
+  pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+    self.retry_policy = retry_policy;
+    self
+  }
+
+  /// Registers `hook` to be run on this transaction, after the hooks registered on the client.
+  pub fn with_hook(mut self, hook: impl TransactionHook + 'static) -> Self {
+    self.hooks.push(Arc::new(hook));
+    // Make sure the new hook gets to check the programmable transaction, even if it has already been built.
+    self.build_hooks_ran = false;
+    self
+  }
+
+  /// Sets the [ObjectLockManager] used to lock the owned objects - gas coins included - used by this transaction,
+  /// until it has been executed through [Self::build_and_execute].
+  /// Defaults to the client's one, see [CoreClientReadOnly::object_lock_manager].
+  pub fn with_object_lock_manager(mut self, manager: ObjectLockManager) -> Self {
+    self.object_lock_manager = Some(manager);
+    self
+  }
+
+  /// Sets the [ProtocolLimits] this transaction is validated against before being signed.
+  /// Defaults to the limits of the protocol version the network currently runs.
+  pub fn with_protocol_limits(mut self, limits: ProtocolLimits) -> Self {
+    self.protocol_limits = Some(limits);
+    self.validate_protocol_limits = true;
+    self
+  }
+
+  /// Disables the validation of this transaction against [ProtocolLimits].
+  pub fn without_protocol_limits_validation(mut self) -> Self {
+    self.validate_protocol_limits = false;
+    self
+  }
+
+  /// Returns the [RetryPolicy] used to submit this transaction.
+  pub(crate) fn retry_policy(&self) -> RetryPolicy {
+    self.retry_policy
+  }
+
+  /// Returns the hooks to run on this transaction, including `client`'s ones.
+  pub(crate) fn hook_chain<C>(&self, client: &C) -> HookChain
+  where
+    C: CoreClientReadOnly + ?Sized,
+  {
+    HookChain::new(client, &self.hooks)
+  }
+
   /// Sets the gas owner.
   pub fn with_gas_owner(mut self, address: IotaAddress) -> Self {
     self.gas.owner = Some(address);
@@ -501,7 +976,7 @@ impl<Tx> TransactionBuilder<Tx> {
   /// Attempts to construct a [TransactionBuilder] from a whole transaction.
   pub fn try_from_signed_transaction(
     tx_data: TransactionData,
-    signatures: Vec<Signature>,
+    signatures: Vec<GenericSignature>,
     effect: Tx,
   ) -> Result<Self, Error> {
     #[allow(irrefutable_let_patterns)]
@@ -512,11 +987,21 @@ impl<Tx> TransactionBuilder<Tx> {
     };
     let sender = tx_data.sender();
     let gas = tx_data.gas_data().clone().into();
+    let expiration = (*tx_data.expiration()).into();
 
     Ok(Self {
       programmable_tx: Some(pt),
       sender: Some(sender),
       gas,
+      expiration,
+      coin_selector: None,
+      gas_budget_estimator: None,
+      retry_policy: RetryPolicy::none(),
+      hooks: vec![],
+      build_hooks_ran: false,
+      object_lock_manager: None,
+      protocol_limits: None,
+      validate_protocol_limits: true,
       signatures,
       tx: effect,
     })
@@ -526,20 +1011,32 @@ impl<Tx> TransactionBuilder<Tx> {
 /// Returns a best effort [GasData] for the given transaction, partial gas information, and client.
 /// ## Notes
 /// If a field is missing from gas data:
-/// - client's address is set as the gas owner;
+/// - `default_owner` is set as the gas owner;
 /// - current gas price is fetched from a node;
-/// - budget is calculated by dry running the transaction;
-/// - objects is set to whatever IOTA coins the gas owner has, that satisfy the tx's budget;
-async fn complete_gas_data_for_tx<C, S>(
+/// - budget is estimated by `gas_budget_estimator`;
+/// - objects is set to at most `max_gas_coins` IOTA coins of the gas owner, picked by `coin_selector` to cover the tx's
+///   budget;
+///
+/// When `lock_manager` is set, the gas objects are locked through it, and coins that are already locked are never
+/// selected.
+#[allow(clippy::too_many_arguments)]
+async fn complete_gas_data_for_tx<C>(
   pt: &ProgrammableTransaction,
+  sender: IotaAddress,
+  default_owner: IotaAddress,
+  tx_type: &str,
+  tx_type_id: TypeId,
   partial_gas_data: PartialGasData,
+  coin_selector: &dyn CoinSelector,
+  max_gas_coins: usize,
+  gas_budget_estimator: &dyn GasBudgetEstimator,
+  lock_manager: Option<&ObjectLockManager>,
   client: &C,
-) -> anyhow::Result<GasData>
+) -> anyhow::Result<(GasData, Option<ObjectLockGuard>)>
 where
-  C: CoreClient<S> + OptionalSync,
-  S: Signer<IotaKeySignature>,
+  C: CoreClientReadOnly + OptionalSync,
 {
-  let owner = partial_gas_data.owner.unwrap_or(client.sender_address());
+  let owner = partial_gas_data.owner.unwrap_or(default_owner);
   let price = if let Some(price) = partial_gas_data.price {
     price
   } else {
@@ -548,42 +1045,224 @@ where
   let budget = if let Some(budget) = partial_gas_data.budget {
     budget
   } else {
-    client.client_adapter().default_gas_budget(owner, pt).await?
+    let request = GasEstimationRequest {
+      sender,
+      gas_owner: owner,
+      gas_price: price,
+      gas_payment: &partial_gas_data.objects,
+      programmable_tx: pt,
+      tx_type,
+      tx_type_id,
+    };
+    gas_budget_estimator
+      .estimate_gas_budget(&request, client.client_adapter())
+      .await?
   };
-  let objects = if !partial_gas_data.objects.is_empty() {
-    partial_gas_data.objects
-  } else {
-    client.get_iota_coins_with_at_least_balance(owner, budget).await?
+  let (objects, locks) = match lock_manager {
+    Some(manager) if !partial_gas_data.objects.is_empty() => {
+      let object_ids = partial_gas_data.objects.iter().map(|object| object.object_id).collect();
+      let locks = manager.lock(object_ids, DEFAULT_LOCK_TIMEOUT).await?;
+      (partial_gas_data.objects, Some(locks))
+    }
+    Some(manager) => {
+      let (objects, locks) =
+        select_and_lock_gas_coins(owner, budget, coin_selector, max_gas_coins, manager, client).await?;
+      (objects, Some(locks))
+    }
+    None if !partial_gas_data.objects.is_empty() => (partial_gas_data.objects, None),
+    None => {
+      // Coins the transaction already uses - e.g. to pay someone - cannot pay for its gas too.
+      let input_objects = owned_input_objects(pt);
+      let coins = client
+        .get_all_coins(owner, IOTA_COIN_TYPE)
+        .await?
+        .into_iter()
+        .filter(|coin| !input_objects.contains(&coin.coin_object_id))
+        .collect::<Vec<_>>();
+      let balances = coins.iter().map(|coin| coin.balance).collect::<Vec<_>>();
+      let selected = coin_selector
+        .select(&balances, budget, max_gas_coins)
+        .with_context(|| format!("address {owner} does not have enough coins to form a balance of {budget}"))?;
+      let objects = selected.into_iter().map(|idx| coins[idx].object_ref()).collect();
+      (objects, None)
+    }
   };
 
-  Ok(GasData {
+  let gas_data = GasData {
     owner,
     objects,
     price,
     budget,
-  })
+  };
+  Ok((gas_data, locks))
+}
+
+/// Picks and locks at most `max_gas_coins` gas coins of `owner` to cover `budget`, preferring the coins of `owner`'s
+/// gas coin pool. Coins that are already locked are never picked.
+async fn select_and_lock_gas_coins<C>(
+  owner: IotaAddress,
+  budget: u64,
+  coin_selector: &dyn CoinSelector,
+  max_gas_coins: usize,
+  lock_manager: &ObjectLockManager,
+  client: &C,
+) -> anyhow::Result<(Vec<ObjectRef>, ObjectLockGuard)>
+where
+  C: CoreClientReadOnly + OptionalSync,
+{
+  const MAX_SELECTION_ATTEMPTS: usize = 3;
+
+  if let Some((coin, locks)) = lock_manager.lock_pooled_gas_coin(owner, budget) {
+    return Ok((vec![coin], locks));
+  }
+
+  for _ in 0..MAX_SELECTION_ATTEMPTS {
+    let coins = client
+      .get_all_coins(owner, IOTA_COIN_TYPE)
+      .await?
+      .into_iter()
+      .filter(|coin| !lock_manager.is_locked(coin.coin_object_id))
+      .collect::<Vec<_>>();
+    let balances = coins.iter().map(|coin| coin.balance).collect::<Vec<_>>();
+    let selected = coin_selector
+      .select(&balances, budget, max_gas_coins)
+      .with_context(|| format!("address {owner} does not have enough unlocked coins to form a balance of {budget}"))?;
+    let objects = selected
+      .into_iter()
+      .map(|idx| coins[idx].object_ref())
+      .collect::<Vec<_>>();
+
+    // Another transaction might have locked some of the selected coins in the meantime.
+    if let Some(locks) = lock_manager.try_lock(objects.iter().map(|object| object.object_id)) {
+      return Ok((objects, locks));
+    }
+  }
+
+  anyhow::bail!("failed to lock gas coins of address {owner}: too many concurrent transactions")
+}
+
+/// Executes an already built and signed transaction, computing its off-chain effects through `tx`.
+pub(crate) async fn execute_and_apply<C, Tx>(
+  tx_data: TransactionData,
+  signatures: Vec<GenericSignature>,
+  tx: Tx,
+  retry_policy: &RetryPolicy,
+  hooks: &HookChain,
+  locks: Option<ObjectLockGuard>,
+  client: &C,
+) -> Result<TransactionOutput<Tx::Output>, Error>
+where
+  C: CoreClientReadOnly + OptionalSync,
+  Tx: Transaction + OptionalSend,
+{
+  // Make sure multisig signatures are valid before submitting the transaction.
+  for signature in &signatures {
+    if let GenericSignature::MultiSig(multisig) = signature {
+      verify_multisig(multisig, &tx_data)?;
+    }
+  }
+
+  // Execute and wait for the transaction to be confirmed.
+  let gas_coin_used = uses_gas_coin(&tx_data);
+  let dyn_tx_block = match execute_with_retry(tx_data, signatures, retry_policy, client).await {
+    Ok(response) => response,
+    Err(e) => {
+      // The transaction might still be executed later on, using its objects.
+      if let (Error::TransactionOutcomeUnknown { .. }, Some(locks)) = (&e, locks) {
+        locks.keep_locked();
+      }
+      return Err(e);
+    }
+  };
+  hooks.after_execute(dyn_tx_block.as_ref()).await;
+
+  // Get the transaction's effects, making sure they are successful.
+  let mut tx_effects = dyn_tx_block
+    .effects()
+    .ok_or_else(|| Error::TransactionUnexpectedResponse("missing effects in response".to_owned()))?
+    .clone();
+  if let Some(locks) = locks {
+    locks.settle(&tx_effects, gas_coin_used, client).await;
+  }
+  let tx_status = tx_effects.status();
+  if tx_status.is_err() {
+    return Err(Error::TransactionUnexpectedResponse(format!(
+      "errors in transaction's effects: {tx_status}"
+    )));
+  }
+
+  let application_result = tx
+    .apply_with_events(
+      &mut tx_effects,
+      &mut dyn_tx_block.events().cloned().unwrap_or_default(),
+      client,
+    )
+    .await;
+  let response = {
+    cfg_if! {
+      if #[cfg(target_arch = "wasm32")] {
+        dyn_tx_block
+      } else {
+        dyn_tx_block.clone_native_response()
+      }
+    }
+  };
+  // Apply the off-chain logic of the transaction by parsing the transaction's effects.
+  // If the application goes awry, salvage the response by returning it alongside the error.
+  let output = match application_result {
+    Ok(output) => output,
+    Err(e) => {
+      let source = Error::Transaction(Box::new(e));
+      hooks.on_apply_error(&source).await;
+      #[cfg(not(target_arch = "wasm32"))]
+      let response = Box::new(response);
+      #[cfg(target_arch = "wasm32")]
+      // For WASM the response is passed in the error as its JSON-encoded string representation.
+      let response = response.as_native_response().to_string();
+      return Err(Error::TransactionOffChainApplicationFailure {
+        source: Box::new(source),
+        response,
+      });
+    }
+  };
+
+  Ok(TransactionOutput { output, response })
 }
 
 /// Extract the signer's address from an IOTA [Signature].
-fn address_from_signature(signature: &Signature) -> IotaAddress {
+/// ## Notes
+/// Signatures decoded from untrusted sources might carry an invalid public key, in which case this function fails.
+pub(crate) fn address_from_signature(signature: &Signature) -> Result<IotaAddress, Error> {
   let scheme = signature.scheme();
   let pk_bytes = signature.public_key_bytes();
-  let pk = PublicKey::try_from_bytes(scheme, pk_bytes).expect("valid signature hence valid key");
+  let pk = PublicKey::try_from_bytes(scheme, pk_bytes)
+    .map_err(|e| Error::TransactionSigningFailed(format!("signature carries an invalid public key: {e}")))?;
 
-  IotaAddress::from(&pk)
+  Ok(IotaAddress::from(&pk))
+}
+
+/// Extract the signer's address from a [GenericSignature], if its kind is supported and its public key is valid.
+pub(crate) fn address_from_generic_signature(signature: &GenericSignature) -> Option<IotaAddress> {
+  match signature {
+    GenericSignature::Signature(signature) => address_from_signature(signature).ok(),
+    GenericSignature::MultiSig(multisig) => Some(IotaAddress::from(multisig.get_pk())),
+    _ => None,
+  }
 }
 
 #[cfg(feature = "gas-station")]
 mod gas_station {
   use std::error;
 
+  use iota_interaction::rpc_types::IotaTransactionBlockResponseOptions;
+
   use super::*;
   use crate::gas_station::*;
   use crate::http_client::{HttpClient, Url};
 
   impl<Tx> TransactionBuilder<Tx>
   where
-    Tx: Transaction + OptionalSend,
+    Tx: Transaction + OptionalSend + 'static,
   {
     /// Execute this transaction using an IOTA Gas Station.
     #[cfg(any(not(feature = "default-http-client"), target_arch = "wasm32"))]
@@ -640,12 +1319,23 @@ mod gas_station {
   where
     S: Signer<IotaKeySignature> + OptionalSync,
     C: CoreClient<S> + OptionalSync,
-    Tx: Transaction + OptionalSend,
+    Tx: Transaction + OptionalSend + 'static,
     H: HttpClient<Error: Into<Box<dyn error::Error + Sync + Send>>>,
   {
     // Compute the arguments for gas reservation.
     let reserve_duration_secs = gas_station_options.gas_reservation_duration.as_secs();
-    let gas_budget = tx_builder.gas.budget.unwrap_or(DEFAULT_GAS_BUDGET_RESERVATION);
+    let gas_budget = if let Some(budget) = tx_builder.gas.budget {
+      budget
+    } else if tx_builder.gas_budget_estimator.is_some() {
+      // The sponsor is not known yet, estimate the budget through a mock gas coin.
+      let sender = tx_builder.sender.unwrap_or(client.sender_address());
+      tx_builder
+        .estimate_gas_budget_with_mock_gas(sender, client)
+        .await
+        .map_err(|e| GasStationError::new(ErrorKind::TxDataBuilding(Box::new(e))))?
+    } else {
+      DEFAULT_GAS_BUDGET_RESERVATION
+    };
 
     // Ensure content-type is set.
     let mut headers = gas_station_options.headers;
@@ -679,12 +1369,14 @@ mod gas_station {
     tx_builder.gas.budget = Some(gas_budget);
 
     // Consume the builder into its parts.
-    let (tx_data, mut sigs, tx) = tx_builder
-      .build(client)
+    let hooks = HookChain::new(client, &tx_builder.hooks);
+    let (tx_data, mut sigs, tx, locks) = tx_builder
+      .build_locking(client)
       .await
       .map_err(|e| GasStationError::new(ErrorKind::TxDataBuilding(Box::new(e))))?;
 
     // Let gas-station execute this transaction.
+    let gas_coin_used = uses_gas_coin(&tx_data);
     let mut effects = execute_sponsored_tx(
       gas_station_url,
       tx_data,
@@ -694,6 +1386,9 @@ mod gas_station {
       http_client,
     )
     .await?;
+    if let Some(locks) = locks {
+      locks.settle(&effects, gas_coin_used, client).await;
+    }
 
     // Fetch Tx response (we only have the effects..);
     let response = client
@@ -705,17 +1400,21 @@ mod gas_station {
       )
       .await
       .map_err(|e| GasStationError::new(ErrorKind::TxApplication(e.into())))?;
+    hooks.after_execute(response.as_ref()).await;
 
     // Apply tx's side-effects.
-    let output = tx
+    let application_result = tx
       .apply_with_events(
         &mut effects,
         &mut response.events().cloned().unwrap_or_default(),
         client,
       )
       .await
-      .map_err(|e| Error::Transaction(e.into()))
-      .map_err(|e| GasStationError::new(ErrorKind::TxApplication(Box::new(e))))?;
+      .map_err(|e| Error::Transaction(e.into()));
+    if let Err(e) = &application_result {
+      hooks.on_apply_error(e).await;
+    }
+    let output = application_result.map_err(|e| GasStationError::new(ErrorKind::TxApplication(Box::new(e))))?;
 
     let response = {
       cfg_if! {
@@ -730,3 +1429,156 @@ mod gas_station {
     Ok(TransactionOutput { output, response })
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::sync::atomic::{AtomicUsize, Ordering};
+
+  use futures::executor::block_on;
+
+  use super::*;
+  use crate::transaction::testing::{address, transaction_data, Noop, OfflineClient};
+
+  /// Counts the programmable transactions it checks, vetoing them if `veto` is set.
+  #[derive(Debug, Default)]
+  struct BuildCounter {
+    calls: Arc<AtomicUsize>,
+    veto: bool,
+  }
+
+  #[cfg_attr(feature = "send-sync", async_trait)]
+  #[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+  impl TransactionHook for BuildCounter {
+    async fn before_build(&self, _tx_type: &str, _programmable_tx: &ProgrammableTransaction) -> Result<(), Error> {
+      self.calls.fetch_add(1, Ordering::SeqCst);
+      if self.veto {
+        return Err(Error::TransactionVetoed("vetoed by test".to_owned()));
+      }
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn before_build_hooks_run_once_per_programmable_transaction() {
+    let client = OfflineClient::new();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let hook = BuildCounter {
+      calls: calls.clone(),
+      veto: false,
+    };
+
+    let builder = block_on(TransactionBuilder::new(Noop).with_hook(hook).prepare(&client)).unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    block_on(builder.build_with_defaults(&client)).unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+  }
+
+  #[test]
+  fn before_build_hooks_cannot_be_bypassed() {
+    let client = OfflineClient::new();
+    let veto = || BuildCounter {
+      veto: true,
+      ..Default::default()
+    };
+
+    let result = block_on(
+      TransactionBuilder::new(Noop)
+        .with_hook(veto())
+        .build_with_defaults(&client),
+    );
+    assert!(matches!(result, Err(Error::TransactionVetoed(_))));
+
+    let result = block_on(TransactionBuilder::new(Noop).with_hook(veto()).prepare(&client));
+    assert!(matches!(result, Err(Error::TransactionVetoed(_))));
+  }
+
+  #[test]
+  fn hooks_added_after_building_check_the_programmable_transaction() {
+    let client = OfflineClient::new();
+    let builder = block_on(TransactionBuilder::new(Noop).prepare(&client)).unwrap();
+    let veto = BuildCounter {
+      veto: true,
+      ..Default::default()
+    };
+
+    let result = block_on(builder.with_hook(veto).build_with_defaults(&client));
+    assert!(matches!(result, Err(Error::TransactionVetoed(_))));
+  }
+
+  #[test]
+  fn absolute_expirations_are_kept_as_is() {
+    let client = OfflineClient::new();
+    for (mut expiration, expected) in [
+      (PartialExpiration::Never, TransactionExpiration::None),
+      (PartialExpiration::Epoch(3), TransactionExpiration::Epoch(3)),
+    ] {
+      let initial = expiration;
+      assert_eq!(block_on(expiration.resolve(&client)).unwrap(), expected);
+      expiration.resolve_in(5).unwrap();
+      assert_eq!(expiration, initial);
+    }
+  }
+
+  #[test]
+  fn relative_expirations_are_resolved_once() {
+    let mut expiration = PartialExpiration::InEpochs(2);
+
+    expiration.resolve_in(5).unwrap();
+    assert_eq!(expiration, PartialExpiration::Epoch(7));
+
+    expiration.resolve_in(10).unwrap();
+    assert_eq!(expiration, PartialExpiration::Epoch(7));
+  }
+
+  #[test]
+  fn relative_expirations_out_of_range_are_rejected() {
+    let mut expiration = PartialExpiration::InEpochs(2);
+
+    let result = expiration.resolve_in(EpochId::MAX - 1);
+
+    assert!(matches!(result, Err(Error::TransactionBuildingFailed(_))));
+    assert_eq!(expiration, PartialExpiration::InEpochs(2));
+  }
+
+  #[test]
+  fn expirations_are_converted_from_transactions() {
+    assert_eq!(
+      PartialExpiration::from(TransactionExpiration::None),
+      PartialExpiration::Never
+    );
+    assert_eq!(
+      PartialExpiration::from(TransactionExpiration::Epoch(4)),
+      PartialExpiration::Epoch(4)
+    );
+  }
+
+  #[test]
+  fn expiration_is_part_of_the_built_transaction() {
+    let client = OfflineClient::new();
+
+    let (tx_data, _, _) = block_on(
+      TransactionBuilder::new(Noop)
+        .with_expiration(9)
+        .build_with_defaults(&client),
+    )
+    .unwrap();
+    assert_eq!(*tx_data.expiration(), TransactionExpiration::Epoch(9));
+
+    let (tx_data, _, _) = block_on(TransactionBuilder::new(Noop).build_with_defaults(&client)).unwrap();
+    assert_eq!(*tx_data.expiration(), TransactionExpiration::None);
+  }
+
+  #[test]
+  fn expiration_of_signed_transactions_is_kept() {
+    let client = OfflineClient::new();
+    let signed_tx_data = transaction_data(address(1), address(1));
+
+    let builder = TransactionBuilder::try_from_signed_transaction(signed_tx_data.clone(), vec![], Noop).unwrap();
+    assert_eq!(builder.expiration, PartialExpiration::Epoch(7));
+
+    let (tx_data, _, _) = block_on(builder.build_with_defaults(&client)).unwrap();
+    assert_eq!(*tx_data.expiration(), TransactionExpiration::Epoch(7));
+    assert_eq!(tx_data, signed_tx_data);
+  }
+}
@@ -0,0 +1,580 @@
+// Copyright 2020-2025 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! Durable workflows made of several dependent transactions.
+//!
+//! A [Workflow] executes a sequence of named steps - each one a transaction that might depend on the outputs of the
+//! previous ones - persisting its progress to a [WorkflowStore] after each step. Running the same code again with
+//! the same workflow ID resumes an interrupted workflow: completed steps are skipped and their persisted outputs are
+//! returned instead.
+//!
+//! Steps are built out of [TransactionBuilder]s, thus a [ProtoTransaction](super::ProtoTransaction) waiting for
+//! a previous step's output can be fed with it and used as a step, e.g. `|| proto_tx.with(previous_output)`.
+//!
+//! A step's signed transaction is persisted before being submitted. When a workflow is interrupted while a step is in
+//! flight, resuming it submits the very same signed transaction again, which never executes it twice.
+//!
+//! ## Example
+//! ```ignore
+//! let mut workflow = Workflow::start_or_resume("onboarding-alice", FileWorkflowStore::new("./workflows")?).await?;
+//!
+//! let account: Account = workflow.step("create", &client, || create_account(...)).await?;
+//! let configure = workflow.step("configure", &client, || configure_account(account.id, ...)).await;
+//! if let Err(e) = configure {
+//!   // Undo what has been done so far.
+//!   workflow.compensate("create", &client, || delete_account(account.id)).await?;
+//!   return Err(e);
+//! }
+//! workflow.step("share", &client, || share_account(account.id)).await?;
+//! workflow.finish().await?;
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use iota_interaction::types::digests::TransactionDigest;
+use iota_interaction::{IotaKeySignature, OptionalSend, OptionalSync};
+use secret_storage::Signer;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::envelope::TransactionEnvelope;
+use super::transaction_builder::{execute_and_apply, Transaction, TransactionBuilder};
+use crate::core_client::CoreClient;
+use crate::Error;
+
+/// The progress of a step, or of its compensation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum StepStatus {
+  /// The step's transaction has been signed, but it's unknown whether it has been executed.
+  Pending {
+    /// The signed transaction.
+    envelope: TransactionEnvelope,
+  },
+  /// The step's transaction has been executed and its output applied.
+  Completed {
+    /// The digest of the executed transaction.
+    digest: TransactionDigest,
+    /// The JSON encoded output of the executed transaction.
+    output: serde_json::Value,
+  },
+  /// The step's transaction has been executed, but its output couldn't be computed.
+  /// ## Notes
+  /// Such a step cannot be run again, as that would execute its transaction twice.
+  Failed {
+    /// The digest of the executed transaction.
+    digest: TransactionDigest,
+    /// Why the output of the transaction couldn't be computed.
+    error: String,
+  },
+}
+
+/// The persisted progress of a single step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StepRecord {
+  name: String,
+  status: StepStatus,
+  compensation: Option<StepStatus>,
+}
+
+impl StepRecord {
+  /// Returns the name of this step.
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// Returns the progress of this step.
+  pub fn status(&self) -> &StepStatus {
+    &self.status
+  }
+
+  /// Returns the progress of this step's compensation, if any.
+  pub fn compensation(&self) -> Option<&StepStatus> {
+    self.compensation.as_ref()
+  }
+}
+
+/// The persisted progress of a [Workflow].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowState {
+  steps: Vec<StepRecord>,
+  finished: bool,
+}
+
+impl WorkflowState {
+  /// Returns the steps that have been started so far, in execution order.
+  pub fn steps(&self) -> &[StepRecord] {
+    &self.steps
+  }
+
+  /// Returns whether the workflow has been marked as finished through [Workflow::finish].
+  pub fn is_finished(&self) -> bool {
+    self.finished
+  }
+}
+
+/// A storage for the progress of [Workflow]s.
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+pub trait WorkflowStore: OptionalSend + OptionalSync {
+  /// Loads the state of the workflow with the given ID, if any.
+  async fn load(&self, workflow_id: &str) -> Result<Option<WorkflowState>, Error>;
+
+  /// Persists the state of the workflow with the given ID, replacing the previous one.
+  async fn save(&self, workflow_id: &str, state: &WorkflowState) -> Result<(), Error>;
+}
+
+/// A [WorkflowStore] that keeps workflows' state in memory.
+/// ## Notes
+/// Progress stored this way doesn't survive the process; it's mostly useful for testing.
+#[derive(Debug, Default)]
+pub struct InMemoryWorkflowStore(Mutex<HashMap<String, WorkflowState>>);
+
+impl InMemoryWorkflowStore {
+  /// Returns a new, empty, [InMemoryWorkflowStore].
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+#[cfg_attr(feature = "send-sync", async_trait)]
+#[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+impl WorkflowStore for InMemoryWorkflowStore {
+  async fn load(&self, workflow_id: &str) -> Result<Option<WorkflowState>, Error> {
+    let workflows = self.0.lock().expect("not poisoned");
+    Ok(workflows.get(workflow_id).cloned())
+  }
+
+  async fn save(&self, workflow_id: &str, state: &WorkflowState) -> Result<(), Error> {
+    let mut workflows = self.0.lock().expect("not poisoned");
+    workflows.insert(workflow_id.to_owned(), state.clone());
+    Ok(())
+  }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use file_store::FileWorkflowStore;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod file_store {
+  use std::path::PathBuf;
+
+  use super::*;
+
+  /// A [WorkflowStore] that keeps each workflow's state in a JSON file named after its ID.
+  #[derive(Debug, Clone)]
+  pub struct FileWorkflowStore {
+    dir: PathBuf,
+  }
+
+  impl FileWorkflowStore {
+    /// Returns a new [FileWorkflowStore] that stores its files in `dir`, creating it if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> Result<Self, Error> {
+      let dir = dir.into();
+      std::fs::create_dir_all(&dir)
+        .map_err(|e| Error::Workflow(format!("failed to create directory {}: {e}", dir.display())))?;
+
+      Ok(Self { dir })
+    }
+
+    fn path(&self, workflow_id: &str) -> Result<PathBuf, Error> {
+      let is_valid_id = !workflow_id.is_empty()
+        && workflow_id
+          .chars()
+          .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !workflow_id.starts_with('.');
+      if !is_valid_id {
+        return Err(Error::Workflow(format!(
+          "invalid workflow ID \"{workflow_id}\"; only ASCII alphanumeric characters, '-', '_' and '.' are allowed"
+        )));
+      }
+
+      Ok(self.dir.join(format!("{workflow_id}.json")))
+    }
+  }
+
+  #[cfg_attr(feature = "send-sync", async_trait)]
+  #[cfg_attr(not(feature = "send-sync"), async_trait(?Send))]
+  impl WorkflowStore for FileWorkflowStore {
+    async fn load(&self, workflow_id: &str) -> Result<Option<WorkflowState>, Error> {
+      let path = self.path(workflow_id)?;
+      let json = match std::fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(Error::Workflow(format!("failed to read {}: {e}", path.display()))),
+      };
+
+      serde_json::from_str(&json)
+        .map(Some)
+        .map_err(|e| Error::Workflow(format!("invalid workflow state in {}: {e}", path.display())))
+    }
+
+    async fn save(&self, workflow_id: &str, state: &WorkflowState) -> Result<(), Error> {
+      let path = self.path(workflow_id)?;
+      let json = serde_json::to_string_pretty(state).expect("workflow state is JSON serializable");
+      // Write to a temporary file first, so that a crash never leaves a truncated state behind.
+      let tmp_path = path.with_extension("json.tmp");
+      std::fs::write(&tmp_path, json)
+        .and_then(|_| std::fs::rename(&tmp_path, &path))
+        .map_err(|e| Error::Workflow(format!("failed to write {}: {e}", path.display())))
+    }
+  }
+}
+
+/// Where the progress of a transaction executed by a [Workflow] is recorded.
+#[derive(Debug, Clone)]
+enum Slot {
+  NewStep(String),
+  Step(usize),
+  Compensation(usize),
+}
+
+/// A resumable sequence of dependent transactions, see the [module level documentation](self).
+#[derive(Debug)]
+pub struct Workflow<St> {
+  id: String,
+  store: St,
+  state: WorkflowState,
+  visited: HashSet<String>,
+}
+
+impl<St: WorkflowStore> Workflow<St> {
+  /// Starts the workflow with the given ID, or resumes it if `store` contains its progress.
+  pub async fn start_or_resume(id: impl Into<String>, store: St) -> Result<Self, Error> {
+    let id = id.into();
+    let state = store.load(&id).await?.unwrap_or_default();
+
+    Ok(Self {
+      id,
+      store,
+      state,
+      visited: HashSet::default(),
+    })
+  }
+
+  /// Returns this workflow's ID.
+  pub fn id(&self) -> &str {
+    &self.id
+  }
+
+  /// Returns this workflow's progress.
+  pub fn state(&self) -> &WorkflowState {
+    &self.state
+  }
+
+  /// Returns the persisted output of the completed step `name`, if any.
+  pub fn output<T: DeserializeOwned>(&self, name: &str) -> Result<Option<T>, Error> {
+    let Some(StepStatus::Completed { output, .. }) = self.record(name).map(StepRecord::status) else {
+      return Ok(None);
+    };
+
+    serde_json::from_value(output.clone())
+      .map(Some)
+      .map_err(|e| Error::Workflow(format!("failed to decode the output of step \"{name}\": {e}")))
+  }
+
+  /// Runs the step `name`, executing the transaction built by `make_tx`, unless the step already completed in
+  /// a previous run, in which case its persisted output is returned.
+  /// ## Notes
+  /// `make_tx` is called even when resuming a step whose transaction was submitted in a previous run, as the
+  /// [Transaction] is needed to compute the step's output. The transaction actually executed is the one that
+  /// was persisted in the previous run, though.
+  ///
+  /// Step names must be unique within a workflow.
+  pub async fn step<Tx, C, S, F>(&mut self, name: &str, client: &C, make_tx: F) -> Result<Tx::Output, Error>
+  where
+    Tx: Transaction + OptionalSend + 'static,
+    Tx::Output: Serialize + DeserializeOwned,
+    C: CoreClient<S> + OptionalSync,
+    S: Signer<IotaKeySignature> + OptionalSync,
+    F: FnOnce() -> TransactionBuilder<Tx>,
+  {
+    if !self.visited.insert(name.to_owned()) {
+      return Err(Error::Workflow(format!(
+        "step \"{name}\" was already run by this workflow"
+      )));
+    }
+
+    let slot = if let Some(idx) = self.state.steps.iter().position(|step| step.name == name) {
+      Slot::Step(idx)
+    } else if self.state.finished {
+      return Err(Error::Workflow(format!(
+        "cannot run new step \"{name}\" on finished workflow \"{}\"",
+        self.id
+      )));
+    } else {
+      Slot::NewStep(name.to_owned())
+    };
+
+    self.run_in_slot(slot, client, make_tx).await
+  }
+
+  /// Compensates the completed step `name` by executing the transaction built by `make_tx`.
+  ///
+  /// Returns `None` - without calling `make_tx` - when step `name` didn't complete.
+  /// Like steps, compensations are persisted and are executed only once.
+  pub async fn compensate<Tx, C, S, F>(
+    &mut self,
+    name: &str,
+    client: &C,
+    make_tx: F,
+  ) -> Result<Option<Tx::Output>, Error>
+  where
+    Tx: Transaction + OptionalSend + 'static,
+    Tx::Output: Serialize + DeserializeOwned,
+    C: CoreClient<S> + OptionalSync,
+    S: Signer<IotaKeySignature> + OptionalSync,
+    F: FnOnce() -> TransactionBuilder<Tx>,
+  {
+    let Some(idx) = self
+      .state
+      .steps
+      .iter()
+      .position(|step| step.name == name && matches!(step.status, StepStatus::Completed { .. }))
+    else {
+      return Ok(None);
+    };
+
+    self
+      .run_in_slot(Slot::Compensation(idx), client, make_tx)
+      .await
+      .map(Some)
+  }
+
+  /// Marks this workflow as finished, returning its final state.
+  pub async fn finish(mut self) -> Result<WorkflowState, Error> {
+    self.state.finished = true;
+    self.store.save(&self.id, &self.state).await?;
+
+    Ok(self.state)
+  }
+
+  fn record(&self, name: &str) -> Option<&StepRecord> {
+    self.state.steps.iter().find(|step| step.name == name)
+  }
+
+  fn slot(&self, slot: &Slot) -> Option<&StepStatus> {
+    match slot {
+      Slot::NewStep(_) => None,
+      Slot::Step(idx) => Some(&self.state.steps[*idx].status),
+      Slot::Compensation(idx) => self.state.steps[*idx].compensation.as_ref(),
+    }
+  }
+
+  /// Records `status` in `slot`, returning the slot it is now recorded in.
+  fn set_slot(&mut self, slot: Slot, status: StepStatus) -> Slot {
+    match slot {
+      Slot::NewStep(name) => {
+        self.state.steps.push(StepRecord {
+          name,
+          status,
+          compensation: None,
+        });
+        Slot::Step(self.state.steps.len() - 1)
+      }
+      Slot::Step(idx) => {
+        self.state.steps[idx].status = status;
+        slot
+      }
+      Slot::Compensation(idx) => {
+        self.state.steps[idx].compensation = Some(status);
+        slot
+      }
+    }
+  }
+
+  /// Forgets about the transaction recorded in `slot`.
+  fn clear_slot(&mut self, slot: Slot) {
+    match slot {
+      Slot::NewStep(_) => (),
+      Slot::Step(idx) => {
+        self.state.steps.remove(idx);
+      }
+      Slot::Compensation(idx) => self.state.steps[idx].compensation = None,
+    }
+  }
+
+  /// Records in `slot` the outcome of its transaction `digest`, whose execution failed with `error`.
+  async fn record_failure(&mut self, slot: Slot, digest: TransactionDigest, error: &Error) -> Result<(), Error> {
+    match error {
+      // The transaction might still be executed, resuming the workflow submits it again.
+      Error::TransactionOutcomeUnknown { .. } => return Ok(()),
+      // The transaction was executed, building it anew would execute it twice.
+      Error::TransactionOffChainApplicationFailure { .. } => {
+        self.set_slot(
+          slot,
+          StepStatus::Failed {
+            digest,
+            error: error.to_string(),
+          },
+        );
+      }
+      // The transaction was either never executed - e.g. it was rejected - or executed without any effect but
+      // charging gas, thus it can be safely built anew when resuming.
+      _ => self.clear_slot(slot),
+    }
+
+    self.store.save(&self.id, &self.state).await
+  }
+
+  async fn run_in_slot<Tx, C, S, F>(&mut self, slot: Slot, client: &C, make_tx: F) -> Result<Tx::Output, Error>
+  where
+    Tx: Transaction + OptionalSend + 'static,
+    Tx::Output: Serialize + DeserializeOwned,
+    C: CoreClient<S> + OptionalSync,
+    S: Signer<IotaKeySignature> + OptionalSync,
+    F: FnOnce() -> TransactionBuilder<Tx>,
+  {
+    let pending_envelope = match self.slot(&slot) {
+      Some(StepStatus::Completed { output, .. }) => {
+        return serde_json::from_value(output.clone())
+          .map_err(|e| Error::Workflow(format!("failed to decode persisted output: {e}")));
+      }
+      Some(StepStatus::Failed { digest, error }) => {
+        return Err(Error::Workflow(format!(
+          "transaction {digest} was executed by a previous run, but its output couldn't be computed: {error}"
+        )));
+      }
+      Some(StepStatus::Pending { envelope }) => Some(envelope.clone()),
+      None => None,
+    };
+
+    let builder = make_tx();
+    let retry_policy = builder.retry_policy();
+    let hooks = builder.hook_chain(client);
+    let (slot, tx_data, signatures, tx) = if let Some(envelope) = pending_envelope {
+      let (tx_data, signatures) = envelope.into_parts();
+      (slot, tx_data, signatures, builder.into_inner())
+    } else {
+      let (tx_data, signatures, tx) = builder.build(client).await?;
+      let envelope = TransactionEnvelope::new(tx_data.clone(), signatures.clone());
+      let slot = self.set_slot(slot, StepStatus::Pending { envelope });
+      self.store.save(&self.id, &self.state).await?;
+
+      (slot, tx_data, signatures, tx)
+    };
+
+    let digest = tx_data.digest();
+    let output = match execute_and_apply(tx_data, signatures, tx, &retry_policy, &hooks, None, client).await {
+      Ok(tx_output) => tx_output.output,
+      Err(e) => {
+        self.record_failure(slot, digest, &e).await?;
+        return Err(e);
+      }
+    };
+
+    let output_json = serde_json::to_value(&output)
+      .map_err(|e| Error::Workflow(format!("failed to encode the output of transaction {digest}: {e}")))?;
+    self.set_slot(
+      slot,
+      StepStatus::Completed {
+        digest,
+        output: output_json,
+      },
+    );
+    self.store.save(&self.id, &self.state).await?;
+
+    Ok(output)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use futures::executor::block_on;
+
+  use super::*;
+  use crate::transaction::testing::{address, transaction_data, Noop, OfflineClient};
+
+  const WORKFLOW_ID: &str = "workflow";
+
+  fn digest() -> TransactionDigest {
+    TransactionDigest::new([7; 32])
+  }
+
+  fn pending() -> StepStatus {
+    StepStatus::Pending {
+      envelope: TransactionEnvelope::new(transaction_data(address(1), address(1)), vec![]),
+    }
+  }
+
+  /// Returns a store containing a workflow whose only step, "step", has the given status.
+  fn store_with_step(status: StepStatus) -> InMemoryWorkflowStore {
+    let store = InMemoryWorkflowStore::new();
+    let state = WorkflowState {
+      steps: vec![StepRecord {
+        name: "step".to_owned(),
+        status,
+        compensation: None,
+      }],
+      finished: false,
+    };
+    block_on(store.save(WORKFLOW_ID, &state)).unwrap();
+
+    store
+  }
+
+  /// Records the failure of "step" with `error`, returning the state a later run would resume from.
+  fn state_after_failure(error: Error) -> WorkflowState {
+    let mut workflow = block_on(Workflow::start_or_resume(WORKFLOW_ID, store_with_step(pending()))).unwrap();
+    block_on(workflow.record_failure(Slot::Step(0), digest(), &error)).unwrap();
+
+    block_on(workflow.store.load(WORKFLOW_ID)).unwrap().unwrap()
+  }
+
+  #[test]
+  fn completed_steps_are_not_run_again() {
+    let store = store_with_step(StepStatus::Completed {
+      digest: digest(),
+      output: serde_json::Value::Null,
+    });
+    let mut workflow = block_on(Workflow::start_or_resume(WORKFLOW_ID, store)).unwrap();
+
+    // The offline client would panic if the step's transaction was built or executed.
+    block_on(workflow.step("step", &OfflineClient::new(), || TransactionBuilder::new(Noop))).unwrap();
+  }
+
+  #[test]
+  fn pending_steps_survive_a_crash() {
+    let state = state_after_failure(Error::TransactionOutcomeUnknown {
+      digest: digest(),
+      reason: "connection reset".to_owned(),
+    });
+
+    assert_eq!(state.steps()[0].status(), &pending());
+  }
+
+  #[test]
+  fn rejected_steps_are_built_anew() {
+    let state = state_after_failure(Error::TransactionRejected {
+      digest: digest(),
+      reason: "invalid signature".to_owned(),
+    });
+
+    assert!(state.steps().is_empty());
+  }
+
+  #[test]
+  fn steps_with_failed_effects_are_built_anew() {
+    let state = state_after_failure(Error::TransactionUnexpectedResponse(
+      "errors in transaction's effects: failure".to_owned(),
+    ));
+
+    assert!(state.steps().is_empty());
+  }
+
+  #[test]
+  fn failed_steps_are_not_run_again() {
+    let store = store_with_step(StepStatus::Failed {
+      digest: digest(),
+      error: "failed to parse effects".to_owned(),
+    });
+    let mut workflow = block_on(Workflow::start_or_resume(WORKFLOW_ID, store)).unwrap();
+
+    let result = block_on(workflow.step("step", &OfflineClient::new(), || TransactionBuilder::new(Noop)));
+    assert!(matches!(result, Err(Error::Workflow(_))));
+  }
+}